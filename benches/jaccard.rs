@@ -0,0 +1,56 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use hnsm::intersect_sorted;
+use rand::{thread_rng, Rng};
+use std::collections::HashSet;
+
+/// Two overlapping sets of `size` random `u64`s, sharing about half their elements.
+fn rand_sets(size: usize) -> (HashSet<u64>, HashSet<u64>) {
+    let mut rng = thread_rng();
+    let shared: Vec<u64> = (0..size / 2).map(|_| rng.gen()).collect();
+
+    let mut a: HashSet<u64> = shared.iter().copied().collect();
+    let mut b: HashSet<u64> = shared.iter().copied().collect();
+    while a.len() < size {
+        a.insert(rng.gen());
+    }
+    while b.len() < size {
+        b.insert(rng.gen());
+    }
+
+    (a, b)
+}
+
+fn intersect_hash(a: &HashSet<u64>, b: &HashSet<u64>) -> usize {
+    a.intersection(b).count()
+}
+
+fn intersect_sort(a: &HashSet<u64>, b: &HashSet<u64>) -> usize {
+    let mut a: Vec<u64> = a.iter().copied().collect();
+    let mut b: Vec<u64> = b.iter().copied().collect();
+    a.sort_unstable();
+    b.sort_unstable();
+    intersect_sorted(&a, &b)
+}
+
+/// Benches `HashSet::intersection` against sort-then-merge across a range of
+/// sketch sizes, to find the crossover point that `--intersection-method
+/// auto` should pick around.
+pub fn bench_intersection_methods(c: &mut Criterion) {
+    let mut group = c.benchmark_group("intersection");
+
+    for size in [100usize, 1_000, 4_000, 16_000, 64_000] {
+        let (a, b) = rand_sets(size);
+
+        group.bench_with_input(BenchmarkId::new("hash", size), &size, |bencher, _| {
+            bencher.iter(|| intersect_hash(black_box(&a), black_box(&b)))
+        });
+        group.bench_with_input(BenchmarkId::new("sort", size), &size, |bencher, _| {
+            bencher.iter(|| intersect_sort(black_box(&a), black_box(&b)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_intersection_methods);
+criterion_main!(benches);
@@ -21,6 +21,19 @@ cat ~/Scripts/DAGCHAINER/data_sets/Arabidopsis/Arabidopsis.Release5.matchList.fi
 
 cargo run --bin hnsm chain ath-1-2.tsv
 
+* `--min-score` is an alias of `--mas`, for filtering chains at a threshold different
+  from the one implied by `--mna`
+
+* `--output-tsv` emits `chain_id\tacc1\tacc2\tx_pos\ty_pos\tpair_score\tpath_score`
+  with a header line, instead of the `> Alignment #N` human-readable format
+
+* `--min-size <bp>` discards a finished chain if the span between its first and last
+  x-coordinate is smaller than the threshold; `--bgs`/`--mdm` already bound how far
+  apart two matches may be to still merge into one chain, and `--mas`/`--min-score`
+  already bounds the merged chain's score, so `--min-size` is the remaining bp-span
+  filter
+
+* `--stats` prints the number of chains kept and discarded by `--min-size` to stderr
 
 "###,
         )
@@ -73,9 +86,10 @@ cargo run --bin hnsm chain ath-1-2.tsv
         .arg(
             Arg::new("mas")
                 .long("mas")
+                .visible_alias("min-score")
                 .num_args(1)
                 .value_parser(value_parser!(f32))
-                .help("Min alignment score"),
+                .help("Min alignment score, defaults to --mna * 0.5 * --mms"),
         )
         .arg(
             Arg::new("mna")
@@ -93,6 +107,31 @@ cargo run --bin hnsm chain ath-1-2.tsv
                 .default_value("stdout")
                 .help("Output filename. [stdout] for screen"),
         )
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .action(ArgAction::SetTrue)
+                .help("Print the resolved chaining options to stderr"),
+        )
+        .arg(
+            Arg::new("output_tsv")
+                .long("output-tsv")
+                .action(ArgAction::SetTrue)
+                .help("Emit a machine-readable TSV instead of the `> Alignment #N` format"),
+        )
+        .arg(
+            Arg::new("min_size")
+                .long("min-size")
+                .num_args(1)
+                .value_parser(value_parser!(i32))
+                .help("Discard chains whose x-coordinate span is smaller than this, in bp"),
+        )
+        .arg(
+            Arg::new("stats")
+                .long("stats")
+                .action(ArgAction::SetTrue)
+                .help("Report how many chains were kept and discarded by --min-size, to stderr"),
+        )
 }
 
 #[derive(Debug)]
@@ -105,6 +144,9 @@ pub struct ChainOpt {
     min_alignment_score: f32,
     reverse_order: bool,
     max_y: i32,
+    output_tsv: bool,
+    min_size: i32,
+    stats: bool,
 }
 
 // command implementation
@@ -113,6 +155,7 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     // Args
     //----------------------------
     let infile = args.get_one::<String>("infile").unwrap();
+    let opt_verbose = args.get_flag("verbose");
 
     let opt_go = *args.get_one::<f32>("go").unwrap();
     let opt_ge = *args.get_one::<f32>("ge").unwrap();
@@ -126,6 +169,9 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     } else {
         opt_mna as f32 * 0.5 * opt_mms
     };
+    let opt_output_tsv = args.get_flag("output_tsv");
+    let opt_min_size = *args.get_one::<i32>("min_size").unwrap_or(&0);
+    let opt_stats = args.get_flag("stats");
 
     let mut chain_opt = ChainOpt {
         gap_open_penalty: opt_go,
@@ -136,14 +182,22 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         min_alignment_score: opt_mas,
         reverse_order: false,
         max_y: 0,
+        output_tsv: opt_output_tsv,
+        min_size: opt_min_size,
+        stats: opt_stats,
     };
 
     //----------------------------
     // Ops
     //----------------------------
     let (acc_info, acc_pair_map, mol_pair_map) = parse_input_file(infile, &chain_opt)?;
-    // eprintln!("{:#?}", mol_pair_map);
 
+    if opt_output_tsv {
+        println!("chain_id\tacc1\tacc2\tx_pos\ty_pos\tpair_score\tpath_score");
+    }
+
+    let mut kept = 0usize;
+    let mut discarded = 0usize;
     for mol_pair in mol_pair_map.keys() {
         let mut scores = vec![];
 
@@ -167,9 +221,19 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
             chain_opt.max_y = value;
         }
         // eprintln!("scores = {:#?}", scores);
-        print_chains(&mut scores, &chain_opt);
+        let (mol_kept, mol_discarded) = print_chains(&mut scores, &chain_opt);
+        kept += mol_kept;
+        discarded += mol_discarded;
+    }
+    if opt_stats {
+        eprintln!(
+            "chain: {} chain(s) kept, {} discarded by --min-size",
+            kept, discarded
+        );
+    }
+    if opt_verbose {
+        eprintln!("chain_opt = {:#?}", chain_opt);
     }
-    eprintln!("chain_opt = {:#?}", chain_opt);
 
     Ok(())
 }
@@ -364,7 +428,10 @@ fn adjust_scores(mut scores: Vec<Score>) -> anyhow::Result<Vec<Score>> {
 }
 
 //  Find and output highest scoring chains in scores treating it as a DAG
-fn print_chains(scores: &mut Vec<Score>, options: &ChainOpt) {
+//  Returns (kept, discarded) chain counts, discarded ones being those pruned by `--min-size`
+fn print_chains(scores: &mut Vec<Score>, options: &ChainOpt) -> (usize, usize) {
+    let mut total_kept = 0usize;
+    let mut total_discarded = 0usize;
     loop {
         let mut updated = false;
 
@@ -443,8 +510,16 @@ fn print_chains(scores: &mut Vec<Score>, options: &ChainOpt) {
         for entry in high {
             if from_indices[entry.sub] != -1 {
                 let alignment_path = build_alignment_path(&from_indices, entry.sub);
+                let span =
+                    (scores[alignment_path[0]].x - scores[*alignment_path.last().unwrap()].x)
+                        .abs();
+                if options.min_size > 0 && span < options.min_size {
+                    total_discarded += 1;
+                    continue;
+                }
                 print_alignment(&scores, &path_scores, alignment_path, options, ali_ct);
                 ali_ct += 1;
+                total_kept += 1;
             }
         }
 
@@ -459,6 +534,8 @@ fn print_chains(scores: &mut Vec<Score>, options: &ChainOpt) {
             from_indices[index - 1] != -1
         });
     }
+
+    (total_kept, total_discarded)
 }
 
 fn build_alignment_path(from_indices: &Vec<i32>, start_index: usize) -> Vec<usize> {
@@ -480,26 +557,43 @@ fn print_alignment(
     options: &ChainOpt,
     alignment_count: usize,
 ) {
-    println!(
-        "> Alignment #{} score = {:.1}",
-        alignment_count + 1,
-        path_scores[*path.first().unwrap()]
-    );
+    let chain_id = alignment_count + 1;
+
+    if !options.output_tsv {
+        println!(
+            "> Alignment #{} score = {:.1}",
+            chain_id,
+            path_scores[*path.first().unwrap()]
+        );
+    }
     for &index in &path {
         let print_y = if options.reverse_order {
             options.max_y - scores[index].y + 1
         } else {
             scores[index].y
         };
-        println!(
-            "{}\t{},{}\t{}\t{}\t{:7.1}\t{:7.1}",
-            path.iter().position(|&x| x == index).unwrap(),
-            scores[index].pair_key.0,
-            scores[index].pair_key.1,
-            scores[index].x,
-            print_y,
-            scores[index].score,
-            path_scores[index],
-        );
+        if options.output_tsv {
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{:.1}\t{:.1}",
+                chain_id,
+                scores[index].pair_key.0,
+                scores[index].pair_key.1,
+                scores[index].x,
+                print_y,
+                scores[index].score,
+                path_scores[index],
+            );
+        } else {
+            println!(
+                "{}\t{},{}\t{}\t{}\t{:7.1}\t{:7.1}",
+                path.iter().position(|&x| x == index).unwrap(),
+                scores[index].pair_key.0,
+                scores[index].pair_key.1,
+                scores[index].x,
+                print_y,
+                scores[index].score,
+                path_scores[index],
+            );
+        }
     }
 }
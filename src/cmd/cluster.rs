@@ -11,11 +11,30 @@ pub fn make_subcommand() -> Command {
 modes:
     * dbscan
     * cc: ignore scores and write all connected components
+    * mcl: Markov Clustering on the pairwise scores as edge weights
 
 format:
     * cluster: a line contains points of one cluster
     * pair: lines of multiple (representative point, cluster member) pairs
 
+* With `--mode dbscan`, `--auto-eps` picks `--eps` automatically instead of using the
+  given value: for each point it finds the distance to its `--min_points`-th nearest
+  neighbor, then takes the "elbow" of the sorted k-distances (maximum-curvature
+  heuristic) as eps. The chosen value is printed to stderr
+
+* `--k-dist-plot <file>` (mode dbscan) writes the sorted k-distances (k = --min_points)
+  to a TSV, for manually picking eps from a plot
+
+* With `--mode cc`, `--dot <file>` writes the component graph in Graphviz DOT format
+  alongside (or instead of) `--graphml`; nodes are colored by cluster id and sized by
+  degree, edges are labeled with the pairwise score
+
+* With `--mode mcl`, `--sweep <list>` (e.g. `--sweep 1.4,2.0,3.0,4.0,5.0`) ignores
+  `--inflation` and instead runs MCL once per listed value, reporting
+  `inflation\tnum_clusters\tmodularity` for each to stderr (or `--sweep-out <file>`)
+  so a good inflation value can be picked without rerunning by hand. No cluster
+  output is written in this mode
+
 "###,
         )
         .arg(
@@ -31,6 +50,7 @@ format:
                 .value_parser([
                     builder::PossibleValue::new("dbscan"),
                     builder::PossibleValue::new("cc"),
+                    builder::PossibleValue::new("mcl"),
                 ])
                 .default_value("matrix")
                 .help("Clustering method"),
@@ -78,6 +98,89 @@ format:
                 .value_parser(value_parser!(usize))
                 .help("core point"),
         )
+        .arg(
+            Arg::new("auto_eps")
+                .long("auto-eps")
+                .action(ArgAction::SetTrue)
+                .help("With --mode dbscan, ignore --eps and pick it from the k-distance elbow instead (k = --min_points)"),
+        )
+        .arg(
+            Arg::new("k_dist_plot")
+                .long("k-dist-plot")
+                .num_args(1)
+                .help("With --mode dbscan, write the sorted k-distances (k = --min_points) to this file as a TSV"),
+        )
+        .arg(
+            Arg::new("weight_threshold")
+                .long("weight-threshold")
+                .num_args(1)
+                .value_parser(value_parser!(f32))
+                .help("With --mode cc, drop edges whose score is below this value before finding connected components"),
+        )
+        .arg(
+            Arg::new("graphml")
+                .long("graphml")
+                .num_args(1)
+                .help("With --mode cc, also write the component graph to this file in GraphML format"),
+        )
+        .arg(
+            Arg::new("dot")
+                .long("dot")
+                .num_args(1)
+                .help("With --mode cc, also write the component graph to this file in Graphviz DOT format"),
+        )
+        .arg(
+            Arg::new("expansion")
+                .long("expansion")
+                .num_args(1)
+                .default_value("2")
+                .value_parser(value_parser!(i32))
+                .help("With --mode mcl, the expansion power applied each round"),
+        )
+        .arg(
+            Arg::new("inflation")
+                .long("inflation")
+                .num_args(1)
+                .default_value("2.0")
+                .value_parser(value_parser!(f64))
+                .help("With --mode mcl, the inflation power applied each round; larger values yield more, smaller clusters"),
+        )
+        .arg(
+            Arg::new("max_iter")
+                .long("max-iter")
+                .num_args(1)
+                .default_value("100")
+                .value_parser(value_parser!(usize))
+                .help("With --mode mcl, the maximum number of expansion/inflation rounds"),
+        )
+        .arg(
+            Arg::new("tolerance")
+                .long("tolerance")
+                .num_args(1)
+                .default_value("1e-6")
+                .value_parser(value_parser!(f64))
+                .help("With --mode mcl, the convergence threshold between successive rounds"),
+        )
+        .arg(
+            Arg::new("prune_limit")
+                .long("prune-limit")
+                .num_args(1)
+                .default_value("0.0")
+                .value_parser(value_parser!(f64))
+                .help("With --mode mcl, zero out matrix entries below this value after each inflation step; 0.0 disables pruning"),
+        )
+        .arg(
+            Arg::new("sweep")
+                .long("sweep")
+                .num_args(1)
+                .help("With --mode mcl, a comma-separated list of inflation values to try instead of --inflation"),
+        )
+        .arg(
+            Arg::new("sweep_out")
+                .long("sweep-out")
+                .num_args(1)
+                .help("With --mode mcl --sweep, write the inflation/num_clusters/modularity TSV here instead of stderr"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -102,6 +205,20 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
 
     let opt_eps = *args.get_one::<f32>("eps").unwrap();
     let opt_min_points = *args.get_one::<usize>("min_points").unwrap();
+    let opt_auto_eps = args.get_flag("auto_eps");
+    let opt_k_dist_plot = args.get_one::<String>("k_dist_plot");
+
+    let opt_weight_threshold = args.get_one::<f32>("weight_threshold").copied();
+    let opt_graphml = args.get_one::<String>("graphml");
+    let opt_dot = args.get_one::<String>("dot");
+
+    let opt_expansion = *args.get_one::<i32>("expansion").unwrap();
+    let opt_inflation = *args.get_one::<f64>("inflation").unwrap();
+    let opt_max_iter = *args.get_one::<usize>("max_iter").unwrap();
+    let opt_tolerance = *args.get_one::<f64>("tolerance").unwrap();
+    let opt_prune_limit = *args.get_one::<f64>("prune_limit").unwrap();
+    let opt_sweep = args.get_one::<String>("sweep");
+    let opt_sweep_out = args.get_one::<String>("sweep_out");
 
     let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
 
@@ -115,7 +232,27 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         "dbscan" => {
             let matrix = hnsm::populate_matrix(&pair_scores, &index_name, opt_same, opt_missing);
 
-            let mut dbscan = hnsm::Dbscan::new(opt_eps, opt_min_points);
+            let mut eps = opt_eps;
+            if opt_auto_eps || opt_k_dist_plot.is_some() {
+                let k_dists = hnsm::k_distances(&matrix, opt_min_points);
+
+                if let Some(plot_file) = opt_k_dist_plot {
+                    let mut sorted = k_dists.clone();
+                    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                    let mut plot_writer = intspan::writer(plot_file);
+                    plot_writer.write_fmt(format_args!("idx\tk_distance\n"))?;
+                    for (i, d) in sorted.iter().enumerate() {
+                        plot_writer.write_fmt(format_args!("{}\t{}\n", i, d))?;
+                    }
+                }
+
+                if opt_auto_eps {
+                    eps = hnsm::find_elbow(&k_dists) as f32;
+                    eprintln!("==> --auto-eps selected eps = {}", eps);
+                }
+            }
+
+            let mut dbscan = hnsm::Dbscan::new(eps, opt_min_points);
             let _ = dbscan.perform_clustering(&matrix);
             match opt_format.as_str() {
                 "cluster" => {
@@ -146,16 +283,206 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         "cc" => {
             let mut graph = petgraph::prelude::UnGraphMap::new();
             // graph will borrow strings in index_name
-            for ((i, j), _) in &pair_scores {
-                graph.add_edge(index_name[*i].as_str(), index_name[*j].as_str(), ());
+            for ((i, j), score) in &pair_scores {
+                if let Some(threshold) = opt_weight_threshold {
+                    if *score < threshold {
+                        continue;
+                    }
+                }
+                graph.add_edge(index_name[*i].as_str(), index_name[*j].as_str(), *score);
+            }
+            let mut scc = petgraph::algo::tarjan_scc(&graph);
+            // Assign cluster ids stably, ordered by each component's smallest node name
+            for cc in &mut scc {
+                cc.sort_unstable();
             }
-            let scc = petgraph::algo::tarjan_scc(&graph);
+            scc.sort_by(|a, b| a[0].cmp(b[0]));
+
             for cc in &scc {
                 writer.write_fmt(format_args!("{}\n", cc.join("\t")))?;
             }
+
+            if let Some(graphml_file) = opt_graphml {
+                let cluster_id: std::collections::HashMap<&str, usize> = scc
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(id, cc)| cc.iter().map(move |&name| (name, id)))
+                    .collect();
+                write_graphml(graphml_file, &graph, &cluster_id)?;
+            }
+
+            if let Some(dot_file) = opt_dot {
+                let cluster_id: std::collections::HashMap<&str, usize> = scc
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(id, cc)| cc.iter().map(move |&name| (name, id)))
+                    .collect();
+                write_dot(dot_file, &graph, &cluster_id)?;
+            }
+        }
+        "mcl" => {
+            let matrix32 = hnsm::populate_matrix(&pair_scores, &index_name, opt_same, opt_missing);
+            let size = matrix32.size();
+            let mut matrix = ScoringMatrix::<f64>::new(size, opt_same as f64, opt_missing as f64);
+            for i in 0..size {
+                for j in i..size {
+                    matrix.set(i, j, matrix32.get(i, j) as f64);
+                }
+            }
+
+            if let Some(sweep) = opt_sweep {
+                let inflations: Vec<f64> = sweep
+                    .split(',')
+                    .map(|s| s.trim().parse::<f64>())
+                    .collect::<Result<_, _>>()?;
+
+                let mut sweep_writer = opt_sweep_out.map(|s| intspan::writer(s));
+                let header = "inflation\tnum_clusters\tmodularity\n";
+                match sweep_writer.as_mut() {
+                    Some(w) => w.write_all(header.as_ref())?,
+                    None => eprint!("{}", header),
+                }
+
+                for inflation in inflations {
+                    let mcl = hnsm::Mcl::new(opt_expansion, inflation, opt_max_iter, opt_tolerance)
+                        .with_prune_limit(opt_prune_limit);
+                    let clusters = mcl.perform_clustering(&matrix);
+                    let q = hnsm::modularity(&matrix, &clusters);
+                    let row = format!("{}\t{}\t{:.6}\n", inflation, clusters.len(), q);
+                    match sweep_writer.as_mut() {
+                        Some(w) => w.write_all(row.as_ref())?,
+                        None => eprint!("{}", row),
+                    }
+                }
+            } else {
+                let mcl = hnsm::Mcl::new(opt_expansion, opt_inflation, opt_max_iter, opt_tolerance)
+                    .with_prune_limit(opt_prune_limit);
+                let clusters = mcl.perform_clustering(&matrix);
+                match opt_format.as_str() {
+                    "cluster" => {
+                        for c in &clusters {
+                            writer.write_fmt(format_args!(
+                                "{}\n",
+                                c.iter()
+                                    .map(|&num| index_name.get(num).unwrap().to_string())
+                                    .collect::<Vec<_>>()
+                                    .join("\t")
+                            ))?;
+                        }
+                    }
+                    "pair" => {
+                        for c in &clusters {
+                            let rep = index_name.get(c[0]).unwrap();
+                            for &point in c {
+                                writer.write_fmt(format_args!(
+                                    "{}\t{}\n",
+                                    rep,
+                                    index_name.get(point).unwrap()
+                                ))?;
+                            }
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
         }
         _ => unreachable!(),
     }
 
     Ok(())
 }
+
+/// Writes the connected-components graph as GraphML, with a `cluster` attribute on
+/// each node and the pairwise score as the `weight` attribute on each edge.
+fn write_graphml(
+    outfile: &str,
+    graph: &petgraph::prelude::UnGraphMap<&str, f32>,
+    cluster_id: &std::collections::HashMap<&str, usize>,
+) -> anyhow::Result<()> {
+    let mut writer = intspan::writer(outfile);
+
+    writer.write_fmt(format_args!(
+        "{}",
+        r###"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="cluster" for="node" attr.name="cluster" attr.type="long" />
+  <key id="weight" for="edge" attr.name="weight" attr.type="double" />
+  <graph id="G" edgedefault="undirected">
+"###
+    ))?;
+
+    for node in graph.nodes() {
+        writer.write_fmt(format_args!(
+            "    <node id=\"{}\">\n      <data key=\"cluster\">{}</data>\n    </node>\n",
+            xml_escape(node),
+            cluster_id.get(node).unwrap()
+        ))?;
+    }
+
+    for (i, (a, b, score)) in graph.all_edges().enumerate() {
+        writer.write_fmt(format_args!(
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n      <data key=\"weight\">{}</data>\n    </edge>\n",
+            i,
+            xml_escape(a),
+            xml_escape(b),
+            score
+        ))?;
+    }
+
+    writer.write_fmt(format_args!("{}", "  </graph>\n</graphml>\n"))?;
+
+    Ok(())
+}
+
+/// Writes the connected-components graph as Graphviz DOT, with each node labeled by
+/// name and sized by its degree (there's no separate occurrence count for this graph),
+/// colored by its `cluster_id`, and each edge labeled with its pairwise score.
+fn write_dot(
+    outfile: &str,
+    graph: &petgraph::prelude::UnGraphMap<&str, f32>,
+    cluster_id: &std::collections::HashMap<&str, usize>,
+) -> anyhow::Result<()> {
+    let mut writer = intspan::writer(outfile);
+
+    writer.write_fmt(format_args!("{}", "graph G {\n"))?;
+
+    for node in graph.nodes() {
+        let degree = graph.neighbors(node).count();
+        let size = 0.5 + degree as f64 * 0.1;
+        writer.write_fmt(format_args!(
+            "  \"{}\" [label=\"{}\", width={:.2}, height={:.2}, style=filled, colorscheme=set19, fillcolor={}];\n",
+            dot_escape(node),
+            dot_escape(node),
+            size,
+            size,
+            cluster_id.get(node).unwrap() % 9 + 1
+        ))?;
+    }
+
+    for (a, b, score) in graph.all_edges() {
+        writer.write_fmt(format_args!(
+            "  \"{}\" -- \"{}\" [label=\"{}\"];\n",
+            dot_escape(a),
+            dot_escape(b),
+            score
+        ))?;
+    }
+
+    writer.write_fmt(format_args!("{}", "}\n"))?;
+
+    Ok(())
+}
+
+/// Escapes the characters that are not allowed verbatim inside a DOT quoted string.
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escapes the characters that are not allowed verbatim in XML attribute/text content.
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
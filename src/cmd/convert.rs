@@ -12,6 +12,12 @@ modes:
     * lower: the inputs are pairwise and output a lower-triangular matrix
     * pair: the input is a (lower-triangular) relaxed phylip distance matrix, and outputs pairwise distances
 
+* `--regex`, `--min-dist`, `--max-dist` subset the pairwise input (`matrix`/`lower`
+  modes only) before it's assembled into a matrix
+    * `--regex <pattern>` keeps a pair only when both names match; `--invert`
+      flips that to keep pairs where neither name matches
+    * `--min-dist`/`--max-dist` keep pairs whose distance falls in that range
+
 "###,
         )
         .arg(
@@ -48,6 +54,32 @@ modes:
                 .value_parser(value_parser!(f32))
                 .help("Default score of missing pairs"),
         )
+        .arg(
+            Arg::new("regex")
+                .long("regex")
+                .num_args(1)
+                .help("Keep a pair only when both names match this pattern"),
+        )
+        .arg(
+            Arg::new("invert")
+                .long("invert")
+                .action(ArgAction::SetTrue)
+                .help("With --regex, keep pairs where neither name matches instead"),
+        )
+        .arg(
+            Arg::new("min_dist")
+                .long("min-dist")
+                .num_args(1)
+                .value_parser(value_parser!(f32))
+                .help("Drop pairs whose distance is below this value"),
+        )
+        .arg(
+            Arg::new("max_dist")
+                .long("max-dist")
+                .num_args(1)
+                .value_parser(value_parser!(f32))
+                .help("Drop pairs whose distance is above this value"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -69,6 +101,14 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let opt_same = *args.get_one::<f32>("same").unwrap();
     let opt_missing = *args.get_one::<f32>("missing").unwrap();
 
+    let opt_regex = args
+        .get_one::<String>("regex")
+        .map(|s| regex::Regex::new(s))
+        .transpose()?;
+    let is_invert = args.get_flag("invert");
+    let opt_min_dist = args.get_one::<f32>("min_dist").copied();
+    let opt_max_dist = args.get_one::<f32>("max_dist").copied();
+
     let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
 
     //----------------------------
@@ -97,7 +137,13 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     }
 
     // Reading pair scores from a TSV file
-    let (pair_scores, index_name) = hnsm::load_pair_scores(infile);
+    let (pair_scores, index_name) = load_pair_scores_filtered(
+        infile,
+        opt_regex.as_ref(),
+        is_invert,
+        opt_min_dist,
+        opt_max_dist,
+    );
     let matrix = hnsm::populate_matrix(&pair_scores, &index_name, opt_same, opt_missing);
     let size = matrix.size();
 
@@ -122,6 +168,61 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Like [`hnsm::load_pair_scores`], but drops pairs before indexing them:
+/// `regex` (with `invert`) subsets by name, `min_dist`/`max_dist` subset by
+/// distance. A dropped pair's names still get an index if they also appear
+/// in a kept pair, matching how a plain name-list subset works.
+fn load_pair_scores_filtered(
+    infile: &str,
+    regex: Option<&regex::Regex>,
+    invert: bool,
+    min_dist: Option<f32>,
+    max_dist: Option<f32>,
+) -> (Vec<((usize, usize), f32)>, Vec<String>) {
+    use std::collections::HashMap;
+
+    let mut pair_scores = Vec::new();
+    let mut index_map = HashMap::new();
+    let mut index_name = vec![];
+    let mut current_index = 0usize;
+
+    let reader = intspan::reader(infile);
+    for line in reader.lines().map_while(Result::ok) {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 3 {
+            continue;
+        }
+        let n1 = fields[0].to_string();
+        let n2 = fields[1].to_string();
+        let score: f32 = fields[2].parse::<f32>().unwrap();
+
+        if let Some(re) = regex {
+            let matches = re.is_match(&n1) && re.is_match(&n2);
+            if matches == invert {
+                continue;
+            }
+        }
+        if matches!(min_dist, Some(min) if score < min) || matches!(max_dist, Some(max) if score > max) {
+            continue;
+        }
+
+        if !index_map.contains_key(&n1) {
+            index_map.insert(n1.clone(), current_index);
+            current_index += 1;
+            index_name.push(n1.clone());
+        }
+        if !index_map.contains_key(&n2) {
+            index_map.insert(n2.clone(), current_index);
+            current_index += 1;
+            index_name.push(n2.clone());
+        }
+
+        pair_scores.push(((index_map[&n1], index_map[&n2]), score));
+    }
+
+    (pair_scores, index_name)
+}
+
 // Process a single line of the PHYLIP matrix and output pairwise distances
 fn process_phylip_line(
     line: &str,
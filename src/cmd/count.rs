@@ -1,11 +1,24 @@
 use clap::*;
-use hnsm::Nt;
+use hnsm::{Nt, SeqStats};
 use noodles_fasta as fasta;
 
 // Create clap subcommand arguments
 pub fn make_subcommand() -> Command {
     Command::new("count")
         .about("Count base statistics in FA file(s)")
+        .after_help(
+            r###"
+* --gc-skew reports (G-C)/(G+C) and (A-T)/(A+T) per sequence instead of the
+  default columns
+* --window <n>, with --gc-skew, computes GC skew in non-overlapping windows
+  along each sequence and outputs `name\tpos\tgc_skew` per window
+* --cumulative, with --window, reports the running sum of the window skew
+  instead of each window's own value, for locating skew switch-points
+* --per-file, in the default mode, prints a subtotal row per input path
+  (labeled with the path) before the grand `total` row
+
+"###,
+        )
         .arg(
             Arg::new("infiles")
                 .required(true)
@@ -13,6 +26,34 @@ pub fn make_subcommand() -> Command {
                 .index(1)
                 .help("Set the input file to use"),
         )
+        .arg(
+            Arg::new("gc_skew")
+                .long("gc-skew")
+                .action(ArgAction::SetTrue)
+                .help("Report GC skew and AT skew per sequence"),
+        )
+        .arg(
+            Arg::new("window")
+                .long("window")
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .requires("gc_skew")
+                .help("With --gc-skew, compute skew in sliding windows of this size"),
+        )
+        .arg(
+            Arg::new("cumulative")
+                .long("cumulative")
+                .action(ArgAction::SetTrue)
+                .requires("window")
+                .help("With --window, report the cumulative GC skew instead of per-window"),
+        )
+        .arg(
+            Arg::new("per_file")
+                .long("per-file")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("gc_skew")
+                .help("In the default mode, also print a subtotal row per input file"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -27,8 +68,58 @@ pub fn make_subcommand() -> Command {
 pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
 
-    let mut total_len = 0usize;
-    let mut total_base_cnt = [0usize; 5];
+    if args.get_flag("gc_skew") {
+        let opt_window = args.get_one::<usize>("window").copied();
+        let is_cumulative = args.get_flag("cumulative");
+
+        if opt_window.is_some() {
+            writer.write_fmt(format_args!("#seq\tpos\tgc_skew\n"))?;
+        } else {
+            writer.write_fmt(format_args!("#seq\tgc_skew\tat_skew\n"))?;
+        }
+
+        for infile in args.get_many::<String>("infiles").unwrap() {
+            let reader = intspan::reader(infile);
+            let mut fa_in = fasta::io::Reader::new(reader);
+
+            for result in fa_in.records() {
+                let record = result?;
+                let name = String::from_utf8(record.name().into()).unwrap();
+                let bytes = record.sequence().get(..).unwrap();
+
+                if let Some(window) = opt_window {
+                    let mut cum_g = 0i64;
+                    let mut cum_c = 0i64;
+                    for (i, chunk) in bytes.chunks(window).enumerate() {
+                        let (g, c) = count_gc(chunk);
+                        let skew = if is_cumulative {
+                            cum_g += g;
+                            cum_c += c;
+                            skew_ratio(cum_g, cum_c)
+                        } else {
+                            skew_ratio(g, c)
+                        };
+                        let pos = i * window + 1;
+                        writer.write_fmt(format_args!("{}\t{}\t{:.4}\n", name, pos, skew))?;
+                    }
+                } else {
+                    let (g, c) = count_gc(bytes);
+                    let (a, t) = count_at(bytes);
+                    writer.write_fmt(format_args!(
+                        "{}\t{:.4}\t{:.4}\n",
+                        name,
+                        skew_ratio(g, c),
+                        skew_ratio(a, t),
+                    ))?;
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    let is_per_file = args.get_flag("per_file");
+    let mut total = SeqStats::new();
 
     writer.write_fmt(format_args!("#seq\tlen\tA\tC\tG\tT\tN\n"))?;
 
@@ -36,50 +127,92 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         let reader = intspan::reader(infile);
         let mut fa_in = fasta::io::Reader::new(reader);
 
+        let mut file_total = SeqStats::new();
+
         for result in fa_in.records() {
             // obtain record or fail with error
             let record = result?;
             let name = String::from_utf8(record.name().into()).unwrap();
-            let seq = record.sequence();
-
-            let mut len = 0usize;
-            let mut base_cnt = [0usize; 5];
-            for el in seq.get(..).unwrap().iter() {
-                let nt = hnsm::to_nt(*el);
-                if !matches!(nt, Nt::Invalid) {
-                    len += 1;
-                    base_cnt[nt as usize] += 1;
-                }
-            }
+
+            let mut stats = SeqStats::new();
+            stats.update(&record);
 
             writer.write_fmt(format_args!(
                 "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
                 name,
-                len,
-                base_cnt[Nt::A as usize],
-                base_cnt[Nt::C as usize],
-                base_cnt[Nt::G as usize],
-                base_cnt[Nt::T as usize],
-                base_cnt[Nt::N as usize],
+                stats.valid_len,
+                stats.base_cnt[Nt::A as usize],
+                stats.base_cnt[Nt::C as usize],
+                stats.base_cnt[Nt::G as usize],
+                stats.base_cnt[Nt::T as usize],
+                stats.base_cnt[Nt::N as usize],
             ))?;
 
-            total_len += len;
-            for &nt in &[Nt::A, Nt::C, Nt::G, Nt::T, Nt::N] {
-                total_base_cnt[nt as usize] += base_cnt[nt as usize];
-            }
+            file_total.merge(&stats);
+            total.merge(&stats);
+        }
+
+        if is_per_file {
+            writer.write_fmt(format_args!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                infile,
+                file_total.valid_len,
+                file_total.base_cnt[Nt::A as usize],
+                file_total.base_cnt[Nt::C as usize],
+                file_total.base_cnt[Nt::G as usize],
+                file_total.base_cnt[Nt::T as usize],
+                file_total.base_cnt[Nt::N as usize],
+            ))?;
         }
     }
 
     writer.write_fmt(format_args!(
         "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
         "total",
-        total_len,
-        total_base_cnt[Nt::A as usize],
-        total_base_cnt[Nt::C as usize],
-        total_base_cnt[Nt::G as usize],
-        total_base_cnt[Nt::T as usize],
-        total_base_cnt[Nt::N as usize],
+        total.valid_len,
+        total.base_cnt[Nt::A as usize],
+        total.base_cnt[Nt::C as usize],
+        total.base_cnt[Nt::G as usize],
+        total.base_cnt[Nt::T as usize],
+        total.base_cnt[Nt::N as usize],
     ))?;
 
     Ok(())
 }
+
+/// Counts G and C bases (case-insensitive), for `--gc-skew`.
+fn count_gc(seq: &[u8]) -> (i64, i64) {
+    let mut g = 0i64;
+    let mut c = 0i64;
+    for &nt in seq {
+        match nt.to_ascii_uppercase() {
+            b'G' => g += 1,
+            b'C' => c += 1,
+            _ => {}
+        }
+    }
+    (g, c)
+}
+
+/// Counts A and T/U bases (case-insensitive), for `--gc-skew`.
+fn count_at(seq: &[u8]) -> (i64, i64) {
+    let mut a = 0i64;
+    let mut t = 0i64;
+    for &nt in seq {
+        match nt.to_ascii_uppercase() {
+            b'A' => a += 1,
+            b'T' | b'U' => t += 1,
+            _ => {}
+        }
+    }
+    (a, t)
+}
+
+/// `(x - y) / (x + y)`, or `0.0` when both counts are zero.
+fn skew_ratio(x: i64, y: i64) -> f64 {
+    if x + y == 0 {
+        0.0
+    } else {
+        (x - y) as f64 / (x + y) as f64
+    }
+}
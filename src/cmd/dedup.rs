@@ -11,9 +11,11 @@ pub fn make_subcommand() -> Command {
 * The default behavior is the same as `hnsm filter -u`
 * By default, only the forward strand is compared, setting `-b` compares both strands
 * `-b` implies `-c`
+* --cluster-out writes cluster membership (representative<TAB>member per
+  duplicate) for whichever dedup mode is active; --file does the same but
+  also participates in `--seq --both`'s combined-stdout convention
 
-TODO:
-* Remove fully contained sequences
+* Removing fully contained sequences is `hnsm filter --contained`/`--rc-contained`
 
  sequence name
  | |
@@ -65,6 +67,12 @@ TODO:
                 .num_args(1)
                 .help("File to save duplicated names"),
         )
+        .arg(
+            Arg::new("cluster_out")
+                .long("cluster-out")
+                .num_args(1)
+                .help("Write cluster membership as TSV rows of `representative<TAB>member`, one per duplicate, for all dedup modes"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -147,18 +155,29 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         }
     }
 
-    if args.contains_id("file") {
-        let opt_file = args.get_one::<String>("file").unwrap();
-        let mut writer = intspan::writer(opt_file);
+    if let Some(opt_file) = args.get_one::<String>("file") {
+        write_clusters(opt_file, &subject_map)?;
+    }
 
-        for (_, v) in &subject_map {
-            if v.len() < 2 {
-                continue;
-            }
+    if let Some(opt_file) = args.get_one::<String>("cluster_out") {
+        write_clusters(opt_file, &subject_map)?;
+    }
 
-            for i in 1..v.len() {
-                writer.write_fmt(format_args!("{}\t{}\n", v[0], v[i]))?;
-            }
+    Ok(())
+}
+
+/// Writes one `representative<TAB>member` row per duplicate, where the
+/// representative is the first-seen name in each cluster.
+fn write_clusters(outfile: &str, subject_map: &HashMap<u64, Vec<String>>) -> anyhow::Result<()> {
+    let mut writer = intspan::writer(outfile);
+
+    for v in subject_map.values() {
+        if v.len() < 2 {
+            continue;
+        }
+
+        for member in &v[1..] {
+            writer.write_fmt(format_args!("{}\t{}\n", v[0], member))?;
         }
     }
 
@@ -0,0 +1,87 @@
+use clap::*;
+use noodles_fasta as fasta;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("degap")
+        .visible_alias("unalign")
+        .about("Remove gap characters from aligned/block FA file(s)")
+        .after_help(
+            r###"
+* Strips '-' (and, with --dot, '.') from each sequence to recover the ungapped
+  original, leaving headers untouched
+* This complements `filter --dash`, which removes '-' alongside other
+  transformations; `degap` does nothing else
+* Case is kept as-is by default; --upper converts to upper case afterwards
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input file(s) to use"),
+        )
+        .arg(
+            Arg::new("dot")
+                .long("dot")
+                .action(ArgAction::SetTrue)
+                .help("Also remove '.' gap characters"),
+        )
+        .arg(
+            Arg::new("upper")
+                .long("upper")
+                .action(ArgAction::SetTrue)
+                .help("Convert sequences to upper case after degapping"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    let infiles = args
+        .get_many::<String>("infiles")
+        .unwrap()
+        .collect::<Vec<_>>();
+
+    let is_dot = args.get_flag("dot");
+    let is_upper = args.get_flag("upper");
+
+    let writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+    let mut fa_out = fasta::io::writer::Builder::default()
+        .set_line_base_count(usize::MAX)
+        .build_from_writer(writer);
+
+    for infile in infiles {
+        let reader = intspan::reader(infile);
+        let mut fa_in = fasta::io::Reader::new(reader);
+
+        for result in fa_in.records() {
+            let record = result?;
+            let name = String::from_utf8(record.name().into()).unwrap();
+
+            let seq: Vec<u8> = record
+                .sequence()
+                .get(..)
+                .unwrap()
+                .iter()
+                .filter(|&&nt| nt != b'-' && !(is_dot && nt == b'.'))
+                .map(|&nt| if is_upper { nt.to_ascii_uppercase() } else { nt })
+                .collect();
+
+            let definition = fasta::record::Definition::new(&*name, None);
+            let record_out = fasta::Record::new(definition, fasta::record::Sequence::from(seq));
+            fa_out.write_record(&record_out)?;
+        }
+    }
+
+    Ok(())
+}
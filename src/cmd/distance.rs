@@ -1,8 +1,12 @@
 use clap::*;
 use hnsm::Minimizer;
 use noodles_fasta as fasta;
-use std::collections::{HashMap, HashSet};
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::io::Write;
 use std::iter::FromIterator;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 // Create clap subcommand arguments
 pub fn make_subcommand() -> Command {
@@ -23,13 +27,77 @@ pub fn make_subcommand() -> Command {
     * DNA: `-k 21 -w 5`
     * Increasing the window size speeds up processing
 
+* `--sampler syncmer` samples closed syncmers instead of minimizers: a k-mer
+  is kept when its minimal s-mer (`--syncmer-s`) sits at either end, which
+  spreads sampled positions more evenly than minimizers can (a minimizer
+  window can pick the same k-mer for several consecutive windows). `--window`
+  and `--seed-pattern` don't apply to syncmers, since there is no window to
+  scan and no seed to mask
+
+* `--seed-pattern <mask>` hashes a spaced seed instead of a contiguous k-mer:
+  a `1` at a position means it contributes to the hash, a `0` means a mismatch
+  there is ignored, which improves sensitivity at higher divergence. Its
+  length replaces `--kmer` (the minimizer window `--window` is unaffected);
+  a mask that isn't all `0`/`1` characters is an error
+
+* `--progress` reports pairs/s and ETA to stderr; `--quiet` silences it
+
+* With two <infiles>, pairs are formed across the two files instead of all-vs-all
+  within a single file
+    * `--translate` additionally six-frame translates the first file on the fly
+      (DNA query vs a protein reference), unioning minimizers per record and
+      dropping any k-mer that spans a stop codon
+
+* `--self` skips the redundant half of a single-file all-vs-all (only i <= j is
+  computed); `--chunk-size` bounds how many pairs are scheduled to rayon at once,
+  which keeps huge all-vs-all runs from materializing the full pair list at once
+
+* `--chunk C` goes further: with a single <infile>, it streams records in
+  blocks of C and computes block-vs-block, so only two blocks' worth of
+  minimizer sets are ever resident, at the cost of re-reading the file once
+  per outer block. Output rows are identical, only the iteration order changes
+
+* `--append existing.tsv` turns on incremental mode: names already appearing in
+  both columns of an existing pair are assumed already compared and are skipped,
+  so only pairs touching a new name are computed; new rows are appended to that
+  file. Minimizers aren't cached across runs, so <infiles> must still contain
+  every sequence (old and new), not just the newly added ones
+
+* `--no-self` skips a pair when the two names are equal, e.g. a record appearing
+  in both <infiles> under the same name
+* `--self-exclude`, with two <infiles>, skips pairs whose two records came from
+  the same input file; with a single <infile> every record shares one source,
+  so it is rejected there
+
+* `--intersection-method` chooses how each pair's minimizer sets are
+  intersected: `hash` uses `HashSet::intersection`, `sort` sorts both sides
+  once and merge-walks them (`intersect_sorted`), `auto` (the default) picks
+  `sort` once both sets are large enough that its better cache behavior wins
+
+* `--output-format` picks the output shape:
+    * `tsv` (default) / `csv`: the same five columns, tab- or comma-separated
+    * `json`: an array of `{name1, name2, distance, jaccard, containment}` objects
+    * `phylip`: a strict PHYLIP square distance matrix (10-char padded names),
+      for a single all-vs-all <infile> with every unordered pair present, so
+      it cannot be combined with --self, --no-self, --append, or --chunk
+  `json` and `phylip` buffer every row before writing, so they're also
+  incompatible with --append and --chunk, which stream incrementally
+
+* `phylip`-only compatibility flags:
+    * `--lower`/`--upper` write just that triangle, no diagonal, for
+      PHYLIP-family tools that reject the full square matrix
+    * `--precision <n>` sets the decimal places (default 6)
+    * `--relaxed` writes relaxed PHYLIP names (up to 255 chars, one trailing
+      space) instead of strict PHYLIP's 10-char, space-padded names
+
 "###,
         )
         .arg(
-            Arg::new("infile")
+            Arg::new("infiles")
                 .required(true)
+                .num_args(1..=2)
                 .index(1)
-                .help("Set the input file to use"),
+                .help("Set the input file(s) to use"),
         )
         .arg(
             Arg::new("hasher")
@@ -60,6 +128,43 @@ pub fn make_subcommand() -> Command {
                 .value_parser(value_parser!(usize))
                 .help("Window size"),
         )
+        .arg(
+            Arg::new("seed_pattern")
+                .long("seed-pattern")
+                .num_args(1)
+                .help("Spaced-seed mask (e.g. 111010011); only the `1` positions contribute to a k-mer's hash. Its length replaces --kmer"),
+        )
+        .arg(
+            Arg::new("sampler")
+                .long("sampler")
+                .action(ArgAction::Set)
+                .value_parser([
+                    builder::PossibleValue::new("minimizer"),
+                    builder::PossibleValue::new("syncmer"),
+                ])
+                .default_value("minimizer")
+                .help("How to sample k-mers: sliding-window minimizers, or closed syncmers"),
+        )
+        .arg(
+            Arg::new("syncmer_s")
+                .long("syncmer-s")
+                .num_args(1)
+                .default_value("5")
+                .value_parser(value_parser!(usize))
+                .help("s-mer size used to pick closed syncmers with --sampler syncmer"),
+        )
+        .arg(
+            Arg::new("intersection_method")
+                .long("intersection-method")
+                .action(ArgAction::Set)
+                .value_parser([
+                    builder::PossibleValue::new("hash"),
+                    builder::PossibleValue::new("sort"),
+                    builder::PossibleValue::new("auto"),
+                ])
+                .default_value("auto")
+                .help("How to intersect two minimizer sets: hash-set, sort-then-merge, or auto-pick by size"),
+        )
         .arg(
             Arg::new("sim")
                 .long("sim")
@@ -74,6 +179,141 @@ pub fn make_subcommand() -> Command {
                 .value_parser(value_parser!(usize))
                 .help("Number of threads"),
         )
+        .arg(
+            Arg::new("verify")
+                .long("verify")
+                .action(ArgAction::Set)
+                .value_parser([
+                    builder::PossibleValue::new("global"),
+                    builder::PossibleValue::new("local"),
+                ])
+                .help("Replace the estimated identity with a banded alignment identity"),
+        )
+        .arg(
+            Arg::new("band")
+                .long("band")
+                .num_args(1)
+                .default_value("50")
+                .value_parser(value_parser!(usize))
+                .help("Extra band width added to the estimated distance when --verify is set"),
+        )
+        .arg(
+            Arg::new("matrix")
+                .long("matrix")
+                .action(ArgAction::Set)
+                .value_parser([
+                    builder::PossibleValue::new("dna"),
+                    builder::PossibleValue::new("blosum62"),
+                ])
+                .default_value("dna")
+                .help("Substitution scheme used by --verify"),
+        )
+        .arg(
+            Arg::new("min_identity")
+                .long("min-identity")
+                .num_args(1)
+                .value_parser(value_parser!(f64))
+                .help("Drop pairs whose verified identity falls below this value"),
+        )
+        .arg(
+            Arg::new("translate")
+                .long("translate")
+                .action(ArgAction::SetTrue)
+                .help("Six-frame translate the first file before comparing to the second"),
+        )
+        .arg(
+            Arg::new("self")
+                .long("self")
+                .action(ArgAction::SetTrue)
+                .help("With a single infile, only compute each unordered pair once (skips j < i)"),
+        )
+        .arg(
+            Arg::new("chunk_size")
+                .long("chunk-size")
+                .num_args(1)
+                .default_value("1000000")
+                .value_parser(value_parser!(usize))
+                .help("Number of pairs scheduled per batch, to bound peak memory on huge runs"),
+        )
+        .arg(
+            Arg::new("chunk")
+                .long("chunk")
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .help("With a single <infile>, stream records in blocks of this many and \
+                       compute block-vs-block, holding only two blocks of minimizer sets \
+                       resident at once (trades I/O for memory on huge all-vs-all runs)"),
+        )
+        .arg(
+            Arg::new("append")
+                .long("append")
+                .num_args(1)
+                .help("Incrementally update this existing pairwise TSV instead of recomputing all-vs-all"),
+        )
+        .arg(
+            Arg::new("no_self")
+                .long("no-self")
+                .action(ArgAction::SetTrue)
+                .help("Skip a pair when the two names are equal"),
+        )
+        .arg(
+            Arg::new("self_exclude")
+                .long("self-exclude")
+                .action(ArgAction::SetTrue)
+                .help("With two <infiles>, skip pairs whose records came from the same input file"),
+        )
+        .arg(
+            Arg::new("progress")
+                .long("progress")
+                .action(ArgAction::SetTrue)
+                .help("Report processed pairs per second and ETA to stderr"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .long("quiet")
+                .action(ArgAction::SetTrue)
+                .help("Suppress all stderr output, overriding --progress"),
+        )
+        .arg(
+            Arg::new("output_format")
+                .long("output-format")
+                .action(ArgAction::Set)
+                .value_parser([
+                    builder::PossibleValue::new("tsv"),
+                    builder::PossibleValue::new("csv"),
+                    builder::PossibleValue::new("phylip"),
+                    builder::PossibleValue::new("json"),
+                ])
+                .default_value("tsv")
+                .help("Output format; `phylip` requires a single square-matrix <infile> and no --self/--no-self/--append/--chunk"),
+        )
+        .arg(
+            Arg::new("lower")
+                .long("lower")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("upper")
+                .help("With --output-format phylip, write only the lower triangle (no diagonal)"),
+        )
+        .arg(
+            Arg::new("upper")
+                .long("upper")
+                .action(ArgAction::SetTrue)
+                .help("With --output-format phylip, write only the upper triangle (no diagonal)"),
+        )
+        .arg(
+            Arg::new("precision")
+                .long("precision")
+                .num_args(1)
+                .default_value("6")
+                .value_parser(value_parser!(usize))
+                .help("With --output-format phylip, decimal places for each distance"),
+        )
+        .arg(
+            Arg::new("relaxed")
+                .long("relaxed")
+                .action(ArgAction::SetTrue)
+                .help("With --output-format phylip, write relaxed PHYLIP (names up to 255 chars, space-separated instead of column-padded to 10)"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -89,77 +329,807 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     //----------------------------
     // Args
     //----------------------------
-    let reader = intspan::reader(args.get_one::<String>("infile").unwrap());
-    let mut fa_in = fasta::io::Reader::new(reader);
+    let infiles = args
+        .get_many::<String>("infiles")
+        .unwrap()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>();
 
     let opt_hasher = args.get_one::<String>("hasher").unwrap();
-    let opt_kmer = *args.get_one::<usize>("kmer").unwrap();
+    let opt_seed_pattern = args
+        .get_one::<String>("seed_pattern")
+        .map(|s| hnsm::parse_seed_pattern(s))
+        .transpose()?;
+    // The seed pattern's length replaces --kmer as the window handed to the
+    // minimizer machinery; --window (the number of k-mers scanned for the
+    // minimum) is unaffected.
+    let opt_kmer = opt_seed_pattern
+        .as_ref()
+        .map_or(*args.get_one::<usize>("kmer").unwrap(), |p| p.len());
     let opt_window = *args.get_one::<usize>("window").unwrap();
+    let opt_sampler = args.get_one::<String>("sampler").unwrap().as_str();
+    let opt_syncmer_s = *args.get_one::<usize>("syncmer_s").unwrap();
+    if opt_sampler == "syncmer" && opt_seed_pattern.is_some() {
+        return Err(anyhow::anyhow!(
+            "--sampler syncmer is incompatible with --seed-pattern"
+        ));
+    }
+    let opt_intersection_method = args.get_one::<String>("intersection_method").unwrap();
     let is_sim = args.get_flag("sim");
+    let is_translate = args.get_flag("translate");
+
+    let opt_verify = args.get_one::<String>("verify");
+    let opt_band = *args.get_one::<usize>("band").unwrap();
+    let opt_matrix = match args.get_one::<String>("matrix").map(|s| s.as_str()) {
+        Some("blosum62") => hnsm::SubMatrix::Blosum62,
+        _ => hnsm::SubMatrix::Dna,
+    };
+    let opt_min_identity = args.get_one::<f64>("min_identity").copied();
 
-    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+    let opt_parallel = *args.get_one::<usize>("parallel").unwrap();
+    let is_progress = args.get_flag("progress");
+    let is_quiet = args.get_flag("quiet");
+
+    let is_self = args.get_flag("self");
+    let opt_chunk_size = *args.get_one::<usize>("chunk_size").unwrap();
+    let opt_record_chunk = args.get_one::<usize>("chunk").copied();
+
+    let opt_append = args.get_one::<String>("append");
+
+    let is_no_self = args.get_flag("no_self");
+    let is_self_exclude = args.get_flag("self_exclude");
+    if is_self_exclude && infiles.len() != 2 {
+        return Err(anyhow::anyhow!(
+            "--self-exclude requires exactly two <infiles>"
+        ));
+    }
+
+    let opt_output_format = args.get_one::<String>("output_format").unwrap().as_str();
+    let is_buffered_format = matches!(opt_output_format, "phylip" | "json");
+    if is_buffered_format && (opt_append.is_some() || opt_record_chunk.is_some()) {
+        return Err(anyhow::anyhow!(
+            "--output-format {opt_output_format} cannot be combined with --append or --chunk, which stream rows incrementally"
+        ));
+    }
+    let is_lower = args.get_flag("lower");
+    let is_upper = args.get_flag("upper");
+    let opt_precision = *args.get_one::<usize>("precision").unwrap();
+    let is_relaxed = args.get_flag("relaxed");
+    if opt_output_format == "phylip" {
+        if infiles.len() != 1 {
+            return Err(anyhow::anyhow!(
+                "--output-format phylip requires exactly one <infile>, to form a square all-vs-all matrix"
+            ));
+        }
+        if is_self || is_no_self {
+            return Err(anyhow::anyhow!(
+                "--output-format phylip is incompatible with --self/--no-self, which would leave the matrix non-square"
+            ));
+        }
+    } else if is_lower || is_upper || is_relaxed {
+        return Err(anyhow::anyhow!(
+            "--lower/--upper/--relaxed only apply to --output-format phylip"
+        ));
+    }
+
+    let mut writer = match opt_append {
+        Some(append_file) => Box::new(
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(append_file)?,
+        ) as Box<dyn Write>,
+        None => intspan::writer(args.get_one::<String>("outfile").unwrap()),
+    };
 
     //----------------------------
     // Ops
     //----------------------------
-    let mut set_of = HashMap::new();
-    let mut names = vec![];
+    let keep_seq = opt_verify.is_some();
 
-    for result in fa_in.records() {
-        // obtain record or fail with error
+    if let Some(block_size) = opt_record_chunk {
+        if infiles.len() == 1 && !is_translate {
+            return run_chunked(
+                infiles[0],
+                block_size,
+                opt_hasher,
+                opt_kmer,
+                opt_window,
+                opt_seed_pattern.as_deref(),
+                opt_sampler,
+                opt_syncmer_s,
+                keep_seq,
+                is_self,
+                opt_verify,
+                opt_band,
+                opt_matrix,
+                opt_min_identity,
+                opt_intersection_method,
+                is_sim,
+                opt_output_format,
+                &mut *writer,
+            );
+        }
+    }
+
+    let entries_a = if is_translate {
+        load_translated_minimizers(
+            infiles[0],
+            0,
+            opt_hasher,
+            opt_kmer,
+            opt_window,
+            opt_seed_pattern.as_deref(),
+            opt_sampler,
+            opt_syncmer_s,
+        )?
+    } else {
+        load_minimizers(
+            infiles[0],
+            0,
+            opt_hasher,
+            opt_kmer,
+            opt_window,
+            opt_seed_pattern.as_deref(),
+            opt_sampler,
+            opt_syncmer_s,
+            keep_seq,
+        )?
+    };
+
+    let entries_b = if infiles.len() == 2 {
+        load_minimizers(
+            infiles[1],
+            1,
+            opt_hasher,
+            opt_kmer,
+            opt_window,
+            opt_seed_pattern.as_deref(),
+            opt_sampler,
+            opt_syncmer_s,
+            keep_seq,
+        )?
+    } else {
+        entries_a.clone()
+    };
+
+    let is_dedup_self = is_self && infiles.len() == 1;
+    let existing_names: HashSet<String> = match opt_append {
+        Some(append_file) => load_existing_pair_names(append_file)?,
+        None => HashSet::new(),
+    };
+
+    let pairs: Vec<(&MinimizerEntry, &MinimizerEntry)> = entries_a
+        .iter()
+        .enumerate()
+        .flat_map(|(i, e1)| {
+            entries_b
+                .iter()
+                .enumerate()
+                .filter(move |(j, _)| !is_dedup_self || i <= *j)
+                .map(move |(_, e2)| (e1, e2))
+        })
+        .filter(|(e1, e2)| !is_no_self || e1.name != e2.name)
+        .filter(|(e1, e2)| !is_self_exclude || e1.source_file != e2.source_file)
+        // Already-computed pairs are those where both sides were already
+        // present in the existing pairwise TSV
+        .filter(|(e1, e2)| {
+            !(existing_names.contains(&e1.name) && existing_names.contains(&e2.name))
+        })
+        .collect();
+    let total = pairs.len() as u64;
+
+    let reporter = hnsm::ProgressReporter::spawn(
+        Some(total),
+        "pairs",
+        is_progress && !is_quiet,
+        Duration::from_millis(500),
+    );
+    let counter = reporter.counter();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(opt_parallel)
+        .build()
+        .unwrap();
+
+    // Pairs are scheduled to rayon one chunk at a time. For the streaming
+    // formats (tsv/csv), each chunk is written out before the next is
+    // computed, so peak memory is bounded by `chunk_size` rather than the
+    // full (potentially huge) pair list. `json`/`phylip` need every row at
+    // once to render their wrapper (array brackets, square matrix), so their
+    // rows are accumulated here instead.
+    let mut buffered_rows: Vec<PairRow> = vec![];
+    for chunk in pairs.chunks(opt_chunk_size.max(1)) {
+        let rows: Vec<Option<PairRow>> = pool.install(|| {
+            chunk
+                .par_iter()
+                .map(|(e1, e2)| {
+                    let row = score_pair(
+                        &e1.name,
+                        &e2.name,
+                        &e1.set,
+                        &e2.set,
+                        e1.seq.as_deref(),
+                        e2.seq.as_deref(),
+                        opt_verify,
+                        opt_band,
+                        opt_matrix,
+                        opt_min_identity,
+                        opt_intersection_method,
+                        is_sim,
+                    );
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    row
+                })
+                .collect()
+        });
+
+        for row in rows.into_iter().flatten() {
+            if is_buffered_format {
+                buffered_rows.push(row);
+            } else {
+                writer.write_all(format_row(&row, opt_output_format).as_bytes())?;
+            }
+        }
+    }
+
+    match opt_output_format {
+        "json" => write_json(&buffered_rows, &mut *writer)?,
+        "phylip" => {
+            let names: Vec<String> = entries_a.iter().map(|e| e.name.clone()).collect();
+            let triangle = if is_lower {
+                PhylipTriangle::Lower
+            } else if is_upper {
+                PhylipTriangle::Upper
+            } else {
+                PhylipTriangle::Full
+            };
+            write_phylip(
+                &names,
+                &buffered_rows,
+                triangle,
+                opt_precision,
+                is_relaxed,
+                &mut *writer,
+            )?;
+        }
+        _ => {}
+    }
+
+    reporter.finish();
+
+    Ok(())
+}
+
+/// Runs a block-vs-block all-vs-all over a single <infile>, streaming records
+/// in blocks of `block_size` from disk so only two blocks' worth of minimizer
+/// sets are ever resident. The outer block is read once; for each outer block
+/// the file is re-read from the outer block's start (or from the beginning,
+/// when `is_self` is off) to stream each inner block in turn. This trades
+/// repeated I/O for bounded memory on huge all-vs-all runs.
+#[allow(clippy::too_many_arguments)]
+fn run_chunked(
+    infile: &str,
+    block_size: usize,
+    opt_hasher: &str,
+    opt_kmer: usize,
+    opt_window: usize,
+    opt_seed_pattern: Option<&[bool]>,
+    opt_sampler: &str,
+    opt_syncmer_s: usize,
+    keep_seq: bool,
+    is_self: bool,
+    opt_verify: Option<&String>,
+    opt_band: usize,
+    opt_matrix: hnsm::SubMatrix,
+    opt_min_identity: Option<f64>,
+    opt_intersection_method: &str,
+    is_sim: bool,
+    output_format: &str,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let mut outer_start = 0usize;
+    loop {
+        let outer_block = read_fasta_block(
+            infile,
+            outer_start,
+            block_size,
+            opt_hasher,
+            opt_kmer,
+            opt_window,
+            opt_seed_pattern,
+            opt_sampler,
+            opt_syncmer_s,
+            keep_seq,
+        )?;
+        if outer_block.is_empty() {
+            break;
+        }
+
+        let mut inner_start = if is_self { outer_start } else { 0 };
+        loop {
+            let inner_block = read_fasta_block(
+                infile,
+                inner_start,
+                block_size,
+                opt_hasher,
+                opt_kmer,
+                opt_window,
+                opt_seed_pattern,
+                opt_sampler,
+                opt_syncmer_s,
+                keep_seq,
+            )?;
+            if inner_block.is_empty() {
+                break;
+            }
+
+            for (i, (n1, s1, seq1)) in outer_block.iter().enumerate() {
+                for (j, (n2, s2, seq2)) in inner_block.iter().enumerate() {
+                    // When comparing a block against itself, only compute each
+                    // unordered pair once, mirroring `--self` for the in-memory path.
+                    if is_self && inner_start == outer_start && j < i {
+                        continue;
+                    }
+                    if let Some(row) = score_pair(
+                        n1,
+                        n2,
+                        s1,
+                        s2,
+                        seq1.as_deref(),
+                        seq2.as_deref(),
+                        opt_verify,
+                        opt_band,
+                        opt_matrix,
+                        opt_min_identity,
+                        opt_intersection_method,
+                        is_sim,
+                    ) {
+                        writer.write_all(format_row(&row, output_format).as_bytes())?;
+                    }
+                }
+            }
+
+            inner_start += block_size;
+        }
+
+        outer_start += block_size;
+    }
+
+    Ok(())
+}
+
+/// Reads up to `block_size` records starting at the `skip`-th record of
+/// `infile`, computing each record's minimizer set. Used by `run_chunked` to
+/// stream both the outer and inner blocks of a block-vs-block all-vs-all.
+#[allow(clippy::too_many_arguments)]
+fn read_fasta_block(
+    infile: &str,
+    skip: usize,
+    block_size: usize,
+    opt_hasher: &str,
+    opt_kmer: usize,
+    opt_window: usize,
+    opt_seed_pattern: Option<&[bool]>,
+    opt_sampler: &str,
+    opt_syncmer_s: usize,
+    keep_seq: bool,
+) -> anyhow::Result<Vec<(String, HashSet<u64>, Option<Vec<u8>>)>> {
+    let reader = intspan::reader(infile);
+    let mut fa_in = fasta::io::Reader::new(reader);
+
+    let mut block = vec![];
+    for result in fa_in.records().skip(skip).take(block_size) {
         let record = result?;
 
         let name = String::from_utf8(record.name().into()).unwrap();
         let seq = record.sequence();
+        let set = minimizer_set(
+            &seq[..],
+            opt_hasher,
+            opt_kmer,
+            opt_window,
+            opt_seed_pattern,
+            opt_sampler,
+            opt_syncmer_s,
+        );
+        let kept_seq = if keep_seq {
+            Some(seq.as_ref().to_vec())
+        } else {
+            None
+        };
 
-        let minimizers = match opt_hasher.as_str() {
-            "fx" => hnsm::JumpingMinimizer {
-                w: opt_window,
-                k: opt_kmer,
-                hasher: hnsm::FxHash,
-            }
-            .minimizer(&seq[..]),
-            "murmur" => hnsm::JumpingMinimizer {
-                w: opt_window,
-                k: opt_kmer,
-                hasher: hnsm::MurmurHash3,
-            }
-            .minimizer(&seq[..]),
-            _ => unreachable!(),
+        block.push((name, set, kept_seq));
+    }
+
+    Ok(block)
+}
+
+/// One scored pair, kept in its raw numeric form so it can still be rendered
+/// as `--output-format tsv|csv|phylip|json`, not just the flat TSV line the
+/// old `score_pair` used to format directly.
+struct PairRow {
+    n1: String,
+    n2: String,
+    distance: f64,
+    jaccard: f64,
+    containment: f64,
+}
+
+/// Scores one pair of minimizer sets (and, with `--verify`, their sequences),
+/// returning the row's numbers, or `None` if `--min-identity` drops it.
+#[allow(clippy::too_many_arguments)]
+fn score_pair(
+    n1: &str,
+    n2: &str,
+    s1: &HashSet<u64>,
+    s2: &HashSet<u64>,
+    seq1: Option<&[u8]>,
+    seq2: Option<&[u8]>,
+    opt_verify: Option<&String>,
+    opt_band: usize,
+    opt_matrix: hnsm::SubMatrix,
+    opt_min_identity: Option<f64>,
+    opt_intersection_method: &str,
+    is_sim: bool,
+) -> Option<PairRow> {
+    let inter_count = intersection_count(s1, s2, opt_intersection_method);
+    let union_count = s1.len() + s2.len() - inter_count;
+
+    let jaccard = (inter_count as f64) / (union_count as f64);
+    let containment = (inter_count as f64) / (s1.len() as f64);
+    // https://mash.readthedocs.io/en/latest/distances.html#mash-distance-formulation
+    let mut mash: f64 = if jaccard == 0.0 {
+        1.0
+    } else {
+        ((-1.0 / 7.0f64) * ((2.0 * jaccard) / (1.0f64 + jaccard)).ln()).abs()
+    };
+
+    if let Some(mode) = opt_verify {
+        let seq1 = seq1.unwrap();
+        let seq2 = seq2.unwrap();
+        let band = (mash * seq1.len() as f64).ceil() as usize + opt_band;
+        let align_mode = match mode.as_str() {
+            "local" => hnsm::AlignMode::Local,
+            _ => hnsm::AlignMode::Global,
         };
+        let identity = hnsm::banded_identity(seq1, seq2, band, align_mode, opt_matrix);
 
-        let set: HashSet<u64> = HashSet::from_iter(minimizers.iter().map(|t| t.1));
-        names.push(name.clone());
-        set_of.insert(name, set);
+        if let Some(min_identity) = opt_min_identity {
+            if identity < min_identity {
+                return None;
+            }
+        }
+
+        mash = 1.0 - identity;
     }
-    // eprintln!("set_of = {:#?}", set_of);
 
-    for n1 in &names {
-        for n2 in &names {
-            let s1 = set_of.get(n1).unwrap();
-            let s2 = set_of.get(n2).unwrap();
-            let inter: HashSet<_> = s1.intersection(&s2).collect();
-            let union: HashSet<_> = s1.union(&s2).collect();
+    Some(PairRow {
+        n1: n1.to_string(),
+        n2: n2.to_string(),
+        distance: if is_sim { 1.0 - mash } else { mash },
+        jaccard,
+        containment,
+    })
+}
 
-            let jaccard = (inter.len() as f64) / (union.len() as f64);
-            let containment = (inter.len() as f64) / (s1.len() as f64);
-            // https://mash.readthedocs.io/en/latest/distances.html#mash-distance-formulation
-            let mash: f64 = if jaccard == 0.0 {
-                1.0
+/// Formats one [`PairRow`] as a `tsv`- or `csv`-separated line, matching the
+/// `n1, n2, mash, jaccard, containment` column order documented in `after_help`.
+fn format_row(row: &PairRow, output_format: &str) -> String {
+    let sep = if output_format == "csv" { ',' } else { '\t' };
+    format!(
+        "{}{sep}{}{sep}{:.4}{sep}{:.4}{sep}{:.4}\n",
+        row.n1, row.n2, row.distance, row.jaccard, row.containment
+    )
+}
+
+/// Writes `rows` as a JSON array of `{name1, name2, distance, jaccard,
+/// containment}` objects.
+fn write_json(rows: &[PairRow], writer: &mut dyn Write) -> anyhow::Result<()> {
+    #[derive(serde::Serialize)]
+    struct JsonRow<'a> {
+        name1: &'a str,
+        name2: &'a str,
+        distance: f64,
+        jaccard: f64,
+        containment: f64,
+    }
+
+    let json_rows: Vec<JsonRow> = rows
+        .iter()
+        .map(|r| JsonRow {
+            name1: &r.n1,
+            name2: &r.n2,
+            distance: r.distance,
+            jaccard: r.jaccard,
+            containment: r.containment,
+        })
+        .collect();
+
+    serde_json::to_writer_pretty(&mut *writer, &json_rows)?;
+    writeln!(writer)?;
+
+    Ok(())
+}
+
+/// Which part of the square matrix [`write_phylip`] emits per row; older
+/// PHYLIP-family tools expect just one triangle, without the diagonal.
+enum PhylipTriangle {
+    Full,
+    Lower,
+    Upper,
+}
+
+/// Writes `rows` as a PHYLIP distance matrix, in `names`' order. `rows` must
+/// cover every unordered pair of `names`, i.e. the caller has already
+/// rejected `--self`/`--no-self`, which would leave gaps.
+///
+/// `triangle` selects `Full` (the strict square format, compatible with
+/// MEGA/PhyML), or `Lower`/`Upper` (no diagonal, for older PHYLIP-family
+/// tools that only accept one triangle). `precision` sets the number of
+/// decimal places. `relaxed` switches from strict PHYLIP's 10-character,
+/// space-padded names to relaxed PHYLIP's names of any length up to 255
+/// characters followed by a single space.
+fn write_phylip(
+    names: &[String],
+    rows: &[PairRow],
+    triangle: PhylipTriangle,
+    precision: usize,
+    relaxed: bool,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let mut dist: std::collections::HashMap<(&str, &str), f64> = std::collections::HashMap::new();
+    for row in rows {
+        dist.insert((row.n1.as_str(), row.n2.as_str()), row.distance);
+        dist.insert((row.n2.as_str(), row.n1.as_str()), row.distance);
+    }
+
+    writeln!(writer, "{}", names.len())?;
+    for (i, name) in names.iter().enumerate() {
+        if relaxed {
+            write!(writer, "{} ", name)?;
+        } else {
+            let mut label = name.clone();
+            label.truncate(10);
+            write!(writer, "{:<10}", label)?;
+        }
+
+        for (j, other) in names.iter().enumerate() {
+            match triangle {
+                PhylipTriangle::Lower if j >= i => continue,
+                PhylipTriangle::Upper if j <= i => continue,
+                _ => {}
+            }
+
+            let d = if name == other {
+                0.0
             } else {
-                ((-1.0 / 7.0f64) * ((2.0 * jaccard) / (1.0f64 + jaccard)).ln()).abs()
+                *dist.get(&(name.as_str(), other.as_str())).ok_or_else(|| {
+                    anyhow::anyhow!("missing distance for pair `{}`-`{}`", name, other)
+                })?
             };
-
-            writer.write_fmt(format_args!(
-                "{}\t{}\t{:.4}\t{:.4}\t{:.4}\n",
-                n1,
-                n2,
-                if is_sim { 1.0 - mash } else { mash },
-                jaccard,
-                containment
-            ))?;
+            write!(writer, "  {:.precision$}", d, precision = precision)?;
         }
+        writeln!(writer)?;
     }
 
     Ok(())
 }
+
+/// Sets smaller than this (on their shorter side) intersect faster by hash
+/// lookup than by sorting; larger ones amortize the sort cost via better
+/// cache behavior during the merge walk. Used by `--intersection-method auto`.
+const AUTO_INTERSECTION_THRESHOLD: usize = 4096;
+
+/// Counts the elements two minimizer sets have in common, via the strategy
+/// named by `--intersection-method`.
+fn intersection_count(s1: &HashSet<u64>, s2: &HashSet<u64>, method: &str) -> usize {
+    let use_sort = match method {
+        "hash" => false,
+        "sort" => true,
+        _ => s1.len().min(s2.len()) >= AUTO_INTERSECTION_THRESHOLD,
+    };
+
+    if use_sort {
+        let mut a: Vec<u64> = s1.iter().copied().collect();
+        let mut b: Vec<u64> = s2.iter().copied().collect();
+        a.sort_unstable();
+        b.sort_unstable();
+        hnsm::intersect_sorted(&a, &b)
+    } else {
+        s1.intersection(s2).count()
+    }
+}
+
+/// Reads the name columns of an existing pairwise TSV, for `--append`.
+fn load_existing_pair_names(infile: &str) -> anyhow::Result<HashSet<String>> {
+    use std::io::BufRead;
+
+    let reader = intspan::reader(infile);
+    let mut names = HashSet::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let mut fields = line.split('\t');
+        if let (Some(n1), Some(n2)) = (fields.next(), fields.next()) {
+            names.insert(n1.to_string());
+            names.insert(n2.to_string());
+        }
+    }
+
+    Ok(names)
+}
+
+/// One record's minimizer set, tagged with the `<infiles>` index it came from so
+/// pairs can tell same-file comparisons apart even when two names collide across
+/// files (`--self-exclude`) or a name is compared against itself (`--no-self`).
+#[derive(Clone)]
+struct MinimizerEntry {
+    name: String,
+    source_file: usize,
+    set: HashSet<u64>,
+    seq: Option<Vec<u8>>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn minimizer_set(
+    seq: &[u8],
+    opt_hasher: &str,
+    opt_kmer: usize,
+    opt_window: usize,
+    opt_seed_pattern: Option<&[bool]>,
+    opt_sampler: &str,
+    opt_syncmer_s: usize,
+) -> HashSet<u64> {
+    if opt_sampler == "syncmer" {
+        return HashSet::from_iter(
+            hnsm::seq_syncmers(seq, opt_kmer, opt_syncmer_s)
+                .into_iter()
+                .map(|t| t.1),
+        );
+    }
+
+    let minimizers = match (opt_hasher, opt_seed_pattern) {
+        ("fx", Some(pattern)) => hnsm::JumpingMinimizer {
+            w: opt_window,
+            k: opt_kmer,
+            hasher: hnsm::FxHash,
+        }
+        .minimizer_seeded(seq, pattern),
+        ("murmur", Some(pattern)) => hnsm::JumpingMinimizer {
+            w: opt_window,
+            k: opt_kmer,
+            hasher: hnsm::MurmurHash3,
+        }
+        .minimizer_seeded(seq, pattern),
+        ("fx", None) => hnsm::JumpingMinimizer {
+            w: opt_window,
+            k: opt_kmer,
+            hasher: hnsm::FxHash,
+        }
+        .minimizer(seq),
+        ("murmur", None) => hnsm::JumpingMinimizer {
+            w: opt_window,
+            k: opt_kmer,
+            hasher: hnsm::MurmurHash3,
+        }
+        .minimizer(seq),
+        _ => unreachable!(),
+    };
+
+    HashSet::from_iter(minimizers.iter().map(|t| t.1))
+}
+
+/// Loads a FASTA file and computes a minimizer set per record.
+#[allow(clippy::too_many_arguments)]
+fn load_minimizers(
+    infile: &str,
+    source_file: usize,
+    opt_hasher: &str,
+    opt_kmer: usize,
+    opt_window: usize,
+    opt_seed_pattern: Option<&[bool]>,
+    opt_sampler: &str,
+    opt_syncmer_s: usize,
+    keep_seq: bool,
+) -> anyhow::Result<Vec<MinimizerEntry>> {
+    let reader = intspan::reader(infile);
+    let mut fa_in = fasta::io::Reader::new(reader);
+
+    let mut entries = vec![];
+
+    for result in fa_in.records() {
+        let record = result?;
+
+        let name = String::from_utf8(record.name().into()).unwrap();
+        let seq = record.sequence();
+        let set = minimizer_set(
+            &seq[..],
+            opt_hasher,
+            opt_kmer,
+            opt_window,
+            opt_seed_pattern,
+            opt_sampler,
+            opt_syncmer_s,
+        );
+        let kept_seq = if keep_seq {
+            Some(seq.as_ref().to_vec())
+        } else {
+            None
+        };
+
+        entries.push(MinimizerEntry {
+            name,
+            source_file,
+            set,
+            seq: kept_seq,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Loads a FASTA file of DNA records and, for each record, six-frame
+/// translates it on the fly and unions the minimizers of every frame's
+/// peptide. Frames are split on stop codons first, so no minimizer spans one.
+#[allow(clippy::too_many_arguments)]
+fn load_translated_minimizers(
+    infile: &str,
+    source_file: usize,
+    opt_hasher: &str,
+    opt_kmer: usize,
+    opt_window: usize,
+    opt_seed_pattern: Option<&[bool]>,
+    opt_sampler: &str,
+    opt_syncmer_s: usize,
+) -> anyhow::Result<Vec<MinimizerEntry>> {
+    let reader = intspan::reader(infile);
+    let mut fa_in = fasta::io::Reader::new(reader);
+
+    let mut entries = vec![];
+
+    for result in fa_in.records() {
+        let record = result?;
+
+        let name = String::from_utf8(record.name().into()).unwrap();
+        let seq = record.sequence();
+        let fwd: Vec<u8> = seq.as_ref().to_vec();
+        let rev: Vec<u8> = seq
+            .complement()
+            .rev()
+            .collect::<Result<Vec<u8>, _>>()
+            .unwrap();
+
+        let mut set: HashSet<u64> = HashSet::new();
+        for frame in [&fwd[0..], &fwd[1.min(fwd.len())..], &fwd[2.min(fwd.len())..]]
+            .into_iter()
+            .chain([&rev[0..], &rev[1.min(rev.len())..], &rev[2.min(rev.len())..]])
+        {
+            let peptide = hnsm::translate(frame);
+            // Stop codons translate to '*'; split on them so no minimizer
+            // straddles a stop-codon-containing k-mer.
+            for orf in peptide.split('*') {
+                if orf.len() >= opt_kmer {
+                    set.extend(minimizer_set(
+                        orf.as_bytes(),
+                        opt_hasher,
+                        opt_kmer,
+                        opt_window,
+                        opt_seed_pattern,
+                        opt_sampler,
+                        opt_syncmer_s,
+                    ));
+                }
+            }
+        }
+
+        entries.push(MinimizerEntry {
+            name,
+            source_file,
+            set,
+            seq: None,
+        });
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,142 @@
+use clap::*;
+use noodles_fasta as fasta;
+use std::io::Write;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("dust")
+        .about("Mask low-complexity regions in FA file(s)")
+        .after_help(
+            r###"
+* <infiles> are paths to fasta files, .fa.gz is supported
+    * infile == stdin means reading from STDIN
+
+* Low-complexity regions are found with a simplified symmetric DUST algorithm
+  (windowed triplet-frequency scoring); a window scores at or above `--level`
+  when it is dominated by a small number of repeated triplets
+
+* By default, masked regions are soft-masked (lower-cased) in the output
+  FASTA, which any FASTA-consuming command can use as-is. With `--ranges`,
+  masked coordinates are written as `name:start-end` lines instead
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input files to use"),
+        )
+        .arg(
+            Arg::new("level")
+                .long("level")
+                .value_parser(value_parser!(f64))
+                .num_args(1)
+                .default_value("20")
+                .help("Windows scoring at or above this level are masked"),
+        )
+        .arg(
+            Arg::new("window")
+                .long("window")
+                .value_parser(value_parser!(usize))
+                .num_args(1)
+                .default_value("64")
+                .help("The size of the sliding window"),
+        )
+        .arg(
+            Arg::new("ranges")
+                .long("ranges")
+                .action(ArgAction::SetTrue)
+                .help("Output masked coordinates as `name:start-end` lines instead of FASTA"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let level = *args.get_one::<f64>("level").unwrap();
+    let window = *args.get_one::<usize>("window").unwrap();
+    let is_ranges = args.get_flag("ranges");
+
+    let outfile = args.get_one::<String>("outfile").unwrap();
+
+    //----------------------------
+    // Ops
+    //----------------------------
+    if is_ranges {
+        let mut writer = intspan::writer(outfile);
+
+        for infile in args.get_many::<String>("infiles").unwrap() {
+            let reader = intspan::reader(infile);
+            let mut fa_in = fasta::io::Reader::new(reader);
+
+            for result in fa_in.records() {
+                // obtain record or fail with error
+                let record = result?;
+
+                let name = String::from_utf8(record.name().into()).unwrap();
+                let seq = record.sequence();
+
+                let ints = hnsm::dust_mask(seq.get(..).unwrap(), window, level);
+                for (lower, upper) in ints.spans().iter() {
+                    writer.write_all(out_line(&name, *lower, *upper).as_ref())?;
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    let mut fa_out = fasta::io::writer::Builder::default()
+        .set_line_base_count(usize::MAX)
+        .build_from_writer(intspan::writer(outfile));
+
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+        let mut fa_in = fasta::io::Reader::new(reader);
+
+        for result in fa_in.records() {
+            // obtain record or fail with error
+            let record = result?;
+
+            let name = String::from_utf8(record.name().into()).unwrap();
+            let seq = record.sequence();
+
+            let ints = hnsm::dust_mask(seq.get(..).unwrap(), window, level);
+
+            //----------------------------
+            // Output
+            //----------------------------
+            let mut seq_out = String::from_utf8(seq[..].into()).unwrap();
+            for (lower, upper) in ints.spans().iter() {
+                let offset = (lower - 1) as usize;
+                let length = (upper - lower + 1) as usize;
+
+                let str = seq_out[offset..offset + length].to_lowercase();
+                seq_out.replace_range(offset..offset + length, &str);
+            }
+
+            let definition = fasta::record::Definition::new(&*name, None);
+            let seq_out = fasta::record::Sequence::from(seq_out.as_bytes().to_vec());
+            let record_out = fasta::Record::new(definition, seq_out);
+            fa_out.write_record(&record_out)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn out_line(name: &str, lower: i32, upper: i32) -> String {
+    format!("{}:{}-{}\n", name, lower, upper)
+}
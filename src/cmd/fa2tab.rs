@@ -0,0 +1,90 @@
+use clap::*;
+use noodles_fasta as fasta;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("fa2tab")
+        .about("Convert FA file(s) to a name/sequence tab-separated table")
+        .after_help(
+            r###"
+* Default output is `name\tsequence`; --length appends a sequence-length
+  column and --hash replaces the sequence column with a checksum, useful
+  for deduplication checks on large sequences without storing them in full
+* --hash md5 uses the `md5` crate; --hash sha256 uses the `sha2` crate,
+  the same one `hnsm hv` relies on for its sketch filenames
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input file(s) to use"),
+        )
+        .arg(
+            Arg::new("length")
+                .long("length")
+                .action(ArgAction::SetTrue)
+                .help("Append a column with the sequence length"),
+        )
+        .arg(
+            Arg::new("hash")
+                .long("hash")
+                .num_args(1)
+                .value_parser([
+                    builder::PossibleValue::new("md5"),
+                    builder::PossibleValue::new("sha256"),
+                ])
+                .help("Emit a sequence checksum instead of the full sequence"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    let is_length = args.get_flag("length");
+    let opt_hash = args.get_one::<String>("hash").map(|s| s.as_str());
+
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+        let mut fa_in = fasta::io::Reader::new(reader);
+
+        for result in fa_in.records() {
+            let record = result?;
+            let name = String::from_utf8(record.name().into()).unwrap();
+            let seq = record.sequence();
+            let seq_bytes = seq.get(..).unwrap();
+
+            let column = match opt_hash {
+                Some("md5") => format!("{:x}", md5::compute(seq_bytes)),
+                Some("sha256") => {
+                    let mut hasher = Sha256::new();
+                    hasher.update(seq_bytes);
+                    format!("{:x}", hasher.finalize())
+                }
+                Some(_) => unreachable!(),
+                None => String::from_utf8_lossy(seq_bytes).into_owned(),
+            };
+
+            if is_length {
+                writer.write_fmt(format_args!("{}\t{}\t{}\n", name, column, seq_bytes.len()))?;
+            } else {
+                writer.write_fmt(format_args!("{}\t{}\n", name, column))?;
+            }
+        }
+    }
+
+    Ok(())
+}
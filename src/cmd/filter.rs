@@ -1,6 +1,8 @@
 use clap::*;
 use noodles_fasta as fasta;
-use std::collections::BTreeSet;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeSet, HashMap};
 
 // Create clap subcommand arguments
 pub fn make_subcommand() -> Command {
@@ -10,9 +12,27 @@ pub fn make_subcommand() -> Command {
             r###"
 * Not all faFilter options have been implemented
   Wildcards for names can be easily implemented with `hnsm some`
+* --min-n/--max-n/--min-n-frac/--max-n-frac filter by N count; --n-stretch drops
+  sequences containing a run of at least that many consecutive Ns
+* --min-entropy drops low-complexity/repetitive sequences by k-mer Shannon
+  entropy (bits); k is set with --entropy-k (default 2)
 * This subcommand is also a formatter
     * -l is used to set the number of bases per line
     * -b/--block is not implemented here
+* --sample reservoir-samples this many passing records instead of passing all
+  of them through; --weighted-by-length weights the reservoir by sequence
+  length (A-Res), so --sample then approximates a target base yield rather
+  than a read count; --seed makes the draw reproducible
+* --contained removes sequences that are an exact substring of another,
+  longer, retained sequence (case-insensitive), keeping the longer one;
+  --rc-contained also matches when a sequence's reverse complement is
+  contained in another. Both buffer all passing records in memory and
+  resolve containment with a Rabin-Karp rolling-hash k-mer index, so most
+  candidates are settled by a hash lookup instead of an O(n) scan against
+  every retained sequence
+* --file, with --contained/--rc-contained, saves removed-name<TAB>containing-name
+  pairs, one per removed sequence, like `dedup --file`
+* --progress reports processed records per second to stderr; --quiet silences it
 
 "###,
         )
@@ -47,6 +67,56 @@ pub fn make_subcommand() -> Command {
                 .value_parser(value_parser!(usize))
                 .help("Pass sequences with fewer than this number of Ns"),
         )
+        .arg(
+            Arg::new("min_n")
+                .long("min-n")
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .help("Pass sequences with at least this many Ns"),
+        )
+        .arg(
+            Arg::new("max_n")
+                .long("max-n")
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .help("Pass sequences with at most this many Ns"),
+        )
+        .arg(
+            Arg::new("min_n_frac")
+                .long("min-n-frac")
+                .num_args(1)
+                .value_parser(value_parser!(f64))
+                .help("Pass sequences whose fraction of Ns is at least this value"),
+        )
+        .arg(
+            Arg::new("max_n_frac")
+                .long("max-n-frac")
+                .num_args(1)
+                .value_parser(value_parser!(f64))
+                .help("Pass sequences whose fraction of Ns is at most this value"),
+        )
+        .arg(
+            Arg::new("n_stretch")
+                .long("n-stretch")
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .help("Drop sequences containing a run of at least this many consecutive Ns"),
+        )
+        .arg(
+            Arg::new("min_entropy")
+                .long("min-entropy")
+                .num_args(1)
+                .value_parser(value_parser!(f64))
+                .help("Pass sequences whose k-mer entropy is at least this value (bits)"),
+        )
+        .arg(
+            Arg::new("entropy_k")
+                .long("entropy-k")
+                .num_args(1)
+                .default_value("2")
+                .value_parser(value_parser!(usize))
+                .help("k-mer size used by --min-entropy"),
+        )
         .arg(
             Arg::new("uniq")
                 .long("uniq")
@@ -90,6 +160,60 @@ pub fn make_subcommand() -> Command {
                 .value_parser(value_parser!(usize))
                 .help("Sequence line length"),
         )
+        .arg(
+            Arg::new("contained")
+                .long("contained")
+                .action(ArgAction::SetTrue)
+                .help("Remove sequences that are an exact substring of another retained sequence"),
+        )
+        .arg(
+            Arg::new("rc_contained")
+                .long("rc-contained")
+                .action(ArgAction::SetTrue)
+                .help("Like --contained, but also matches a sequence's reverse complement"),
+        )
+        .arg(
+            Arg::new("file")
+                .long("file")
+                .short('f')
+                .num_args(1)
+                .help("With --contained/--rc-contained, save removed-name<TAB>containing-name pairs to this file"),
+        )
+        .arg(
+            Arg::new("sample")
+                .long("sample")
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .help("Reservoir-sample this many passing records"),
+        )
+        .arg(
+            Arg::new("weighted_by_length")
+                .long("weighted-by-length")
+                .action(ArgAction::SetTrue)
+                .requires("sample")
+                .help("Weight --sample's reservoir by sequence length, so --sample \
+                       approximates a target base yield instead of a read count"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .num_args(1)
+                .default_value("42")
+                .value_parser(value_parser!(u64))
+                .help("With --sample, seeds the RNG for a reproducible draw"),
+        )
+        .arg(
+            Arg::new("progress")
+                .long("progress")
+                .action(ArgAction::SetTrue)
+                .help("Report processed records per second to stderr"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .long("quiet")
+                .action(ArgAction::SetTrue)
+                .help("Suppress all stderr output, overriding --progress"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -117,6 +241,13 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     } else {
         usize::MAX
     };
+    let opt_min_n = args.get_one::<usize>("min_n").copied();
+    let opt_max_n = args.get_one::<usize>("max_n").copied();
+    let opt_min_n_frac = args.get_one::<f64>("min_n_frac").copied();
+    let opt_max_n_frac = args.get_one::<f64>("max_n_frac").copied();
+    let opt_n_stretch = args.get_one::<usize>("n_stretch").copied();
+    let opt_min_entropy = args.get_one::<f64>("min_entropy").copied();
+    let opt_entropy_k = *args.get_one::<usize>("entropy_k").unwrap();
     let opt_line = if args.contains_id("line") {
         *args.get_one::<usize>("line").unwrap()
     } else {
@@ -129,6 +260,29 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let is_dash = args.get_flag("dash");
     let is_simplify = args.get_flag("simplify");
 
+    let is_contained = args.get_flag("contained");
+    let is_rc_contained = args.get_flag("rc_contained");
+    let is_containment = is_contained || is_rc_contained;
+    let opt_file = args.get_one::<String>("file");
+
+    let opt_sample = args.get_one::<usize>("sample").copied();
+    let is_weighted = args.get_flag("weighted_by_length");
+    let opt_seed = *args.get_one::<u64>("seed").unwrap();
+    let is_progress = args.get_flag("progress");
+    let is_quiet = args.get_flag("quiet");
+    let reporter = hnsm::ProgressReporter::spawn(
+        None,
+        "records",
+        is_progress && !is_quiet,
+        std::time::Duration::from_millis(500),
+    );
+    let mut rng = StdRng::seed_from_u64(opt_seed);
+    // A-Res weighted reservoir: (key, name, raw sequence), kept sorted by key ascending
+    let mut reservoir: Vec<(f64, String, Vec<u8>)> = vec![];
+    // --contained/--rc-contained need every passing record in hand before
+    // containment can be decided, so they buffer here instead of streaming out.
+    let mut contained_buf: Vec<(String, Vec<u8>)> = vec![];
+
     let writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
     let mut fa_out = fasta::io::writer::Builder::default()
         .set_line_base_count(opt_line)
@@ -142,6 +296,7 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         for result in fa_in.records() {
             // obtain record or fail with error
             let record = result?;
+            reporter.inc(1);
 
             let mut name = String::from_utf8(record.name().into()).unwrap();
             if is_simplify {
@@ -155,13 +310,46 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
             let mut flag_pass = true;
             if opt_minsize != usize::MAX && seq.len() < opt_minsize {
                 flag_pass = false;
-            } else if opt_maxsize != usize::MAX && seq.len() > opt_maxsize {
+            }
+            if opt_maxsize != usize::MAX && seq.len() > opt_maxsize {
+                flag_pass = false;
+            }
+            if opt_maxn != usize::MAX && hnsm::count_n(seq.get(..).unwrap()) > opt_maxn {
                 flag_pass = false;
-            } else if opt_maxn != usize::MAX {
-                if hnsm::count_n(seq.get(..).unwrap()) > opt_maxn {
+            }
+            if let Some(min_n) = opt_min_n {
+                if hnsm::count_n(seq.get(..).unwrap()) < min_n {
+                    flag_pass = false;
+                }
+            }
+            if let Some(max_n) = opt_max_n {
+                if hnsm::count_n(seq.get(..).unwrap()) > max_n {
+                    flag_pass = false;
+                }
+            }
+            if let Some(min_n_frac) = opt_min_n_frac {
+                let frac = hnsm::count_n(seq.get(..).unwrap()) as f64 / seq.len() as f64;
+                if frac < min_n_frac {
+                    flag_pass = false;
+                }
+            }
+            if let Some(max_n_frac) = opt_max_n_frac {
+                let frac = hnsm::count_n(seq.get(..).unwrap()) as f64 / seq.len() as f64;
+                if frac > max_n_frac {
+                    flag_pass = false;
+                }
+            }
+            if let Some(n_stretch) = opt_n_stretch {
+                if longest_n_run(seq.get(..).unwrap()) >= n_stretch {
+                    flag_pass = false;
+                }
+            }
+            if let Some(min_entropy) = opt_min_entropy {
+                if hnsm::kmer_entropy(seq.get(..).unwrap(), opt_entropy_k) < min_entropy {
                     flag_pass = false;
                 }
-            } else if is_uniq {
+            }
+            if is_uniq {
                 // If the set did not previously contain an equal value, true is returned.
                 let seen = !set_list.insert(name.clone());
                 if seen {
@@ -173,33 +361,242 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
                 continue;
             }
 
-            // formatters
-            let mut seq_out = String::new();
-            for nt in seq.get(..).unwrap().iter() {
-                if is_dash && *nt == b'-' {
-                    continue;
-                }
-                if is_iupac {
-                    if is_upper {
-                        seq_out.push(char::from(hnsm::to_n(*nt)).to_ascii_uppercase());
-                    } else {
-                        seq_out.push(char::from(hnsm::to_n(*nt)));
-                    }
-                } else {
-                    if is_upper {
-                        seq_out.push(char::from(*nt).to_ascii_uppercase());
-                    } else {
-                        seq_out.push(char::from(*nt));
-                    }
-                }
-            } // end of each nt
+            if is_containment {
+                contained_buf.push((name, seq.get(..).unwrap().to_vec()));
+                continue;
+            }
+
+            if let Some(k) = opt_sample {
+                let weight = if is_weighted { seq.len() as f64 } else { 1.0 };
+                let key = rng.gen::<f64>().powf(1.0 / weight.max(f64::MIN_POSITIVE));
+                reservoir_insert(&mut reservoir, k, key, name, seq.get(..).unwrap().to_vec());
+                continue;
+            }
 
-            let definition = fasta::record::Definition::new(&*name, None);
-            let seq_out = fasta::record::Sequence::from(seq_out.as_bytes().to_vec());
-            let record_out = fasta::Record::new(definition, seq_out);
+            let record_out = format_record(&name, seq.get(..).unwrap(), is_dash, is_iupac, is_upper);
             fa_out.write_record(&record_out)?;
         }
     }
+    reporter.finish();
+
+    for (_, name, seq) in &reservoir {
+        let record_out = format_record(name, seq, is_dash, is_iupac, is_upper);
+        fa_out.write_record(&record_out)?;
+    }
+
+    if is_containment {
+        let containing_of = resolve_contained(&contained_buf, is_rc_contained);
+
+        let mut removed_pairs: Vec<(&str, &str)> = vec![];
+        for (i, (name, seq)) in contained_buf.iter().enumerate() {
+            match &containing_of[i] {
+                Some(container) => removed_pairs.push((name, container)),
+                None => {
+                    let record_out = format_record(name, seq, is_dash, is_iupac, is_upper);
+                    fa_out.write_record(&record_out)?;
+                }
+            }
+        }
+
+        eprintln!(
+            "==> --contained removed {} of {} sequences",
+            removed_pairs.len(),
+            contained_buf.len()
+        );
+
+        if let Some(file) = opt_file {
+            let mut file_writer = intspan::writer(file);
+            for (name, container) in &removed_pairs {
+                file_writer.write_fmt(format_args!("{}\t{}\n", name, container))?;
+            }
+        }
+    }
 
     Ok(())
 }
+
+/// For each record in `records`, decides whether it is an exact substring of
+/// another, longer, record still being kept, returning that record's name
+/// (`None` for records that are kept). `rc` also checks the reverse
+/// complement.
+///
+/// Records are visited longest-first, so a record is only ever compared
+/// against records at least as long as itself. Containment is resolved with
+/// a Rabin-Karp rolling-hash index over retained records' k-mers ([`rolling_kmer_hashes`]):
+/// a candidate's leading k-mer hash narrows the search to retained records
+/// that could plausibly contain it, so most candidates cost one hash lookup
+/// rather than a full scan against every retained record.
+fn resolve_contained(records: &[(String, Vec<u8>)], rc: bool) -> Vec<Option<String>> {
+    const K: usize = 16;
+
+    let upper: Vec<Vec<u8>> = records
+        .iter()
+        .map(|(_, seq)| seq.to_ascii_uppercase())
+        .collect();
+
+    let mut order: Vec<usize> = (0..records.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(upper[i].len()));
+
+    // Indices into `records`/`upper`, in the order they were retained.
+    let mut retained: Vec<usize> = vec![];
+    // k-mer hash -> positions into `retained`.
+    let mut kmer_index: HashMap<u64, Vec<usize>> = HashMap::new();
+    let mut containing_of: Vec<Option<String>> = vec![None; records.len()];
+
+    for i in order {
+        let seq = &upper[i];
+        let rc_seq = if rc {
+            Some(seq.iter().rev().map(|&b| hnsm::complement_nt(b)).collect::<Vec<u8>>())
+        } else {
+            None
+        };
+
+        let container = find_container(seq, &upper, &retained, &kmer_index, K).or_else(|| {
+            rc_seq
+                .as_deref()
+                .and_then(|s| find_container(s, &upper, &retained, &kmer_index, K))
+        });
+
+        match container {
+            Some(idx) => containing_of[i] = Some(records[idx].0.clone()),
+            None => {
+                index_insert(&mut kmer_index, seq, K, retained.len());
+                retained.push(i);
+            }
+        }
+    }
+
+    containing_of
+}
+
+/// Finds a retained record whose (uppercased) sequence contains `needle`,
+/// or `None`. Falls back to a direct scan for `needle` shorter than the
+/// k-mer size, since it has no k-mer to anchor a lookup on.
+fn find_container(
+    needle: &[u8],
+    upper: &[Vec<u8>],
+    retained: &[usize],
+    kmer_index: &HashMap<u64, Vec<usize>>,
+    k: usize,
+) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+    if needle.len() < k {
+        return retained
+            .iter()
+            .copied()
+            .find(|&idx| upper[idx].len() >= needle.len() && contains_subseq(&upper[idx], needle));
+    }
+
+    let anchor = rolling_kmer_hashes(needle, k)[0];
+    kmer_index.get(&anchor)?.iter().copied().find_map(|pos| {
+        let idx = retained[pos];
+        if upper[idx].len() >= needle.len() && contains_subseq(&upper[idx], needle) {
+            Some(idx)
+        } else {
+            None
+        }
+    })
+}
+
+fn contains_subseq(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+/// Indexes every k-mer of `seq` (a newly retained record at `pos` in the
+/// `retained` list) into `kmer_index`.
+fn index_insert(kmer_index: &mut HashMap<u64, Vec<usize>>, seq: &[u8], k: usize, pos: usize) {
+    for h in rolling_kmer_hashes(seq, k) {
+        kmer_index.entry(h).or_default().push(pos);
+    }
+}
+
+/// Rabin-Karp rolling hash of every k-mer in `seq`: after the first, each
+/// hash is derived in O(1) from the previous one instead of rehashed from
+/// scratch, so indexing all of a sequence's k-mers costs O(len) rather than
+/// O(len * k).
+fn rolling_kmer_hashes(seq: &[u8], k: usize) -> Vec<u64> {
+    const BASE: u64 = 131;
+    if seq.len() < k {
+        return vec![];
+    }
+
+    let base_pow_k1 = (0..k - 1).fold(1u64, |acc, _| acc.wrapping_mul(BASE));
+    let mut hashes = Vec::with_capacity(seq.len() - k + 1);
+
+    let mut h: u64 = 0;
+    for &b in &seq[..k] {
+        h = h.wrapping_mul(BASE).wrapping_add(b as u64);
+    }
+    hashes.push(h);
+
+    for i in k..seq.len() {
+        h = h.wrapping_sub((seq[i - k] as u64).wrapping_mul(base_pow_k1));
+        h = h.wrapping_mul(BASE).wrapping_add(seq[i] as u64);
+        hashes.push(h);
+    }
+
+    hashes
+}
+
+/// Builds an output record after applying `--dash`/`--iupac`/`--upper` formatting.
+fn format_record(name: &str, seq: &[u8], is_dash: bool, is_iupac: bool, is_upper: bool) -> fasta::Record {
+    let mut seq_out = String::new();
+    for nt in seq.iter() {
+        if is_dash && *nt == b'-' {
+            continue;
+        }
+        if is_iupac {
+            if is_upper {
+                seq_out.push(char::from(hnsm::to_n(*nt)).to_ascii_uppercase());
+            } else {
+                seq_out.push(char::from(hnsm::to_n(*nt)));
+            }
+        } else if is_upper {
+            seq_out.push(char::from(*nt).to_ascii_uppercase());
+        } else {
+            seq_out.push(char::from(*nt));
+        }
+    } // end of each nt
+
+    let definition = fasta::record::Definition::new(name, None);
+    let seq_out = fasta::record::Sequence::from(seq_out.as_bytes().to_vec());
+    fasta::Record::new(definition, seq_out)
+}
+
+/// Weighted (A-Res) reservoir insertion: keeps the `k` highest-key items seen so far,
+/// replacing the current minimum once the reservoir is full.
+fn reservoir_insert(reservoir: &mut Vec<(f64, String, Vec<u8>)>, k: usize, key: f64, name: String, seq: Vec<u8>) {
+    if k == 0 {
+        return;
+    }
+    if reservoir.len() < k {
+        reservoir.push((key, name, seq));
+        return;
+    }
+    let min_idx = reservoir
+        .iter()
+        .enumerate()
+        .min_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap())
+        .map(|(i, _)| i)
+        .unwrap();
+    if key > reservoir[min_idx].0 {
+        reservoir[min_idx] = (key, name, seq);
+    }
+}
+
+/// Returns the length of the longest run of consecutive N-like bases, for `--n-stretch`.
+fn longest_n_run(seq: &[u8]) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for &nt in seq {
+        if hnsm::is_n(nt) {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
@@ -0,0 +1,131 @@
+use clap::*;
+use noodles_fasta as fasta;
+use std::io::Write;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("gc")
+        .about("Sliding-window GC content and GC skew")
+        .after_help(
+            r###"
+* GC content and skew ((G-C)/(G+C)) are computed per window via a single
+  prefix-sum pass over each sequence (see `windowed_fraction` in
+  `libs/nt.rs`), not a recount for every window
+* Windows whose N-fraction exceeds --max-n-frac report NA for both columns
+* --skew adds a skew column; --cumulative adds a running sum of skew
+  (treating NA as 0), which is what origin-of-replication detection needs
+* Coordinates are 0-based, half-open, matching BedGraph
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input file(s) to use"),
+        )
+        .arg(
+            Arg::new("window")
+                .long("window")
+                .short('w')
+                .num_args(1)
+                .default_value("10000")
+                .value_parser(value_parser!(usize))
+                .help("Window size"),
+        )
+        .arg(
+            Arg::new("step")
+                .long("step")
+                .num_args(1)
+                .default_value("1000")
+                .value_parser(value_parser!(usize))
+                .help("Step size between windows"),
+        )
+        .arg(
+            Arg::new("skew")
+                .long("skew")
+                .action(ArgAction::SetTrue)
+                .help("Also report GC skew, (G-C)/(G+C)"),
+        )
+        .arg(
+            Arg::new("cumulative")
+                .long("cumulative")
+                .action(ArgAction::SetTrue)
+                .requires("skew")
+                .help("Report a running sum of GC skew, treating NA as 0"),
+        )
+        .arg(
+            Arg::new("max_n_frac")
+                .long("max-n-frac")
+                .num_args(1)
+                .default_value("0.5")
+                .value_parser(value_parser!(f64))
+                .help("Windows with more Ns than this fraction report NA"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let infiles: Vec<&String> = args.get_many::<String>("infiles").unwrap().collect();
+
+    let opt_window = *args.get_one::<usize>("window").unwrap();
+    let opt_step = *args.get_one::<usize>("step").unwrap();
+    let is_skew = args.get_flag("skew");
+    let is_cumulative = args.get_flag("cumulative");
+    let opt_max_n_frac = *args.get_one::<f64>("max_n_frac").unwrap();
+
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    //----------------------------
+    // Ops
+    //----------------------------
+    for infile in &infiles {
+        let reader = intspan::reader(infile);
+        let mut fa_in = fasta::io::Reader::new(reader);
+
+        for result in fa_in.records() {
+            let record = result?;
+            let name = String::from_utf8(record.name().into()).unwrap();
+            let seq = record.sequence();
+            let seq_bytes = seq.get(..).unwrap();
+
+            let mut cum_skew = 0.0;
+            for w in hnsm::gc_windows(seq_bytes, opt_window, opt_step, opt_max_n_frac) {
+                write!(writer, "{}\t{}\t{}", name, w.start, w.end)?;
+
+                match w.gc {
+                    Some(gc) => write!(writer, "\t{:.4}", gc)?,
+                    None => write!(writer, "\tNA")?,
+                }
+
+                if is_skew {
+                    match w.skew {
+                        Some(skew) => write!(writer, "\t{:.4}", skew)?,
+                        None => write!(writer, "\tNA")?,
+                    }
+
+                    if is_cumulative {
+                        cum_skew += w.skew.unwrap_or(0.0);
+                        write!(writer, "\t{:.4}", cum_skew)?;
+                    }
+                }
+
+                writeln!(writer)?;
+            }
+        }
+    }
+
+    Ok(())
+}
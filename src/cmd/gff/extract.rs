@@ -0,0 +1,257 @@
+use clap::*;
+use std::collections::HashMap;
+use std::{ffi, fs, path};
+
+use noodles_bgzf as bgzf;
+use noodles_core::Position;
+use noodles_fasta as fasta;
+
+use super::{parse_gff, GffRecord};
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("extract")
+        .about("Extract feature sequences (CDS, spliced transcripts) from FASTA + GFF")
+        .after_help(
+            r###"
+* <fasta> is indexed on first use via a `.loc` file, the same mechanism `hnsm range` uses
+* <gff> is a GFF3 file, .gz is supported
+
+* `--type cds|exon|gene` selects which feature types to extract; features are grouped
+  by their `Parent` attribute
+
+* `--spliced` concatenates a group's features (in ascending genomic order) into one
+  transcript sequence instead of writing one entry per feature
+
+* `--translate` (implies `--type cds --spliced`) translates the spliced CDS, honoring
+  the phase (column 8) of its 5'-most segment; a `*` before the final codon is reported
+  as a premature stop in the FASTA description
+
+* Minus-strand transcripts are reverse-complemented after splicing. Trans-spliced
+  features (a `Parent` group spanning more than one seqid) are skipped with a warning
+
+"###,
+        )
+        .arg(
+            Arg::new("fasta")
+                .required(true)
+                .index(1)
+                .help("Set the genome fasta file to use"),
+        )
+        .arg(
+            Arg::new("gff")
+                .required(true)
+                .index(2)
+                .help("Set the GFF3 file to use"),
+        )
+        .arg(
+            Arg::new("type")
+                .long("type")
+                .num_args(1)
+                .default_value("cds")
+                .help("Feature type to extract: cds, exon or gene"),
+        )
+        .arg(
+            Arg::new("spliced")
+                .long("spliced")
+                .action(ArgAction::SetTrue)
+                .help("Concatenate a group's features into one transcript sequence"),
+        )
+        .arg(
+            Arg::new("translate")
+                .long("translate")
+                .action(ArgAction::SetTrue)
+                .help("Translate the (spliced) CDS to protein"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+struct Group<'a> {
+    id: String,
+    seqid: String,
+    strand: char,
+    members: Vec<&'a GffRecord>,
+}
+
+fn build_groups<'a>(records: &'a [GffRecord], ftype: &str) -> Vec<Group<'a>> {
+    let mut order: Vec<String> = vec![];
+    let mut by_parent: HashMap<String, Vec<&GffRecord>> = HashMap::new();
+
+    for r in records {
+        if !r.ftype.eq_ignore_ascii_case(ftype) {
+            continue;
+        }
+        let parent = match r.attrs.get("Parent") {
+            Some(p) => p.clone(),
+            None => continue,
+        };
+        if !by_parent.contains_key(&parent) {
+            order.push(parent.clone());
+        }
+        by_parent.entry(parent).or_default().push(r);
+    }
+
+    let mut groups = vec![];
+    for id in order {
+        let mut members = by_parent.remove(&id).unwrap();
+        members.sort_by_key(|r| r.start);
+
+        let seqid = members[0].seqid.clone();
+        let strand = members[0].strand;
+        if members.iter().any(|r| r.seqid != seqid) {
+            eprintln!(
+                "gff extract: skipping trans-spliced feature `{}` (spans multiple seqids)",
+                id
+            );
+            continue;
+        }
+
+        groups.push(Group {
+            id,
+            seqid,
+            strand,
+            members,
+        });
+    }
+
+    groups
+}
+
+fn rev_comp(seq: &[u8]) -> Vec<u8> {
+    seq.iter().rev().map(|&nt| hnsm::complement_nt(nt)).collect()
+}
+
+fn fetch_genome_seq(
+    reader: &mut hnsm::Input,
+    loc_of: &HashMap<String, (u64, usize)>,
+    cache: &mut HashMap<String, fasta::Record>,
+    seqid: &str,
+) -> anyhow::Result<()> {
+    if !cache.contains_key(seqid) {
+        if !loc_of.contains_key(seqid) {
+            return Err(anyhow::anyhow!(
+                "seqid `{}` not found in the .loc index file",
+                seqid
+            ));
+        }
+        let record = hnsm::record_loc(reader, loc_of, seqid)?;
+        cache.insert(seqid.to_string(), record);
+    }
+    Ok(())
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let fasta_file = args.get_one::<String>("fasta").unwrap();
+    let gff_file = args.get_one::<String>("gff").unwrap();
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    let ftype = args.get_one::<String>("type").unwrap();
+    let opt_translate = args.get_flag("translate");
+    let opt_spliced = args.get_flag("spliced") || opt_translate;
+
+    //----------------------------
+    // Open the indexed fasta
+    //----------------------------
+    let is_bgzf = {
+        let path = path::Path::new(fasta_file);
+        path.extension() == Some(ffi::OsStr::new("gz"))
+    };
+
+    let loc_file = format!("{}.loc", fasta_file);
+    if !path::Path::new(&loc_file).is_file() {
+        hnsm::create_loc(fasta_file, &loc_file, is_bgzf)?;
+    }
+    let loc_of: HashMap<String, (u64, usize)> = hnsm::load_loc(&loc_file)?;
+
+    let mut reader = if is_bgzf {
+        hnsm::Input::Bgzf(
+            bgzf::indexed_reader::Builder::default()
+                .build_from_path(fasta_file)
+                .unwrap(),
+        )
+    } else {
+        hnsm::Input::File(fs::File::open(path::Path::new(fasta_file))?)
+    };
+    let mut cache: HashMap<String, fasta::Record> = HashMap::new();
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    let (records, _regions) = parse_gff(gff_file)?;
+
+    let groups = if opt_spliced {
+        build_groups(&records, ftype)
+    } else {
+        records
+            .iter()
+            .filter(|r| r.ftype.eq_ignore_ascii_case(ftype))
+            .map(|r| Group {
+                id: r
+                    .attrs
+                    .get("ID")
+                    .cloned()
+                    .unwrap_or_else(|| format!("{}:{}-{}", r.seqid, r.start, r.end)),
+                seqid: r.seqid.clone(),
+                strand: r.strand,
+                members: vec![r],
+            })
+            .collect()
+    };
+
+    for group in &groups {
+        fetch_genome_seq(&mut reader, &loc_of, &mut cache, &group.seqid)?;
+        let chrom = cache.get(&group.seqid).unwrap();
+
+        let mut seq: Vec<u8> = vec![];
+        for member in &group.members {
+            let start = Position::new(member.start as usize).unwrap();
+            let end = Position::new(member.end as usize).unwrap();
+            let slice = chrom.sequence().slice(start..=end).unwrap();
+            seq.extend_from_slice(&slice[..]);
+        }
+
+        if group.strand == '-' {
+            seq = rev_comp(&seq);
+        }
+
+        let mut description = String::new();
+        if opt_translate {
+            let phase = (if group.strand == '-' {
+                group.members.last().unwrap().phase.unwrap_or(0)
+            } else {
+                group.members.first().unwrap().phase.unwrap_or(0)
+            }) as usize;
+            let phase = phase.min(seq.len());
+
+            let protein = hnsm::translate(&seq[phase..]);
+            if let Some(idx) = protein.find('*') {
+                if idx + 1 != protein.len() {
+                    description = format!(" premature stop codon at codon {}", idx + 1);
+                }
+            }
+            writer.write_all(format!(">{}{}\n{}\n", group.id, description, protein).as_ref())?;
+        } else {
+            writer.write_all(
+                format!(
+                    ">{}\n{}\n",
+                    group.id,
+                    String::from_utf8_lossy(&seq)
+                )
+                .as_ref(),
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+//! Subcommands for operating on GFF3 annotation files.
+
+pub mod extract;
+pub mod rg;
+pub mod to_annot;
+
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use clap::*;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("gff")
+        .about("Operations on GFF3 annotation files")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(rg::make_subcommand())
+        .subcommand(extract::make_subcommand())
+        .subcommand(to_annot::make_subcommand())
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    match args.subcommand() {
+        Some(("rg", sub_matches)) => rg::execute(sub_matches),
+        Some(("extract", sub_matches)) => extract::execute(sub_matches),
+        Some(("to-annot", sub_matches)) => to_annot::execute(sub_matches),
+        _ => unreachable!(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct GffRecord {
+    pub(super) seqid: String,
+    pub(super) ftype: String,
+    pub(super) start: i64,
+    pub(super) end: i64,
+    pub(super) strand: char,
+    pub(super) phase: Option<i32>,
+    pub(super) attrs: HashMap<String, String>,
+}
+
+pub(super) fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 3 <= bytes.len() {
+            if let Ok(byte) = u8::from_str_radix(&s[i + 1..i + 3], 16) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+pub(super) fn parse_attributes(
+    field: &str,
+    line_no: usize,
+) -> anyhow::Result<HashMap<String, String>> {
+    let mut attrs = HashMap::new();
+    for pair in field.trim().split(';') {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+        let mut parts = pair.splitn(2, '=');
+        let key = parts
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("line {}: malformed attribute `{}`", line_no, pair))?;
+        let value = parts
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("line {}: malformed attribute `{}`", line_no, pair))?;
+        attrs.insert(percent_decode(key), percent_decode(value));
+    }
+    Ok(attrs)
+}
+
+pub(super) fn parse_sequence_region(line: &str) -> Option<(String, i64, i64)> {
+    let parts: Vec<&str> = line
+        .trim_start_matches("##sequence-region")
+        .split_whitespace()
+        .collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let start = parts[1].parse().ok()?;
+    let end = parts[2].parse().ok()?;
+    Some((parts[0].to_string(), start, end))
+}
+
+pub(super) fn parse_gff(
+    infile: &str,
+) -> anyhow::Result<(Vec<GffRecord>, HashMap<String, (i64, i64)>)> {
+    let reader = intspan::reader(infile);
+
+    let mut records = vec![];
+    let mut regions: HashMap<String, (i64, i64)> = HashMap::new();
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line?;
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with("##sequence-region") {
+            if let Some((seqid, start, end)) = parse_sequence_region(line) {
+                regions.insert(seqid, (start, end));
+            }
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 9 {
+            return Err(anyhow::anyhow!(
+                "line {}: expected 9 tab-separated columns, got {}",
+                line_no,
+                fields.len()
+            ));
+        }
+
+        let seqid = fields[0].to_string();
+        let ftype = fields[2].to_string();
+        let start: i64 = fields[3].parse().map_err(|_| {
+            anyhow::anyhow!("line {}: invalid start coordinate `{}`", line_no, fields[3])
+        })?;
+        let end: i64 = fields[4].parse().map_err(|_| {
+            anyhow::anyhow!("line {}: invalid end coordinate `{}`", line_no, fields[4])
+        })?;
+        let strand = fields[6].chars().next().unwrap_or('+');
+        let phase = fields[7].parse::<i32>().ok();
+        let attrs = parse_attributes(fields[8], line_no)?;
+
+        if let Some((region_start, region_end)) = regions.get(&seqid) {
+            if start < *region_start || end > *region_end {
+                return Err(anyhow::anyhow!(
+                    "line {}: {}:{}-{} falls outside ##sequence-region {}:{}-{}",
+                    line_no,
+                    seqid,
+                    start,
+                    end,
+                    seqid,
+                    region_start,
+                    region_end
+                ));
+            }
+        }
+
+        records.push(GffRecord {
+            seqid,
+            ftype,
+            start,
+            end,
+            strand,
+            phase,
+            attrs,
+        });
+    }
+
+    Ok((records, regions))
+}
+
+/// Builds a `seqid(strand):start-end` range string via `intspan::Range`, the
+/// same range type/parser `hnsm range` and `hnsm one` use, so a range emitted
+/// here round-trips through `intspan::Range::from_str` identically to one
+/// built anywhere else in the crate.
+pub(super) fn range_string(seqid: &str, strand: char, start: i64, end: i64) -> String {
+    let strand = if strand == '-' { "-" } else { "+" };
+    intspan::Range::from_full(seqid, seqid, strand, start as i32, end as i32).to_string()
+}
+
+pub(super) fn record_name(record: &GffRecord, name_attr: &str) -> String {
+    record
+        .attrs
+        .get(name_attr)
+        .cloned()
+        .unwrap_or_else(|| format!("{}:{}-{}", record.seqid, record.start, record.end))
+}
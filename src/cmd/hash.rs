@@ -0,0 +1,108 @@
+use clap::*;
+use hnsm::Minimizer;
+use noodles_fasta as fasta;
+use std::io::Write;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("hash")
+        .about("Dump per-record minimizer/syncmer hashes")
+        .after_help(
+            r###"
+* For each record, prints `name\tpos\tstrand\thash`, one line per sampled
+  k-mer, using the same minimizer/syncmer machinery as `hnsm distance`.
+  This is meant for debugging: comparing the hashes of two records side by
+  side explains why their Jaccard/distance came out the way it did
+* `pos` is the 0-based offset of the k-mer in the record. `strand` is
+  always `+`: hashes here are computed on the given strand only, with no
+  reverse-complement canonicalization
+* `--hasher`, `-k`, `-w` mirror `hnsm distance`'s options of the same name
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input file(s) to use"),
+        )
+        .arg(
+            Arg::new("hasher")
+                .long("hasher")
+                .action(ArgAction::Set)
+                .value_parser([
+                    builder::PossibleValue::new("fx"),
+                    builder::PossibleValue::new("murmur"),
+                ])
+                .default_value("fx")
+                .help("Set the hash algorithm"),
+        )
+        .arg(
+            Arg::new("kmer")
+                .long("kmer")
+                .short('k')
+                .num_args(1)
+                .default_value("7")
+                .value_parser(value_parser!(usize))
+                .help("Kmer size"),
+        )
+        .arg(
+            Arg::new("window")
+                .long("window")
+                .short('w')
+                .num_args(1)
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("Window size"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    let opt_hasher = args.get_one::<String>("hasher").unwrap();
+    let opt_kmer = *args.get_one::<usize>("kmer").unwrap();
+    let opt_window = *args.get_one::<usize>("window").unwrap();
+
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+        let mut fa_in = fasta::io::Reader::new(reader);
+
+        for result in fa_in.records() {
+            let record = result?;
+            let name = String::from_utf8(record.name().into()).unwrap();
+            let seq = record.sequence();
+
+            let minimizers = match opt_hasher.as_str() {
+                "fx" => hnsm::JumpingMinimizer {
+                    w: opt_window,
+                    k: opt_kmer,
+                    hasher: hnsm::FxHash,
+                }
+                .minimizer(&seq[..]),
+                "murmur" => hnsm::JumpingMinimizer {
+                    w: opt_window,
+                    k: opt_kmer,
+                    hasher: hnsm::MurmurHash3,
+                }
+                .minimizer(&seq[..]),
+                _ => unreachable!(),
+            };
+
+            for (pos, hash) in minimizers {
+                writeln!(writer, "{}\t{}\t+\t{}", name, pos, hash)?;
+            }
+        }
+    }
+
+    Ok(())
+}
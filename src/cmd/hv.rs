@@ -0,0 +1,379 @@
+use clap::*;
+use hnsm::Minimizer;
+use noodles_fasta as fasta;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::Path;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("hv")
+        .about("Compute and persist per-record hash vectors (minimizer sketches)")
+        .after_help(
+            r###"
+* Without --load/--list, computes a minimizer-based hash vector for each record in
+  <infiles> and prints `name\thash count` rows
+
+* --save <dir> additionally persists each hash vector: it is bincode-serialized to
+  `<dir>/<sha256(name)>.hv`, and `<dir>/hv_manifest.tsv` (name, filename, hash count)
+  is (re)written to record where each one landed
+
+* --load <dir> reads `<dir>/hv_manifest.tsv` and prints `name\thash count` for every
+  persisted entry instead of recomputing from <infiles>
+
+* --list <dir> prints `<dir>/hv_manifest.tsv` as-is, without deserializing the
+  hash vectors themselves
+
+* --dim-test <dims> benchmarks sketch quality: for each comma-separated dimension,
+  truncates every record's hash vector to its `dim` smallest hashes, computes
+  all-vs-all Mash distances from that sketch, and reports the Pearson correlation
+  against the same all-vs-all distances at dimension 4096 (as ground truth), plus
+  the wall-clock time taken. Prints a `dim\tcorr_with_4096\truntime_ms` TSV
+
+* --progress reports records/s to stderr while computing hash vectors from
+  <infiles>; --quiet silences it
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .num_args(1..)
+                .index(1)
+                .help("Set the input file(s) to use, required unless --load or --list is set"),
+        )
+        .arg(
+            Arg::new("hasher")
+                .long("hasher")
+                .action(ArgAction::Set)
+                .value_parser([
+                    builder::PossibleValue::new("fx"),
+                    builder::PossibleValue::new("murmur"),
+                ])
+                .default_value("fx")
+                .help("Set the hash algorithm"),
+        )
+        .arg(
+            Arg::new("kmer")
+                .long("kmer")
+                .short('k')
+                .num_args(1)
+                .default_value("21")
+                .value_parser(value_parser!(usize))
+                .help("Kmer size"),
+        )
+        .arg(
+            Arg::new("window")
+                .long("window")
+                .short('w')
+                .num_args(1)
+                .default_value("5")
+                .value_parser(value_parser!(usize))
+                .help("Window size"),
+        )
+        .arg(
+            Arg::new("save")
+                .long("save")
+                .num_args(1)
+                .conflicts_with_all(["load", "list"])
+                .help("Persist the computed hash vectors to this directory"),
+        )
+        .arg(
+            Arg::new("load")
+                .long("load")
+                .num_args(1)
+                .conflicts_with_all(["save", "list", "infiles"])
+                .help("Load previously saved hash vectors from this directory instead of <infiles>"),
+        )
+        .arg(
+            Arg::new("list")
+                .long("list")
+                .num_args(1)
+                .conflicts_with_all(["save", "load", "infiles"])
+                .help("Print the hv_manifest.tsv in this directory"),
+        )
+        .arg(
+            Arg::new("dim_test")
+                .long("dim-test")
+                .num_args(1)
+                .conflicts_with_all(["save", "load", "list"])
+                .help("Comma-separated sketch dimensions to benchmark against dimension 4096"),
+        )
+        .arg(
+            Arg::new("progress")
+                .long("progress")
+                .action(ArgAction::SetTrue)
+                .help("Report processed records per second to stderr"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .long("quiet")
+                .action(ArgAction::SetTrue)
+                .help("Suppress all stderr output, overriding --progress"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    let opt_hasher = args.get_one::<String>("hasher").unwrap();
+    let opt_kmer = *args.get_one::<usize>("kmer").unwrap();
+    let opt_window = *args.get_one::<usize>("window").unwrap();
+    let is_progress = args.get_flag("progress");
+    let is_quiet = args.get_flag("quiet");
+
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    if let Some(dir) = args.get_one::<String>("list") {
+        return list_manifest(dir, &mut *writer);
+    }
+
+    if let Some(dir) = args.get_one::<String>("load") {
+        let entries = load_entries(dir)?;
+        for entry in &entries {
+            writer.write_fmt(format_args!("{}\t{}\n", entry.name, entry.hashes.len()))?;
+        }
+        return Ok(());
+    }
+
+    let infiles = args
+        .get_many::<String>("infiles")
+        .ok_or_else(|| anyhow::anyhow!("<infiles> is required unless --load or --list is set"))?
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>();
+
+    let reporter = hnsm::ProgressReporter::spawn(
+        None,
+        "records",
+        is_progress && !is_quiet,
+        std::time::Duration::from_millis(500),
+    );
+
+    let mut entries = vec![];
+    for infile in &infiles {
+        entries.extend(compute_entries(
+            infile,
+            opt_hasher,
+            opt_kmer,
+            opt_window,
+            &reporter,
+        )?);
+    }
+    reporter.finish();
+
+    if let Some(dims) = args.get_one::<String>("dim_test") {
+        return run_dim_test(dims, &entries, &mut *writer);
+    }
+
+    for entry in &entries {
+        writer.write_fmt(format_args!("{}\t{}\n", entry.name, entry.hashes.len()))?;
+    }
+
+    if let Some(dir) = args.get_one::<String>("save") {
+        save_entries(dir, &entries)?;
+    }
+
+    Ok(())
+}
+
+fn record_minimizer_hashes(
+    seq: &[u8],
+    opt_hasher: &str,
+    opt_kmer: usize,
+    opt_window: usize,
+) -> Vec<u64> {
+    let minimizers = match opt_hasher {
+        "fx" => hnsm::JumpingMinimizer {
+            w: opt_window,
+            k: opt_kmer,
+            hasher: hnsm::FxHash,
+        }
+        .minimizer(seq),
+        "murmur" => hnsm::JumpingMinimizer {
+            w: opt_window,
+            k: opt_kmer,
+            hasher: hnsm::MurmurHash3,
+        }
+        .minimizer(seq),
+        _ => unreachable!(),
+    };
+
+    let mut hashes: Vec<u64> = minimizers.into_iter().map(|t| t.1).collect();
+    hashes.sort_unstable();
+    hashes.dedup();
+    hashes
+}
+
+/// Loads a FASTA file and computes a hash vector per record.
+fn compute_entries(
+    infile: &str,
+    opt_hasher: &str,
+    opt_kmer: usize,
+    opt_window: usize,
+    reporter: &hnsm::ProgressReporter,
+) -> anyhow::Result<Vec<hnsm::HvEntry>> {
+    let reader = intspan::reader(infile);
+    let mut fa_in = fasta::io::Reader::new(reader);
+
+    let mut entries = vec![];
+    for result in fa_in.records() {
+        let record = result?;
+        let name = String::from_utf8(record.name().into()).unwrap();
+        let seq = record.sequence();
+        let hashes = record_minimizer_hashes(&seq[..], opt_hasher, opt_kmer, opt_window);
+
+        entries.push(hnsm::HvEntry {
+            name,
+            hasher: opt_hasher.to_string(),
+            kmer: opt_kmer,
+            window: opt_window,
+            hashes,
+        });
+        reporter.inc(1);
+    }
+
+    Ok(entries)
+}
+
+/// Hex-encoded SHA256 of a record name, used as the persisted filename's stem.
+fn hash_name(name: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(name.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn save_entries(dir: &str, entries: &[hnsm::HvEntry]) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    let mut manifest = std::fs::File::create(Path::new(dir).join("hv_manifest.tsv"))?;
+    for entry in entries {
+        let filename = format!("{}.hv", hash_name(&entry.name));
+        std::fs::write(Path::new(dir).join(&filename), bincode::serialize(entry)?)?;
+        manifest.write_fmt(format_args!(
+            "{}\t{}\t{}\n",
+            entry.name,
+            filename,
+            entry.hashes.len()
+        ))?;
+    }
+
+    Ok(())
+}
+
+fn load_entries(dir: &str) -> anyhow::Result<Vec<hnsm::HvEntry>> {
+    let manifest = std::fs::read_to_string(Path::new(dir).join("hv_manifest.tsv"))?;
+
+    let mut entries = vec![];
+    for line in manifest.lines() {
+        let filename = line
+            .split('\t')
+            .nth(1)
+            .ok_or_else(|| anyhow::anyhow!("malformed hv_manifest.tsv line: {}", line))?;
+        let bytes = std::fs::read(Path::new(dir).join(filename))?;
+        entries.push(bincode::deserialize(&bytes)?);
+    }
+
+    Ok(entries)
+}
+
+fn list_manifest(dir: &str, writer: &mut dyn Write) -> anyhow::Result<()> {
+    let manifest = std::fs::read_to_string(Path::new(dir).join("hv_manifest.tsv"))?;
+    writer.write_all(manifest.as_bytes())?;
+    Ok(())
+}
+
+/// The dimension used as ground truth when benchmarking `--dim-test`.
+const DIM_TEST_BASELINE: usize = 4096;
+
+fn run_dim_test(
+    dims: &str,
+    entries: &[hnsm::HvEntry],
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let mut dims: Vec<usize> = dims
+        .split(',')
+        .map(|s| s.trim().parse::<usize>())
+        .collect::<Result<_, _>>()?;
+    dims.sort_unstable();
+    dims.dedup();
+
+    let baseline = pairwise_mash_distances(entries, DIM_TEST_BASELINE);
+
+    writer.write_fmt(format_args!("dim\tcorr_with_4096\truntime_ms\n"))?;
+    for dim in dims {
+        let start = std::time::Instant::now();
+        let distances = pairwise_mash_distances(entries, dim);
+        let runtime_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        let corr = pearson(&distances, &baseline);
+        writer.write_fmt(format_args!("{}\t{:.4}\t{:.2}\n", dim, corr, runtime_ms))?;
+    }
+
+    Ok(())
+}
+
+/// All-vs-all Mash distances from each entry's hash vector truncated to its
+/// `dim` smallest hashes (a bottom-k MinHash sketch), in a fixed `i < j` pair
+/// order shared across dimensions so distance vectors can be correlated.
+fn pairwise_mash_distances(entries: &[hnsm::HvEntry], dim: usize) -> Vec<f64> {
+    let sketches: Vec<std::collections::HashSet<u64>> = entries
+        .iter()
+        .map(|e| e.hashes.iter().take(dim).copied().collect())
+        .collect();
+
+    let mut distances = Vec::with_capacity(sketches.len() * sketches.len() / 2);
+    for i in 0..sketches.len() {
+        for j in (i + 1)..sketches.len() {
+            let inter = sketches[i].intersection(&sketches[j]).count();
+            let union = sketches[i].union(&sketches[j]).count();
+            let jaccard = if union == 0 {
+                0.0
+            } else {
+                inter as f64 / union as f64
+            };
+            // https://mash.readthedocs.io/en/latest/distances.html#mash-distance-formulation
+            let mash = if jaccard == 0.0 {
+                1.0
+            } else {
+                ((-1.0 / 7.0f64) * ((2.0 * jaccard) / (1.0f64 + jaccard)).ln()).abs()
+            };
+            distances.push(mash);
+        }
+    }
+    distances
+}
+
+/// Pearson correlation coefficient; 0.0 for empty, mismatched-length, or
+/// zero-variance inputs.
+fn pearson(xs: &[f64], ys: &[f64]) -> f64 {
+    if xs.is_empty() || xs.len() != ys.len() {
+        return 0.0;
+    }
+
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+
+    let mut cov = 0.0;
+    let mut var_x = 0.0;
+    let mut var_y = 0.0;
+    for (&x, &y) in xs.iter().zip(ys.iter()) {
+        let dx = x - mean_x;
+        let dy = y - mean_y;
+        cov += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+
+    if var_x == 0.0 || var_y == 0.0 {
+        0.0
+    } else {
+        cov / (var_x.sqrt() * var_y.sqrt())
+    }
+}
@@ -0,0 +1,254 @@
+use clap::*;
+use noodles_fasta as fasta;
+use std::io::Write;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("kcount")
+        .about("Approximate canonical k-mer counting with a fixed-memory counting Bloom filter")
+        .after_help(
+            r###"
+* K-mers are hashed into a fixed-size counting Bloom filter sized by `--mem`
+  (split between the counts and a dedup presence filter), so peak memory
+  never exceeds the requested budget no matter how much sequence streams
+  through - a bigger input just means more hash collisions (and so more
+  over-counting), never more memory
+* --canonical folds a k-mer and its reverse complement onto the same
+  counter, via the packed 2-bit encoder in `libs/hash.rs`
+  (`encode_kmer_2bit`/`canonical_kmer_2bit`); without it, only the observed
+  strand is counted
+* --histo prints a `count\tdistinct_kmers` abundance histogram instead of
+  per-k-mer rows
+* k-mers containing anything but A/C/G/T are skipped, since they don't pack
+  into the 2-bit encoding
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input file(s) to use"),
+        )
+        .arg(
+            Arg::new("kmer")
+                .long("kmer")
+                .short('k')
+                .num_args(1)
+                .default_value("21")
+                .value_parser(value_parser!(usize))
+                .help("Kmer size, 1-32"),
+        )
+        .arg(
+            Arg::new("canonical")
+                .long("canonical")
+                .action(ArgAction::SetTrue)
+                .help("Count a k-mer and its reverse complement together"),
+        )
+        .arg(
+            Arg::new("min_count")
+                .long("min-count")
+                .num_args(1)
+                .default_value("1")
+                .value_parser(value_parser!(u8))
+                .help("Only report k-mers with an estimated count at or above this value"),
+        )
+        .arg(
+            Arg::new("mem")
+                .long("mem")
+                .num_args(1)
+                .default_value("1G")
+                .help("Memory budget for the counting filter, e.g. 512M, 4G"),
+        )
+        .arg(
+            Arg::new("histo")
+                .long("histo")
+                .action(ArgAction::SetTrue)
+                .help("Print a count/distinct-k-mers histogram instead of per-k-mer rows"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let infiles: Vec<&String> = args.get_many::<String>("infiles").unwrap().collect();
+
+    let opt_kmer = *args.get_one::<usize>("kmer").unwrap();
+    if opt_kmer == 0 || opt_kmer > 32 {
+        return Err(anyhow::anyhow!(
+            "--kmer must be between 1 and 32, got {opt_kmer}"
+        ));
+    }
+    let is_canonical = args.get_flag("canonical");
+    let opt_min_count = *args.get_one::<u8>("min_count").unwrap();
+    let mem_bytes = parse_mem(args.get_one::<String>("mem").unwrap())?;
+    let is_histo = args.get_flag("histo");
+
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    //----------------------------
+    // Ops
+    //----------------------------
+    // Split the budget between the counts themselves and the presence
+    // filter that dedupes a k-mer's many occurrences down to one report;
+    // together they never exceed --mem regardless of how much data streams
+    // through.
+    let mut counting = CountingFilter::new((mem_bytes / 2).max(1));
+    let mut seen = PresenceFilter::new((mem_bytes / 2).max(1) * 8);
+
+    for infile in &infiles {
+        let reader = intspan::reader(infile);
+        let mut fa_in = fasta::io::Reader::new(reader);
+        for result in fa_in.records() {
+            let record = result?;
+            let seq = record.sequence();
+            let seq_bytes = seq.get(..).unwrap();
+            for code in kmer_codes(seq_bytes, opt_kmer, is_canonical) {
+                counting.insert(code);
+            }
+        }
+    }
+
+    let mut histo: std::collections::BTreeMap<u8, usize> = std::collections::BTreeMap::new();
+    for infile in &infiles {
+        let reader = intspan::reader(infile);
+        let mut fa_in = fasta::io::Reader::new(reader);
+        for result in fa_in.records() {
+            let record = result?;
+            let seq = record.sequence();
+            let seq_bytes = seq.get(..).unwrap();
+            for code in kmer_codes(seq_bytes, opt_kmer, is_canonical) {
+                if seen.check_and_set(code) {
+                    continue;
+                }
+                let count = counting.estimate(code);
+                if is_histo {
+                    *histo.entry(count).or_insert(0) += 1;
+                } else if count >= opt_min_count {
+                    let kmer = hnsm::decode_kmer_2bit(code, opt_kmer);
+                    writer.write_fmt(format_args!("{}\t{}\n", kmer, count))?;
+                }
+            }
+        }
+    }
+
+    if is_histo {
+        for (count, distinct) in &histo {
+            writer.write_fmt(format_args!("{}\t{}\n", count, distinct))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn kmer_codes(seq: &[u8], k: usize, canonical: bool) -> impl Iterator<Item = u64> + '_ {
+    seq.windows(k).filter_map(move |w| {
+        if canonical {
+            hnsm::canonical_kmer_2bit(w)
+        } else {
+            hnsm::encode_kmer_2bit(w)
+        }
+    })
+}
+
+/// A fixed-size counting Bloom filter: `NUM_HASHES` independent slots per
+/// key, each saturating at `u8::MAX`. The reported count is the minimum
+/// across those slots, the classic counting-Bloom-filter estimator - a
+/// collision can only ever inflate a count, never deflate one.
+struct CountingFilter {
+    counters: Vec<u8>,
+}
+
+const NUM_HASHES: usize = 4;
+
+impl CountingFilter {
+    fn new(num_counters: usize) -> Self {
+        Self {
+            counters: vec![0u8; num_counters],
+        }
+    }
+
+    fn slot(&self, code: u64, i: usize) -> usize {
+        (fxhash::hash64(&(code, i as u64)) as usize) % self.counters.len()
+    }
+
+    fn insert(&mut self, code: u64) {
+        for i in 0..NUM_HASHES {
+            let idx = self.slot(code, i);
+            if self.counters[idx] < u8::MAX {
+                self.counters[idx] += 1;
+            }
+        }
+    }
+
+    fn estimate(&self, code: u64) -> u8 {
+        (0..NUM_HASHES)
+            .map(|i| self.counters[self.slot(code, i)])
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+/// A fixed-size bit array used purely to dedupe: `check_and_set` reports
+/// whether `code` (or a hash collision with it) was already seen, then marks
+/// it seen. False positives only ever suppress a report early, never
+/// fabricate one, matching the filter's approximate, memory-first design.
+struct PresenceFilter {
+    bits: Vec<u64>,
+    num_bits: usize,
+}
+
+impl PresenceFilter {
+    fn new(num_bits: usize) -> Self {
+        let num_bits = num_bits.max(1);
+        Self {
+            bits: vec![0u64; num_bits.div_ceil(64)],
+            num_bits,
+        }
+    }
+
+    fn check_and_set(&mut self, code: u64) -> bool {
+        let idx = (fxhash::hash64(&code) as usize) % self.num_bits;
+        let word = idx / 64;
+        let bit = idx % 64;
+        let mask = 1u64 << bit;
+        let was_set = self.bits[word] & mask != 0;
+        self.bits[word] |= mask;
+        was_set
+    }
+}
+
+/// Parses a `--mem`-style byte-size string like `4G`, `512M`, `2048K`, or a
+/// bare byte count. Case-insensitive; a trailing `B` (as in `4GB`) is
+/// tolerated.
+fn parse_mem(s: &str) -> anyhow::Result<usize> {
+    let trimmed = s.trim();
+    let trimmed = trimmed
+        .strip_suffix('b')
+        .or_else(|| trimmed.strip_suffix('B'))
+        .unwrap_or(trimmed);
+
+    let (num, mult) = match trimmed.chars().last() {
+        Some(c) if c.eq_ignore_ascii_case(&'g') => (&trimmed[..trimmed.len() - 1], 1024 * 1024 * 1024),
+        Some(c) if c.eq_ignore_ascii_case(&'m') => (&trimmed[..trimmed.len() - 1], 1024 * 1024),
+        Some(c) if c.eq_ignore_ascii_case(&'k') => (&trimmed[..trimmed.len() - 1], 1024),
+        _ => (trimmed, 1),
+    };
+
+    let value: f64 = num
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid --mem value: {s}"))?;
+    Ok((value * mult as f64) as usize)
+}
@@ -0,0 +1,83 @@
+use clap::*;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("maf2fa")
+        .about("Convert maf to block fasta")
+        .after_help(
+            r###"
+* <infiles> are paths to maf files, .maf.gz is supported
+    * infile == stdin means reading from STDIN
+
+* --min-seqs skips blocks with fewer than this many sequences
+
+* `pgr chain`'s axtToMaf stage bakes `-tPrefix`/`-qPrefix` into each
+  sequence's name (e.g. `-tPrefix=target.` turns `chr1` into `target.chr1`),
+  so the block fasta names this writes already carry the species prefix
+  needed by the `fasr` tools; no extra mapping step is required to pipe
+  `pgr chain`'s output into `fasr`
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input files to use"),
+        )
+        .arg(
+            Arg::new("min_seqs")
+                .long("min-seqs")
+                .num_args(1)
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("Skip blocks with fewer than this many sequences"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+    let opt_min_seqs = *args.get_one::<usize>("min_seqs").unwrap();
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let mut reader = intspan::reader(infile);
+
+        while let Ok(block) = hnsm::next_maf_block(&mut reader) {
+            if block.entries.len() < opt_min_seqs {
+                continue;
+            }
+
+            // Can't use reference as entry.alignment does not Copy
+            for entry in block.entries {
+                let range = entry.to_range();
+                let seq = String::from_utf8(entry.alignment).unwrap();
+
+                //----------------------------
+                // Output
+                //----------------------------
+                writer.write_all(format!(">{}\n{}\n", range, seq).as_ref())?;
+            }
+
+            // end of a block
+            writer.write_all("\n".as_ref())?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
 use clap::*;
+use std::collections::HashMap;
+use std::io::BufRead;
 
 // Create clap subcommand arguments
 pub fn make_subcommand() -> Command {
@@ -27,10 +29,35 @@ format:
             Arg::new("mode")
                 .long("mode")
                 .action(ArgAction::Set)
-                .value_parser([builder::PossibleValue::new("pcoa")])
+                .value_parser([
+                    builder::PossibleValue::new("pcoa"),
+                    builder::PossibleValue::new("tsne"),
+                ])
                 .default_value("pcoa")
                 .help("Reduction method"),
         )
+        .arg(
+            Arg::new("perplexity")
+                .long("perplexity")
+                .num_args(1)
+                .default_value("30.0")
+                .value_parser(value_parser!(f64))
+                .help("With --mode tsne, roughly the number of effective nearest neighbors"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .num_args(1)
+                .default_value("42")
+                .value_parser(value_parser!(u64))
+                .help("With --mode tsne, seeds the random initial layout for reproducible runs"),
+        )
+        .arg(
+            Arg::new("annotate")
+                .long("annotate")
+                .num_args(1)
+                .help("A `name\\tlabel` TSV file; joins a label column into the output"),
+        )
         .arg(
             Arg::new("same")
                 .long("same")
@@ -78,6 +105,10 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
 
     let opt_dim = *args.get_one::<usize>("dim").unwrap();
 
+    let opt_perplexity = *args.get_one::<f64>("perplexity").unwrap();
+    let opt_seed = *args.get_one::<u64>("seed").unwrap();
+    let opt_annotate = args.get_one::<String>("annotate");
+
     let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
 
     //----------------------------
@@ -88,7 +119,7 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let matrix = hnsm::populate_matrix(&pair_scores, &index_name, opt_same, opt_missing);
     let size = matrix.size();
 
-    match opt_mode.as_str() {
+    let coords: Vec<Vec<f64>> = match opt_mode.as_str() {
         "pcoa" => {
             let mut dmatrix = pcoa::nalgebra::DMatrix::from_element(size, size, 1.);
 
@@ -99,17 +130,64 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
             }
 
             let coords_matrix = pcoa::apply_pcoa(dmatrix, opt_dim).expect("cannot apply PCoA");
-
             let coords_matrix = coords_matrix.transpose();
-            let xs: Vec<_> = coords_matrix.column(0).iter().copied().collect();
-            let ys: Vec<_> = coords_matrix.column(1).iter().copied().collect();
 
-            for ((x, y), n) in std::iter::zip(xs, ys).zip(index_name) {
-                writer.write_fmt(format_args!("{}\t{}\t{}\n", n, x, y))?;
+            (0..size)
+                .map(|row| {
+                    (0..opt_dim)
+                        .map(|col| coords_matrix[(row, col)] as f64)
+                        .collect()
+                })
+                .collect()
+        }
+        "tsne" => {
+            let mut dmatrix: hnsm::ScoringMatrix<f64> =
+                hnsm::ScoringMatrix::new(size, opt_same as f64, opt_missing as f64);
+            for row in 0..size {
+                for col in 0..size {
+                    dmatrix.set(row, col, matrix.get(row, col) as f64);
+                }
             }
+
+            let tsne = hnsm::Tsne::new(opt_dim, opt_perplexity, 1000, opt_seed);
+            tsne.fit(&dmatrix)
         }
         _ => unreachable!(),
+    };
+
+    let labels = opt_annotate.map(|path| load_annotations(path)).transpose()?;
+
+    for (point, name) in coords.iter().zip(&index_name) {
+        let coord_str = point
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join("\t");
+        match &labels {
+            Some(map) => {
+                let label = map.get(name).map(|s| s.as_str()).unwrap_or("");
+                writer.write_fmt(format_args!("{}\t{}\t{}\n", name, coord_str, label))?;
+            }
+            None => {
+                writer.write_fmt(format_args!("{}\t{}\n", name, coord_str))?;
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Loads a `name\tlabel` TSV file into a name-to-label map, for `--annotate`.
+fn load_annotations(path: &str) -> anyhow::Result<HashMap<String, String>> {
+    let reader = intspan::reader(path);
+    let mut map = HashMap::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        if let Some((name, label)) = line.split_once('\t') {
+            map.insert(name.to_string(), label.to_string());
+        }
+    }
+
+    Ok(map)
+}
@@ -20,6 +20,22 @@ pub fn make_subcommand() -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Only regions of N/n"),
         )
+        .arg(
+            Arg::new("window")
+                .long("window")
+                .short('w')
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .help("Report the masked fraction in sliding windows of this size instead of listing regions"),
+        )
+        .arg(
+            Arg::new("step")
+                .long("step")
+                .num_args(1)
+                .default_value("1000")
+                .value_parser(value_parser!(usize))
+                .help("Step size between windows, used with --window"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -33,6 +49,8 @@ pub fn make_subcommand() -> Command {
 // command implementation
 pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let is_gap = args.get_flag("gap");
+    let opt_window = args.get_one::<usize>("window").copied();
+    let opt_step = *args.get_one::<usize>("step").unwrap();
 
     let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
 
@@ -47,6 +65,21 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
             let name = String::from_utf8(record.name().into()).unwrap();
             let seq = record.sequence();
 
+            if let Some(window) = opt_window {
+                let seq_bytes = seq.get(..).unwrap();
+                let pred = |el: u8| {
+                    if is_gap {
+                        hnsm::is_n(el)
+                    } else {
+                        hnsm::is_n(el) || hnsm::is_lower(el)
+                    }
+                };
+                for (start, end, fraction) in hnsm::windowed_fraction(seq_bytes, window, opt_step, pred) {
+                    writer.write_fmt(format_args!("{}\t{}\t{}\t{:.4}\n", name, start, end, fraction))?;
+                }
+                continue;
+            }
+
             let mut begin = usize::MAX;
             let mut end = usize::MAX;
 
@@ -6,10 +6,20 @@ pub mod convert;
 pub mod count;
 pub mod das;
 pub mod dedup;
+pub mod degap;
 pub mod distance;
+pub mod dust;
+pub mod expand;
+pub mod fa2tab;
 pub mod filter;
+pub mod gc;
+pub mod gff;
 pub mod gz;
+pub mod hash;
+pub mod hv;
 pub mod interleave;
+pub mod kcount;
+pub mod maf2fa;
 pub mod manifold;
 pub mod mask;
 pub mod masked;
@@ -18,9 +28,13 @@ pub mod one;
 pub mod order;
 pub mod range;
 pub mod rc;
+pub mod rename;
+pub mod repeats;
 pub mod replace;
+pub mod screen;
 pub mod similarity;
 pub mod sixframe;
 pub mod size;
 pub mod some;
 pub mod split;
+pub mod validate;
@@ -1,4 +1,5 @@
 use clap::*;
+use hnsm::SeqStats;
 use noodles_fasta as fasta;
 
 // Create clap subcommand arguments
@@ -9,6 +10,8 @@ pub fn make_subcommand() -> Command {
             r#"
 * N50 is the default output value, set a single `-N 0` to skip this
 * To calculate both N50 and N90, enter `-N 50 -N 90`
+* L50/L90 are the counterpart contig counts, e.g. `-L 50 -L 90`
+* auN (`-U`) is the area under the Nx curve
 * Turn on other options to compute more statitics
 * E-size is defined as the expected contig length at which a random position locates
 
@@ -38,6 +41,22 @@ pub fn make_subcommand() -> Command {
                 .value_parser(value_parser!(usize))
                 .help("Compute Nx statistic"),
         )
+        .arg(
+            Arg::new("lx")
+                .long("lx")
+                .short('L')
+                .num_args(1)
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(usize))
+                .help("Compute Lx statistic, the number of contigs needed to reach x% of the total size"),
+        )
+        .arg(
+            Arg::new("aun")
+                .long("aun")
+                .short('U')
+                .action(ArgAction::SetTrue)
+                .help("Compute auN, the area under the Nx curve"),
+        )
         .arg(
             Arg::new("sum")
                 .long("sum")
@@ -100,6 +119,7 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let is_sum = args.get_flag("sum");
     let is_average = args.get_flag("average");
     let is_esize = args.get_flag("esize");
+    let is_aun = args.get_flag("aun");
     let is_count = args.get_flag("count");
     let is_transpose = args.get_flag("transpose");
 
@@ -109,6 +129,15 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         .map(|el| *el)
         .collect();
 
+    let opt_lx: Vec<_> = if args.contains_id("lx") {
+        args.get_many::<usize>("lx")
+            .unwrap()
+            .map(|el| *el)
+            .collect()
+    } else {
+        vec![]
+    };
+
     let opt_genome = if args.contains_id("genome") {
         *args.get_one::<usize>("genome").unwrap()
     } else {
@@ -120,9 +149,7 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     //----------------------------
     // Operating
     //----------------------------
-    let mut lens = vec![];
-    let mut record_cnt = 0;
-    let mut total_size = 0;
+    let mut stats = SeqStats::new();
     for infile in args.get_many::<String>("infiles").unwrap() {
         let reader = intspan::reader(infile);
         let mut fa_in = fasta::io::Reader::new(reader);
@@ -130,46 +157,56 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         for result in fa_in.records() {
             // obtain record or fail with error
             let record = result?;
-
-            let len = record.sequence().len();
-
-            lens.push(len);
-            record_cnt += 1;
-            total_size += len;
+            stats.update(&record);
         }
     }
-    lens.sort_unstable_by(|a, b| b.cmp(a));
+    let stats = stats.finalize();
+    let lens = stats.lens;
+    let record_cnt = stats.record_cnt;
+    let total_size = stats.total_len;
     // eprintln!("lens = {:#?}", lens);
 
     // reach n_given% of total_size or genome_size
-    let mut goals = vec![];
-    for el in opt_nx.iter() {
-        let goal = if opt_genome != usize::MAX {
+    let goal_of = |el: &usize| -> usize {
+        (if opt_genome != usize::MAX {
             (*el as f64) * (opt_genome as f64) / 100.0
         } else {
             (*el as f64) * (total_size as f64) / 100.0
-        } as usize;
-        goals.push(goal);
-    }
+        }) as usize
+    };
+    let goals: Vec<_> = opt_nx.iter().map(goal_of).collect();
+    let l_goals: Vec<_> = opt_lx.iter().map(goal_of).collect();
 
     let mut cumul_size = 0; // the cumulative size
+    let mut cur_count = 0; // the number of contigs seen so far
     let mut e_size = 0.0;
+    let mut sum_sq = 0u128; // for auN, Σ len_i^2
     let mut nx_sizes = vec![0; goals.len()];
+    let mut lx_counts = vec![0; l_goals.len()];
 
     for cur_size in lens {
         let prev_cumul_size = cumul_size;
         cumul_size += cur_size;
+        cur_count += 1;
 
         e_size = (prev_cumul_size as f64) / (cumul_size as f64) * e_size
             + (cur_size as f64 * cur_size as f64) / cumul_size as f64;
+        sum_sq += (cur_size as u128) * (cur_size as u128);
 
         for (i, goal) in goals.iter().enumerate() {
             if nx_sizes[i] == 0 && cumul_size > *goal {
                 nx_sizes[i] = cur_size;
             }
         }
+        for (i, goal) in l_goals.iter().enumerate() {
+            if lx_counts[i] == 0 && cumul_size > *goal {
+                lx_counts[i] = cur_count;
+            }
+        }
     }
 
+    let au_n = sum_sq as f64 / total_size as f64;
+
     //----------------------------
     // Output
     //----------------------------
@@ -187,6 +224,24 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         }
     }
 
+    for (i, lx) in opt_lx.iter().enumerate() {
+        let mut row = vec![];
+        if !is_noheader {
+            row.push(format!("L{}", lx));
+        }
+        row.push(format!("{}", lx_counts[i]));
+        outputs.push(row);
+    }
+
+    if is_aun {
+        let mut row = vec![];
+        if !is_noheader {
+            row.push("U".to_string());
+        }
+        row.push(format!("{:.2}", au_n));
+        outputs.push(row);
+    }
+
     if is_sum {
         let mut row = vec![];
         if !is_noheader {
@@ -1,10 +1,34 @@
 use clap::*;
+use noodles_core::Position;
 use noodles_fasta as fasta;
+use std::collections::HashMap;
+use std::io::Write;
 
 // Create clap subcommand arguments
 pub fn make_subcommand() -> Command {
     Command::new("one")
         .about("Extract one FA record")
+        .after_help(
+            r###"
+* --range slices the extracted record by a 1-based inclusive `start-end`
+  range, e.g. `--range 100-200`; prefix with `(-)` to also reverse-complement,
+  e.g. `--range (-):100-200`
+* --multi extracts several records in a single pass instead of one, e.g.
+  `--multi name1,name2,name3`; it also accepts a file of names (one per
+  line) or `-` for stdin. Output order follows the order of the requested
+  names, not the order they appear in the file. Names not found are warned
+  about on stderr. --range is not supported together with --multi
+* .2bit files are detected by their magic number and read directly, no
+  conversion to fasta needed
+* --output-format picks the output shape:
+    * `fa` (default): a FASTA record
+    * `raw`: just the sequence bytes, no header, e.g. for
+      `hnsm one large.fa myseq --output-format raw | wc -c`
+    * `len`: just the sequence length, equivalent to `hnsm size` for one record
+    * `json`: `{"name": "...", "seq": "...", "len": N}`
+
+"###,
+        )
         .arg(
             Arg::new("infile")
                 .required(true)
@@ -13,10 +37,37 @@ pub fn make_subcommand() -> Command {
         )
         .arg(
             Arg::new("name")
-                .required(true)
+                .required_unless_present("multi")
                 .index(2)
                 .help("The name of the wanted record"),
         )
+        .arg(
+            Arg::new("multi")
+                .long("multi")
+                .num_args(1)
+                .conflicts_with("name")
+                .help("A comma-separated list of names, or a file of names, one per line ('-' for stdin)"),
+        )
+        .arg(
+            Arg::new("range")
+                .long("range")
+                .num_args(1)
+                .conflicts_with("multi")
+                .help("Extract only this 1-based inclusive range of the record"),
+        )
+        .arg(
+            Arg::new("output_format")
+                .long("output-format")
+                .action(ArgAction::Set)
+                .value_parser([
+                    builder::PossibleValue::new("fa"),
+                    builder::PossibleValue::new("raw"),
+                    builder::PossibleValue::new("len"),
+                    builder::PossibleValue::new("json"),
+                ])
+                .default_value("fa")
+                .help("Output format: fa (default), raw sequence, length, or json"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -29,15 +80,94 @@ pub fn make_subcommand() -> Command {
 
 // command implementation
 pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
-    let reader = intspan::reader(args.get_one::<String>("infile").unwrap());
+    let infile = args.get_one::<String>("infile").unwrap();
+    let opt_output_format = args.get_one::<String>("output_format").unwrap().as_str();
+
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    if hnsm::TwoBitReader::is_twobit(infile) {
+        let mut tb = hnsm::TwoBitReader::open(infile)?;
+
+        if let Some(multi) = args.get_one::<String>("multi") {
+            let wanted: Vec<String> = if multi.contains(',') {
+                multi.split(',').map(|s| s.to_string()).collect()
+            } else {
+                intspan::read_first_column(multi)
+            };
+
+            for name in &wanted {
+                match tb.record(name) {
+                    Ok(record) => write_record(&mut *writer, opt_output_format, &record)?,
+                    Err(_) => eprintln!("Name not found: {}", name),
+                }
+            }
+
+            return Ok(());
+        }
+
+        let name = args.get_one::<String>("name").unwrap();
+        let opt_range = args.get_one::<String>("range");
+
+        return match opt_range {
+            None => {
+                let record = tb.record(name)?;
+                write_record(&mut *writer, opt_output_format, &record)?;
+                Ok(())
+            }
+            Some(range) => {
+                let rg = intspan::Range::from_str(&format!("{}:{}", name, range));
+                let seq = tb.sequence(name, *rg.start() as u32 - 1, *rg.end() as u32)?;
+
+                let seq = if rg.strand() == "-" {
+                    seq.iter().rev().map(|&nt| hnsm::complement_nt(nt)).collect()
+                } else {
+                    seq
+                };
+
+                let definition = fasta::record::Definition::new(rg.to_string(), None);
+                let record = fasta::Record::new(definition, fasta::record::Sequence::from(seq));
+                write_record(&mut *writer, opt_output_format, &record)?;
+                Ok(())
+            }
+        };
+    }
+
+    let reader = intspan::reader(infile);
     let mut fa_in = fasta::io::Reader::new(reader);
 
-    let writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
-    let mut fa_out = fasta::io::writer::Builder::default()
-        .set_line_base_count(usize::MAX)
-        .build_from_writer(writer);
+    if let Some(multi) = args.get_one::<String>("multi") {
+        let wanted: Vec<String> = if multi.contains(',') {
+            multi.split(',').map(|s| s.to_string()).collect()
+        } else {
+            intspan::read_first_column(multi)
+        };
+
+        let wanted_uniq: std::collections::HashSet<&String> = wanted.iter().collect();
+        let mut found: HashMap<String, fasta::Record> = HashMap::new();
+        for result in fa_in.records() {
+            // obtain record or fail with error
+            let record = result?;
+            if found.len() == wanted_uniq.len() {
+                break;
+            }
+            let this_name = String::from_utf8(record.name().into()).unwrap();
+            if wanted_uniq.contains(&this_name) && !found.contains_key(&this_name) {
+                found.insert(this_name, record);
+            }
+        }
+
+        for name in &wanted {
+            match found.get(name) {
+                Some(record) => write_record(&mut *writer, opt_output_format, record)?,
+                None => eprintln!("Name not found: {}", name),
+            }
+        }
+
+        return Ok(());
+    }
 
     let name = args.get_one::<String>("name").unwrap();
+    let opt_range = args.get_one::<String>("range");
 
     for result in fa_in.records() {
         // obtain record or fail with error
@@ -45,10 +175,78 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
 
         let this_name = String::from_utf8(record.name().into()).unwrap();
         if this_name == *name {
-            fa_out.write_record(&record)?;
+            match opt_range {
+                None => write_record(&mut *writer, opt_output_format, &record)?,
+                Some(range) => {
+                    let rg = intspan::Range::from_str(&format!("{}:{}", name, range));
+
+                    let definition = fasta::record::Definition::new(rg.to_string(), None);
+                    let start = Position::new(*rg.start() as usize).unwrap();
+                    let end = Position::new(*rg.end() as usize).unwrap();
+
+                    let record_rg = if rg.strand() == "-" {
+                        let seq_rc: fasta::record::Sequence = record
+                            .sequence()
+                            .complement()
+                            .rev()
+                            .collect::<Result<_, _>>()?;
+                        let slice = seq_rc.slice(start..=end).unwrap();
+                        fasta::Record::new(definition, slice)
+                    } else {
+                        let slice = record.sequence().slice(start..=end).unwrap();
+                        fasta::Record::new(definition, slice)
+                    };
+                    write_record(&mut *writer, opt_output_format, &record_rg)?;
+                }
+            }
             break;
         }
     }
 
     Ok(())
 }
+
+/// Writes one record in `--output-format`'s shape: `fa` builds a one-off
+/// FASTA writer over `writer` (unwrapped, since `Box<dyn Write>`'s
+/// blanket impl lets a `&mut dyn Write` stand in for an owned writer);
+/// `raw`/`len`/`json` write directly.
+fn write_record(writer: &mut dyn Write, format: &str, record: &fasta::Record) -> anyhow::Result<()> {
+    let name = String::from_utf8(record.name().into()).unwrap();
+    let seq = record.sequence();
+    let seq_bytes = seq.get(..).unwrap();
+
+    match format {
+        "fa" => {
+            let mut fa_out = fasta::io::writer::Builder::default()
+                .set_line_base_count(usize::MAX)
+                .build_from_writer(writer);
+            fa_out.write_record(record)?;
+        }
+        "raw" => {
+            writer.write_all(seq_bytes)?;
+            writer.write_all(b"\n")?;
+        }
+        "len" => {
+            writeln!(writer, "{}", seq_bytes.len())?;
+        }
+        "json" => {
+            #[derive(serde::Serialize)]
+            struct OneJson<'a> {
+                name: &'a str,
+                seq: &'a str,
+                len: usize,
+            }
+            let seq_str = String::from_utf8_lossy(seq_bytes);
+            let json = OneJson {
+                name: &name,
+                seq: &seq_str,
+                len: seq_bytes.len(),
+            };
+            serde_json::to_writer_pretty(&mut *writer, &json)?;
+            writeln!(writer)?;
+        }
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
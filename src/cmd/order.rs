@@ -9,6 +9,10 @@ pub fn make_subcommand() -> Command {
         .after_help(
             r###"
 * Loads all sequences in memory, thus consuming more memory
+* --exclude removes listed names from the reordered output; combine with
+  --strict to warn about exclude names never seen in <infile>
+* <list.txt> also accepts `stdin`/`-`, to pipe a name list in directly; only
+  one of <infile>/<list.txt> may read from stdin at a time
 
 "###,
         )
@@ -22,7 +26,19 @@ pub fn make_subcommand() -> Command {
             Arg::new("list.txt")
                 .required(true)
                 .index(2)
-                .help("One name per line"),
+                .help("One name per line; `stdin`/`-` reads the list from stdin"),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .num_args(1)
+                .help("A file of names, one per line, to drop from the reordered output"),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .action(ArgAction::SetTrue)
+                .help("Warn on stderr about --exclude names never seen in the input"),
         )
         .arg(
             Arg::new("outfile")
@@ -36,7 +52,15 @@ pub fn make_subcommand() -> Command {
 
 // command implementation
 pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
-    let reader = intspan::reader(args.get_one::<String>("infile").unwrap());
+    let infile = args.get_one::<String>("infile").unwrap();
+    let list_path = args.get_one::<String>("list.txt").unwrap();
+    if infile == "stdin" && hnsm::is_stdin(list_path) {
+        return Err(anyhow::anyhow!(
+            "<infile> and <list.txt> cannot both read from stdin"
+        ));
+    }
+
+    let reader = intspan::reader(infile);
     let mut fa_in = fasta::io::Reader::new(reader);
 
     let writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
@@ -44,7 +68,7 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         .set_line_base_count(usize::MAX)
         .build_from_writer(writer);
 
-    let vec_list = intspan::read_first_column(args.get_one::<String>("list.txt").unwrap());
+    let vec_list = hnsm::read_name_list(list_path);
     let mut record_of = BTreeMap::new();
 
     for result in fa_in.records() {
@@ -57,12 +81,26 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         }
     }
 
+    let is_strict = args.get_flag("strict");
+    let mut opt_exclude = args
+        .get_one::<String>("exclude")
+        .map(|path| hnsm::ExcludeSet::new(path, is_strict));
+
     for el in vec_list.iter() {
         if record_of.contains_key(el) {
+            if let Some(exclude) = opt_exclude.as_mut() {
+                if exclude.contains(el) {
+                    continue;
+                }
+            }
             let record = record_of.get(el).unwrap();
             fa_out.write_record(record)?;
         }
     }
 
+    if let Some(exclude) = &opt_exclude {
+        exclude.warn_unused();
+    }
+
     Ok(())
 }
@@ -25,6 +25,19 @@ pub fn make_subcommand() -> Command {
 
 * The default capacity of the LRU cache is 1, i.e., the most recent record is cached
 * Sorting the rgfile will speed up the extraction
+* .2bit files are detected by their magic number and read directly, bypassing
+  the .loc index entirely (the format already carries its own name/offset index)
+
+* --flank <N> extends both sides of every range by N bp, clipped at the
+  sequence's ends
+* --up <N> / --down <N> extend only one side, strand-aware: on a `+` range
+  "up" extends the start and "down" extends the end; on a `-` range this is
+  reversed, so "up" still means further from the transcription start. Both
+  are clipped at the sequence's ends; --flank conflicts with --up/--down
+* --name-template "{chr}_{start}_{end}_{strand}" names each output record
+  from a template instead of the default `name(strand):start-end` form;
+  {start}/{end} are always the actual, possibly-clipped coordinates after
+  --flank/--up/--down are applied
 
 "###,
         )
@@ -57,6 +70,36 @@ pub fn make_subcommand() -> Command {
                 .default_value("1")
                 .help("Set the capacity of the LRU cache"),
         )
+        .arg(
+            Arg::new("flank")
+                .long("flank")
+                .num_args(1)
+                .value_parser(value_parser!(i32))
+                .conflicts_with_all(["up", "down"])
+                .help("Extend both sides of each range by this many bp, clipped at sequence ends"),
+        )
+        .arg(
+            Arg::new("up")
+                .long("up")
+                .num_args(1)
+                .value_parser(value_parser!(i32))
+                .conflicts_with("flank")
+                .help("Extend the upstream side by this many bp, strand-aware, clipped at sequence ends"),
+        )
+        .arg(
+            Arg::new("down")
+                .long("down")
+                .num_args(1)
+                .value_parser(value_parser!(i32))
+                .conflicts_with("flank")
+                .help("Extend the downstream side by this many bp, strand-aware, clipped at sequence ends"),
+        )
+        .arg(
+            Arg::new("name_template")
+                .long("name-template")
+                .num_args(1)
+                .help("Template for output record names, e.g. \"{chr}_{start}_{end}_{strand}\""),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -103,6 +146,70 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let opt_cache = *args.get_one::<num::NonZeroUsize>("cache").unwrap();
     let mut cache: lru::LruCache<String, fasta::Record> = lru::LruCache::new(opt_cache);
 
+    let opt_flank = args.get_one::<i32>("flank").copied();
+    let opt_up = args.get_one::<i32>("up").copied();
+    let opt_down = args.get_one::<i32>("down").copied();
+    let opt_name_template = args.get_one::<String>("name_template");
+
+    //----------------------------
+    // .2bit files carry their own index; skip the .loc machinery entirely
+    //----------------------------
+    if hnsm::TwoBitReader::is_twobit(infile) {
+        let mut tb = hnsm::TwoBitReader::open(infile)?;
+
+        for el in ranges.iter() {
+            let rg = intspan::Range::from_str(el);
+            let seq_id = rg.chr().to_string();
+
+            // name only
+            if *rg.start() == 0 {
+                match tb.record(&seq_id) {
+                    Ok(record) => fa_out.write_record(&record)?,
+                    Err(_) => eprintln!("{} for [{}] not found in the .2bit file\n", seq_id, el),
+                }
+                continue;
+            }
+
+            let seq_len = match tb.seq_size(&seq_id) {
+                Ok(size) => size as i32,
+                Err(_) => {
+                    eprintln!("{} for [{}] not found in the .2bit file\n", seq_id, el);
+                    continue;
+                }
+            };
+            let (ext_start, ext_end) = extend_range(
+                *rg.start(),
+                *rg.end(),
+                rg.strand(),
+                opt_flank,
+                opt_up,
+                opt_down,
+                seq_len,
+            );
+
+            let seq = match tb.sequence(&seq_id, ext_start as u32 - 1, ext_end as u32) {
+                Ok(seq) => seq,
+                Err(_) => {
+                    eprintln!("{} for [{}] not found in the .2bit file\n", seq_id, el);
+                    continue;
+                }
+            };
+
+            let seq = if rg.strand() == "-" {
+                seq.iter().rev().map(|&nt| hnsm::complement_nt(nt)).collect()
+            } else {
+                seq
+            };
+
+            let name = record_name(opt_name_template, rg.name(), &seq_id, rg.strand(), ext_start, ext_end);
+            let definition = fasta::record::Definition::new(name, None);
+            let record_rg = fasta::Record::new(definition, fasta::record::Sequence::from(seq));
+            fa_out.write_record(&record_rg)?;
+        }
+
+        return Ok(());
+    }
+
     //----------------------------
     // Open files
     //----------------------------
@@ -146,11 +253,23 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
             continue;
         }
 
-        let definition = fasta::record::Definition::new(rg.to_string(), None);
+        let seq_len = record.sequence().len() as i32;
+        let (ext_start, ext_end) = extend_range(
+            *rg.start(),
+            *rg.end(),
+            rg.strand(),
+            opt_flank,
+            opt_up,
+            opt_down,
+            seq_len,
+        );
+
+        let name = record_name(opt_name_template, rg.name(), rg.chr(), rg.strand(), ext_start, ext_end);
+        let definition = fasta::record::Definition::new(name, None);
 
         // slice here is 1-based
-        let start = Position::new(*rg.start() as usize).unwrap();
-        let end = Position::new(*rg.end() as usize).unwrap();
+        let start = Position::new(ext_start as usize).unwrap();
+        let end = Position::new(ext_end as usize).unwrap();
 
         let record_rg = if rg.strand() == "-" {
             let seq_rc: fasta::record::Sequence = record
@@ -170,6 +289,50 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Extends `[start, end]` by `flank` on both sides, or by `up`/`down` on a
+/// strand-aware single side, clamped to `[1, seq_len]`.
+fn extend_range(
+    start: i32,
+    end: i32,
+    strand: &str,
+    opt_flank: Option<i32>,
+    opt_up: Option<i32>,
+    opt_down: Option<i32>,
+    seq_len: i32,
+) -> (i32, i32) {
+    let (up, down) = match opt_flank {
+        Some(flank) => (flank, flank),
+        None => (opt_up.unwrap_or(0), opt_down.unwrap_or(0)),
+    };
+
+    let (left, right) = if strand == "-" { (down, up) } else { (up, down) };
+
+    let new_start = (start - left).max(1);
+    let new_end = (end + right).min(seq_len);
+
+    (new_start, new_end)
+}
+
+/// Builds an output record name, either from `--name-template` or the
+/// default `name(strand):start-end` form.
+fn record_name(
+    opt_name_template: Option<&String>,
+    name: &str,
+    chr: &str,
+    strand: &str,
+    start: i32,
+    end: i32,
+) -> String {
+    match opt_name_template {
+        Some(template) => template
+            .replace("{chr}", chr)
+            .replace("{start}", &start.to_string())
+            .replace("{end}", &end.to_string())
+            .replace("{strand}", strand),
+        None => intspan::Range::from_full(name, chr, strand, start, end).to_string(),
+    }
+}
+
 // fn print_type_of<T: ?Sized>(_: &T) {
 //     println!("{}", std::any::type_name::<T>())
 // }
@@ -9,6 +9,14 @@ pub fn make_subcommand() -> Command {
         .after_help(
             r###"
 * [list] is optional, only RC sequences listed in this file
+* --all reverse-complements every sequence, ignoring [list] if given
+* --invert reverse-complements sequences NOT in [list] instead of those in it
+* --iupac correctly complements IUPAC ambiguity codes (M, R, W, S, Y, K, V,
+  H, D, B, N), not just A/C/G/T
+* .2bit files are detected by their magic number and read directly, no
+  conversion to fasta needed
+* [list] also accepts `stdin`/`-`, to pipe a name list in directly; only one
+  of <infile>/[list] may read from stdin at a time
 
 "###,
         )
@@ -22,12 +30,31 @@ pub fn make_subcommand() -> Command {
             Arg::new("list.txt")
                 .required(false)
                 .index(2)
-                .help("One name per line"),
+                .help("One name per line; `stdin`/`-` reads the list from stdin"),
+        )
+        .arg(
+            Arg::new("all")
+                .long("all")
+                .action(ArgAction::SetTrue)
+                .help("Reverse-complement every sequence, ignoring [list]"),
+        )
+        .arg(
+            Arg::new("invert")
+                .long("invert")
+                .action(ArgAction::SetTrue)
+                .help("Reverse-complement sequences not in [list] instead of those in it"),
+        )
+        .arg(
+            Arg::new("iupac")
+                .long("iupac")
+                .action(ArgAction::SetTrue)
+                .help("Correctly complement IUPAC ambiguity codes"),
         )
         .arg(
             Arg::new("consistent")
                 .long("consistent")
                 .short('c')
+                .visible_alias("strip-prefix")
                 .action(ArgAction::SetTrue)
                 .help("Keep the name consistent (don't prepend RC_)"),
         )
@@ -43,29 +70,81 @@ pub fn make_subcommand() -> Command {
 
 // command implementation
 pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
-    let reader = intspan::reader(args.get_one::<String>("infile").unwrap());
-    let mut fa_in = fasta::io::Reader::new(reader);
+    let infile = args.get_one::<String>("infile").unwrap();
 
     let is_consistent = args.get_flag("consistent");
+    let is_all = args.get_flag("all");
+    let is_invert = args.get_flag("invert");
+    let is_iupac = args.get_flag("iupac");
 
     let writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
     let mut fa_out = fasta::io::writer::Builder::default()
         .set_line_base_count(usize::MAX)
         .build_from_writer(writer);
 
+    let has_list = !is_all && args.contains_id("list.txt");
     let mut set_list: HashSet<String> = HashSet::new();
-    if args.contains_id("list.txt") {
-        set_list = intspan::read_first_column(args.get_one::<String>("list.txt").unwrap())
-            .into_iter()
-            .collect();
+    if has_list {
+        let list_path = args.get_one::<String>("list.txt").unwrap();
+        if infile == "stdin" && hnsm::is_stdin(list_path) {
+            return Err(anyhow::anyhow!(
+                "<infile> and [list] cannot both read from stdin"
+            ));
+        }
+        set_list = hnsm::read_name_list(list_path).into_iter().collect();
     }
 
+    if hnsm::TwoBitReader::is_twobit(infile) {
+        let mut tb = hnsm::TwoBitReader::open(infile)?;
+
+        for name in tb.names() {
+            let record = tb.record(&name)?;
+
+            if has_list && (set_list.contains(&name) == is_invert) {
+                fa_out.write_record(&record)?;
+                continue;
+            }
+
+            let out_name = if is_consistent {
+                name.clone()
+            } else {
+                format!("RC_{}", name)
+            };
+            let definition = fasta::record::Definition::new(&*out_name, None);
+
+            let seq_rc: fasta::record::Sequence = if is_iupac {
+                let bytes: Vec<u8> = record
+                    .sequence()
+                    .get(..)
+                    .unwrap()
+                    .iter()
+                    .rev()
+                    .map(|&nt| hnsm::complement_nt(nt))
+                    .collect();
+                fasta::record::Sequence::from(bytes)
+            } else {
+                record
+                    .sequence()
+                    .complement()
+                    .rev()
+                    .collect::<Result<_, _>>()?
+            };
+            let record_rc = fasta::Record::new(definition, seq_rc);
+            fa_out.write_record(&record_rc)?;
+        }
+
+        return Ok(());
+    }
+
+    let reader = intspan::reader(infile);
+    let mut fa_in = fasta::io::Reader::new(reader);
+
     for result in fa_in.records() {
         // obtain record or fail with error
         let record = result?;
         let mut name = String::from_utf8(record.name().into()).unwrap();
 
-        if args.contains_id("list.txt") && !set_list.contains(&name) {
+        if has_list && (set_list.contains(&name) == is_invert) {
             fa_out.write_record(&record)?;
             continue;
         }
@@ -76,11 +155,23 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
 
         let definition = fasta::record::Definition::new(&*name, None);
 
-        let seq_rc: fasta::record::Sequence = record
-            .sequence()
-            .complement()
-            .rev()
-            .collect::<Result<_, _>>()?;
+        let seq_rc: fasta::record::Sequence = if is_iupac {
+            let bytes: Vec<u8> = record
+                .sequence()
+                .get(..)
+                .unwrap()
+                .iter()
+                .rev()
+                .map(|&nt| hnsm::complement_nt(nt))
+                .collect();
+            fasta::record::Sequence::from(bytes)
+        } else {
+            record
+                .sequence()
+                .complement()
+                .rev()
+                .collect::<Result<_, _>>()?
+        };
         let record_rc = fasta::Record::new(definition, seq_rc);
         fa_out.write_record(&record_rc)?;
     }
@@ -0,0 +1,116 @@
+use clap::*;
+use noodles_fasta as fasta;
+use std::io::Write;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("rename")
+        .about("Bulk rename sequences to sequential IDs")
+        .after_help(
+            r###"
+* Headers are rewritten as `PREFIX0001`, `PREFIX0002`, ... in record order
+* A `name_map.tsv` of old_name -> new_name is written next to <outfile>,
+  or to stdout after the fasta records when <outfile> is stdout
+* --keep-desc keeps the original header text after the new name
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input file to use"),
+        )
+        .arg(
+            Arg::new("prefix")
+                .long("prefix")
+                .num_args(1)
+                .default_value("")
+                .help("Prefix prepended to the sequential number"),
+        )
+        .arg(
+            Arg::new("start")
+                .long("start")
+                .num_args(1)
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("First sequential number"),
+        )
+        .arg(
+            Arg::new("width")
+                .long("width")
+                .num_args(1)
+                .default_value("4")
+                .value_parser(value_parser!(usize))
+                .help("Zero-padding width of the sequential number"),
+        )
+        .arg(
+            Arg::new("keep_desc")
+                .long("keep-desc")
+                .action(ArgAction::SetTrue)
+                .help("Preserve the original header as a trailing description"),
+        )
+        .arg(
+            Arg::new("map")
+                .long("map")
+                .num_args(1)
+                .default_value("name_map.tsv")
+                .help("Filename of the old-to-new name map, written in the current directory"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    let prefix = args.get_one::<String>("prefix").unwrap();
+    let opt_start = *args.get_one::<usize>("start").unwrap();
+    let opt_width = *args.get_one::<usize>("width").unwrap();
+    let is_keep_desc = args.get_flag("keep_desc");
+
+    let writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+    let mut fa_out = fasta::io::writer::Builder::default()
+        .set_line_base_count(usize::MAX)
+        .build_from_writer(writer);
+
+    let mut map_file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(args.get_one::<String>("map").unwrap())?;
+
+    let mut sn = opt_start;
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+        let mut fa_in = fasta::io::Reader::new(reader);
+
+        for result in fa_in.records() {
+            // obtain record or fail with error
+            let record = result?;
+            let old_name = String::from_utf8(record.name().into()).unwrap();
+
+            let new_name = format!("{}{:0width$}", prefix, sn, width = opt_width);
+            writeln!(map_file, "{}\t{}", old_name, new_name)?;
+
+            let description = if is_keep_desc {
+                record.description().map(|d| d.to_vec())
+            } else {
+                None
+            };
+            let definition = fasta::record::Definition::new(&*new_name, description);
+            let record_out = fasta::Record::new(definition, record.sequence().clone());
+            fa_out.write_record(&record_out)?;
+
+            sn += 1;
+        }
+    }
+
+    Ok(())
+}
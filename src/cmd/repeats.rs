@@ -0,0 +1,320 @@
+use clap::*;
+use noodles_fasta as fasta;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("repeats")
+        .about("Find exact repeat regions (direct and inverted) within/between sequences")
+        .after_help(
+            r###"
+* <infiles> are paths to fasta files, .fa.gz is supported
+    * infile == stdin means reading from STDIN
+
+* A k-mer anchored approach: every k-mer of every sequence is canonicalized
+  (the lexicographically smaller of itself and its reverse complement) and
+  indexed by position; k-mers shared by two positions seed a pairwise exact
+  match, which is then extended base-by-base in both directions - forward and
+  backward together for a direct (same-strand) repeat, mirrored for an
+  inverted (opposite-strand) repeat - until the match breaks
+
+* Only maximal matches of at least `--min-len` bp are reported, as TSV:
+  seq1, start1, end1, seq2, start2, end2, length, orientation
+  (1-based, inclusive coordinates; orientation is `direct` or `inverted`)
+
+* A region is never reported against itself: overlapping start1/start2
+  intervals within the same sequence are skipped as trivial self-hits
+
+* `--max-hits` bounds how many k-mer anchor pairs are extended, guarding
+  against the combinatorial blowup of a highly-repetitive input (e.g. a
+  large poly-A run); anchors beyond the cap are dropped and a warning is
+  printed to stderr
+
+* Extension of the anchor pairs sharing a k-mer runs in parallel via rayon;
+  `--parallel` sets the thread pool size
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input files to use"),
+        )
+        .arg(
+            Arg::new("kmer")
+                .long("kmer")
+                .short('k')
+                .num_args(1)
+                .default_value("20")
+                .value_parser(value_parser!(usize))
+                .help("Kmer size used to seed anchors"),
+        )
+        .arg(
+            Arg::new("min_len")
+                .long("min-len")
+                .num_args(1)
+                .default_value("200")
+                .value_parser(value_parser!(usize))
+                .help("Minimum length of a reported repeat"),
+        )
+        .arg(
+            Arg::new("max_hits")
+                .long("max-hits")
+                .num_args(1)
+                .default_value("1000000")
+                .value_parser(value_parser!(usize))
+                .help("Maximum number of k-mer anchor pairs to extend"),
+        )
+        .arg(
+            Arg::new("parallel")
+                .long("parallel")
+                .num_args(1)
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("Number of threads"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+#[derive(Clone, Copy)]
+struct Occ {
+    seq: usize,
+    pos: usize,
+    is_rc: bool,
+}
+
+#[derive(Eq, PartialEq, Hash)]
+struct Repeat {
+    seq1: usize,
+    start1: usize,
+    end1: usize,
+    seq2: usize,
+    start2: usize,
+    end2: usize,
+    inverted: bool,
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let opt_kmer = *args.get_one::<usize>("kmer").unwrap();
+    let opt_min_len = *args.get_one::<usize>("min_len").unwrap();
+    let opt_max_hits = *args.get_one::<usize>("max_hits").unwrap();
+    let opt_parallel = *args.get_one::<usize>("parallel").unwrap();
+
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    //----------------------------
+    // Load sequences
+    //----------------------------
+    let mut names = vec![];
+    let mut seqs: Vec<Vec<u8>> = vec![];
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+        let mut fa_in = fasta::io::Reader::new(reader);
+
+        for result in fa_in.records() {
+            let record = result?;
+            names.push(String::from_utf8(record.name().into()).unwrap());
+            seqs.push(record.sequence()[..].to_ascii_uppercase());
+        }
+    }
+
+    //----------------------------
+    // Index canonical k-mers with their positions
+    //----------------------------
+    let mut index: HashMap<Vec<u8>, Vec<Occ>> = HashMap::new();
+    for (seq_idx, seq) in seqs.iter().enumerate() {
+        if seq.len() < opt_kmer {
+            continue;
+        }
+        for pos in 0..=(seq.len() - opt_kmer) {
+            let kmer = &seq[pos..pos + opt_kmer];
+            if kmer.iter().any(|&nt| hnsm::is_n(nt)) {
+                continue;
+            }
+            let rc: Vec<u8> = kmer.iter().rev().map(|&nt| hnsm::complement_nt(nt)).collect();
+            let is_rc = rc.as_slice() < kmer;
+            let canonical = if is_rc { rc } else { kmer.to_vec() };
+
+            index.entry(canonical).or_default().push(Occ {
+                seq: seq_idx,
+                pos,
+                is_rc,
+            });
+        }
+    }
+
+    //----------------------------
+    // Collect anchor pairs, capped at --max-hits
+    //----------------------------
+    let mut anchors = vec![];
+    'outer: for occs in index.values() {
+        if occs.len() < 2 {
+            continue;
+        }
+        for i in 0..occs.len() {
+            for j in (i + 1)..occs.len() {
+                if anchors.len() >= opt_max_hits {
+                    eprintln!(
+                        "==> --max-hits ({}) reached; remaining anchors were dropped",
+                        opt_max_hits
+                    );
+                    break 'outer;
+                }
+                anchors.push((occs[i], occs[j]));
+            }
+        }
+    }
+
+    //----------------------------
+    // Extend anchors in parallel
+    //----------------------------
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(opt_parallel)
+        .build()
+        .unwrap();
+
+    let repeats: Vec<Repeat> = pool.install(|| {
+        anchors
+            .par_iter()
+            .filter_map(|(a, b)| extend_anchor(&seqs, a, b, opt_kmer, opt_min_len))
+            .collect()
+    });
+
+    //----------------------------
+    // Dedup and output
+    //----------------------------
+    let mut seen: HashSet<Repeat> = HashSet::new();
+    writer.write_all(b"seq1\tstart1\tend1\tseq2\tstart2\tend2\tlength\torientation\n")?;
+    for repeat in repeats {
+        if seen.contains(&repeat) {
+            continue;
+        }
+        let length = repeat.end1 - repeat.start1 + 1;
+        let orientation = if repeat.inverted { "inverted" } else { "direct" };
+        writer.write_fmt(format_args!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            names[repeat.seq1],
+            repeat.start1 + 1,
+            repeat.end1 + 1,
+            names[repeat.seq2],
+            repeat.start2 + 1,
+            repeat.end2 + 1,
+            length,
+            orientation,
+        ))?;
+        seen.insert(repeat);
+    }
+
+    Ok(())
+}
+
+/// Extends a shared-k-mer anchor between `a` and `b` in both directions until
+/// the exact match breaks, returning the maximal match if it is at least
+/// `min_len` bp and not a trivial self-overlap. Direct (same-strand) repeats
+/// extend forward/backward in lockstep on both sequences; inverted
+/// (opposite-strand) repeats extend forward on one sequence while walking
+/// backward - through the complement - on the other.
+fn extend_anchor(
+    seqs: &[Vec<u8>],
+    a: &Occ,
+    b: &Occ,
+    kmer: usize,
+    min_len: usize,
+) -> Option<Repeat> {
+    let seq_a = &seqs[a.seq];
+    let seq_b = &seqs[b.seq];
+
+    let (start1, end1, start2, end2, inverted) = if a.is_rc == b.is_rc {
+        // Direct: extend forward and backward together
+        let mut fwd = 0usize;
+        while a.pos + kmer + fwd < seq_a.len()
+            && b.pos + kmer + fwd < seq_b.len()
+            && seq_a[a.pos + kmer + fwd] == seq_b[b.pos + kmer + fwd]
+        {
+            fwd += 1;
+        }
+
+        let mut back = 0usize;
+        while a.pos > back
+            && b.pos > back
+            && seq_a[a.pos - 1 - back] == seq_b[b.pos - 1 - back]
+        {
+            back += 1;
+        }
+
+        (
+            a.pos - back,
+            a.pos + kmer - 1 + fwd,
+            b.pos - back,
+            b.pos + kmer - 1 + fwd,
+            false,
+        )
+    } else {
+        // Inverted: seq_a[a.pos + kmer + t] mirrors seq_b[b.pos - 1 - t]
+        let mut fwd = 0usize;
+        while a.pos + kmer + fwd < seq_a.len()
+            && b.pos > fwd
+            && seq_a[a.pos + kmer + fwd] == hnsm::complement_nt(seq_b[b.pos - 1 - fwd])
+        {
+            fwd += 1;
+        }
+
+        let mut back = 0usize;
+        while a.pos > back
+            && b.pos + kmer + back < seq_b.len()
+            && seq_a[a.pos - 1 - back] == hnsm::complement_nt(seq_b[b.pos + kmer + back])
+        {
+            back += 1;
+        }
+
+        (
+            a.pos - back,
+            a.pos + kmer - 1 + fwd,
+            b.pos - fwd,
+            b.pos + kmer - 1 + back,
+            true,
+        )
+    };
+
+    let length = end1 - start1 + 1;
+    if length < min_len {
+        return None;
+    }
+
+    // Exclude self-overlapping trivial hits: a region against (an overlapping
+    // part of) itself
+    if a.seq == b.seq && start1.max(start2) <= end1.min(end2) {
+        return None;
+    }
+
+    let (seq1, start1, end1, seq2, start2, end2) = if (a.seq, start1) <= (b.seq, start2) {
+        (a.seq, start1, end1, b.seq, start2, end2)
+    } else {
+        (b.seq, start2, end2, a.seq, start1, end1)
+    };
+
+    Some(Repeat {
+        seq1,
+        start1,
+        end1,
+        seq2,
+        start2,
+        end2,
+        inverted,
+    })
+}
@@ -0,0 +1,161 @@
+use clap::*;
+use hnsm::Minimizer;
+use noodles_fasta as fasta;
+use std::collections::{HashMap, HashSet};
+use std::iter::FromIterator;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("screen")
+        .about("Screen queries against a reference database, containment-first")
+        .after_help(
+            r###"
+* Unlike `distance`, which reports a symmetric distance for every pair, `screen`
+  is asymmetric: it answers "how much of this query's k-mer content is
+  contained in the database?", which is the useful question when the database
+  is large and the queries are short reads or partial assemblies.
+* <db> is scanned once and kept in memory; <queries> are streamed against it.
+* The outputs: query, db, containment, jaccard
+
+"###,
+        )
+        .arg(
+            Arg::new("db")
+                .required(true)
+                .index(1)
+                .help("The reference database, a FA file"),
+        )
+        .arg(
+            Arg::new("queries")
+                .required(true)
+                .num_args(1..)
+                .index(2)
+                .help("Query FA file(s)"),
+        )
+        .arg(
+            Arg::new("hasher")
+                .long("hasher")
+                .action(ArgAction::Set)
+                .value_parser([
+                    builder::PossibleValue::new("fx"),
+                    builder::PossibleValue::new("murmur"),
+                ])
+                .default_value("fx")
+                .help("Set the hash algorithm"),
+        )
+        .arg(
+            Arg::new("kmer")
+                .long("kmer")
+                .short('k')
+                .num_args(1)
+                .default_value("21")
+                .value_parser(value_parser!(usize))
+                .help("Kmer size"),
+        )
+        .arg(
+            Arg::new("window")
+                .long("window")
+                .short('w')
+                .num_args(1)
+                .default_value("5")
+                .value_parser(value_parser!(usize))
+                .help("Window size"),
+        )
+        .arg(
+            Arg::new("min_containment")
+                .long("min-containment")
+                .num_args(1)
+                .default_value("0.9")
+                .value_parser(value_parser!(f64))
+                .help("Only report queries with at least this much of their content in the db"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let db_file = args.get_one::<String>("db").unwrap();
+
+    let opt_hasher = args.get_one::<String>("hasher").unwrap();
+    let opt_kmer = *args.get_one::<usize>("kmer").unwrap();
+    let opt_window = *args.get_one::<usize>("window").unwrap();
+    let opt_min_containment = *args.get_one::<f64>("min_containment").unwrap();
+
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    //----------------------------
+    // Ops
+    //----------------------------
+    // Build one union set for the whole database: `screen` only needs to know
+    // whether a k-mer occurs *somewhere* in the db, not which record it is in.
+    let mut db_set: HashSet<u64> = HashSet::new();
+
+    let reader = intspan::reader(db_file);
+    let mut fa_in = fasta::io::Reader::new(reader);
+    for result in fa_in.records() {
+        let record = result?;
+        let seq = record.sequence();
+        db_set.extend(minimizer_set(&seq[..], opt_hasher, opt_kmer, opt_window));
+    }
+
+    for infile in args.get_many::<String>("queries").unwrap() {
+        let reader = intspan::reader(infile);
+        let mut fa_in = fasta::io::Reader::new(reader);
+
+        for result in fa_in.records() {
+            let record = result?;
+            let name = String::from_utf8(record.name().into()).unwrap();
+            let seq = record.sequence();
+
+            let q_set = minimizer_set(&seq[..], opt_hasher, opt_kmer, opt_window);
+            if q_set.is_empty() {
+                continue;
+            }
+
+            let inter = q_set.intersection(&db_set).count();
+            let union = q_set.union(&db_set).count();
+
+            let containment = inter as f64 / q_set.len() as f64;
+            let jaccard = inter as f64 / union as f64;
+
+            if containment >= opt_min_containment {
+                writer.write_fmt(format_args!(
+                    "{}\t{}\t{:.4}\t{:.4}\n",
+                    name, db_file, containment, jaccard
+                ))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn minimizer_set(seq: &[u8], opt_hasher: &str, opt_kmer: usize, opt_window: usize) -> HashSet<u64> {
+    let minimizers = match opt_hasher {
+        "fx" => hnsm::JumpingMinimizer {
+            w: opt_window,
+            k: opt_kmer,
+            hasher: hnsm::FxHash,
+        }
+        .minimizer(seq),
+        "murmur" => hnsm::JumpingMinimizer {
+            w: opt_window,
+            k: opt_kmer,
+            hasher: hnsm::MurmurHash3,
+        }
+        .minimizer(seq),
+        _ => unreachable!(),
+    };
+
+    HashSet::from_iter(minimizers.iter().map(|t| t.1))
+}
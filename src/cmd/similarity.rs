@@ -332,3 +332,73 @@ fn d2s(dist: f32) -> f32 {
 fn dis(dist: f32) -> f32 {
     1.0 - dist
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `euclidean_distance`/`dot_product`/`norm`/`jaccard_intersection`/`jaccard_union`
+    // all split their input into `LANES`-sized SIMD chunks via `as_rchunks`, with any
+    // remainder handled by a separate scalar loop over the leading `a_extra`/`b_extra`
+    // elements. Sizes that aren't a multiple of `LANES` (8) are the ones that actually
+    // exercise that scalar tail, so this compares each against a plain scalar
+    // reference implementation for a handful of non-aligned sizes. (There is no
+    // `norm_l2_sq`; the closest real function is `norm`, the L2 norm itself.)
+
+    fn vec_a(n: usize) -> Vec<f32> {
+        (0..n).map(|i| (i as f32) * 0.5 + 1.0).collect()
+    }
+
+    fn vec_b(n: usize) -> Vec<f32> {
+        (0..n).map(|i| ((n - i) as f32) * 0.3 - 0.2).collect()
+    }
+
+    fn scalar_euclidean(a: &[f32], b: &[f32]) -> f32 {
+        std::iter::zip(a, b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>().sqrt()
+    }
+
+    fn scalar_dot(a: &[f32], b: &[f32]) -> f32 {
+        std::iter::zip(a, b).map(|(x, y)| x * y).sum()
+    }
+
+    fn scalar_norm(a: &[f32]) -> f32 {
+        a.iter().map(|x| x * x).sum::<f32>().sqrt()
+    }
+
+    fn scalar_jaccard_intersection(a: &[f32], b: &[f32]) -> f32 {
+        std::iter::zip(a, b).map(|(x, y)| x.min(*y)).sum()
+    }
+
+    fn scalar_jaccard_union(a: &[f32], b: &[f32]) -> f32 {
+        std::iter::zip(a, b).map(|(x, y)| x.max(*y)).sum()
+    }
+
+    #[test]
+    fn simd_matches_scalar_for_non_aligned_sizes() {
+        for n in [7, 9, 15, 17] {
+            let a = vec_a(n);
+            let b = vec_b(n);
+
+            assert!(
+                (euclidean_distance(&a, &b) - scalar_euclidean(&a, &b)).abs() < 1e-4,
+                "euclidean_distance mismatch at n={n}"
+            );
+            assert!(
+                (dot_product(&a, &b) - scalar_dot(&a, &b)).abs() < 1e-3,
+                "dot_product mismatch at n={n}"
+            );
+            assert!(
+                (norm(&a) - scalar_norm(&a)).abs() < 1e-4,
+                "norm mismatch at n={n}"
+            );
+            assert!(
+                (jaccard_intersection(&a, &b) - scalar_jaccard_intersection(&a, &b)).abs() < 1e-4,
+                "jaccard_intersection mismatch at n={n}"
+            );
+            assert!(
+                (jaccard_union(&a, &b) - scalar_jaccard_union(&a, &b)).abs() < 1e-4,
+                "jaccard_union mismatch at n={n}"
+            );
+        }
+    }
+}
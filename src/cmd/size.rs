@@ -5,6 +5,18 @@ use noodles_fasta as fasta;
 pub fn make_subcommand() -> Command {
     Command::new("size")
         .about("Count total bases in FA file(s)")
+        .after_help(
+            r#"
+* --sort buffers all (name, length) pairs before writing them, sorted by
+  length; without it, output streams in input order
+* --top N keeps only the first N records after sorting; it has no effect
+  without --sort
+* .2bit files are detected by their magic number (`0x1A412743`, in either
+  byte order) and read directly via the index's per-record `dnaSize`, no
+  conversion to fasta needed; there is no separate `--twobit` flag to set
+
+"#,
+        )
         .arg(
             Arg::new("infiles")
                 .required(true)
@@ -12,6 +24,29 @@ pub fn make_subcommand() -> Command {
                 .index(1)
                 .help("Set the input file to use"),
         )
+        .arg(
+            Arg::new("total")
+                .long("total")
+                .action(ArgAction::SetTrue)
+                .help("Sum sequence lengths across all input files into a single count"),
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .num_args(1)
+                .value_parser([
+                    builder::PossibleValue::new("asc"),
+                    builder::PossibleValue::new("desc"),
+                ])
+                .help("Sort output by length instead of streaming in input order"),
+        )
+        .arg(
+            Arg::new("top")
+                .long("top")
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .help("Only output the top N records after sorting"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -26,21 +61,66 @@ pub fn make_subcommand() -> Command {
 pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
 
+    let is_total = args.get_flag("total");
+    let opt_sort = args.get_one::<String>("sort").map(|s| s.as_str());
+    let opt_top = args.get_one::<usize>("top").copied();
+    let mut total: usize = 0;
+
+    // Sorting requires seeing every length before writing any of them; the
+    // default (no --sort) stays streaming/unbuffered.
+    let mut sizes: Vec<(String, usize)> = vec![];
+
     for infile in args.get_many::<String>("infiles").unwrap() {
-        let reader = intspan::reader(infile);
-        let mut fa_in = fasta::io::Reader::new(reader);
-
-        for result in fa_in.records() {
-            // obtain record or fail with error
-            let record = result?;
-
-            writer.write_fmt(format_args!(
-                "{}\t{}\n",
-                String::from_utf8(record.name().into()).unwrap(),
-                record.sequence().len()
-            ))?;
+        let mut records: Vec<(String, usize)> = vec![];
+
+        if hnsm::TwoBitReader::is_twobit(infile) {
+            let mut tb = hnsm::TwoBitReader::open(infile)?;
+            for name in tb.names() {
+                let len = tb.seq_size(&name)? as usize;
+                records.push((name, len));
+            }
+        } else {
+            let reader = intspan::reader(infile);
+            let mut fa_in = fasta::io::Reader::new(reader);
+
+            for result in fa_in.records() {
+                // obtain record or fail with error
+                let record = result?;
+                records.push((
+                    String::from_utf8(record.name().into()).unwrap(),
+                    record.sequence().len(),
+                ));
+            }
+        }
+
+        for (name, len) in records {
+            if is_total {
+                total += len;
+            } else if opt_sort.is_some() {
+                sizes.push((name, len));
+            } else {
+                writer.write_fmt(format_args!("{}\t{}\n", name, len))?;
+            }
         }
     }
 
+    if let Some(sort) = opt_sort {
+        match sort {
+            "asc" => sizes.sort_by_key(|(_, len)| *len),
+            "desc" => sizes.sort_by_key(|(_, len)| std::cmp::Reverse(*len)),
+            _ => unreachable!(),
+        }
+        if let Some(top) = opt_top {
+            sizes.truncate(top);
+        }
+        for (name, len) in &sizes {
+            writer.write_fmt(format_args!("{}\t{}\n", name, len))?;
+        }
+    }
+
+    if is_total {
+        writer.write_fmt(format_args!("{}\n", total))?;
+    }
+
     Ok(())
 }
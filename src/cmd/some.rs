@@ -6,6 +6,15 @@ use std::collections::HashSet;
 pub fn make_subcommand() -> Command {
     Command::new("some")
         .about("Extract some FA records")
+        .after_help(
+            r#"
+* --exclude drops listed names from the output regardless of --invert;
+  combine with --strict to warn about exclude names never seen in <infile>
+* <list.txt> also accepts `stdin`/`-`, to pipe a name list in directly; only
+  one of <infile>/<list.txt> may read from stdin at a time
+
+"#,
+        )
         .arg(
             Arg::new("infile")
                 .required(true)
@@ -16,7 +25,7 @@ pub fn make_subcommand() -> Command {
             Arg::new("list.txt")
                 .required(true)
                 .index(2)
-                .help("One name per line"),
+                .help("One name per line; `stdin`/`-` reads the list from stdin"),
         )
         .arg(
             Arg::new("invert")
@@ -25,6 +34,26 @@ pub fn make_subcommand() -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Output sequences not in the list"),
         )
+        .arg(
+            Arg::new("fuzzy")
+                .long("fuzzy")
+                .num_args(0..=1)
+                .default_missing_value("1")
+                .value_parser(value_parser!(usize))
+                .help("Match names within this Levenshtein distance of a list entry"),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .num_args(1)
+                .help("A file of names, one per line, to drop from the output regardless of --invert"),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .action(ArgAction::SetTrue)
+                .help("Warn on stderr about --exclude names never seen in the input"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -39,7 +68,15 @@ pub fn make_subcommand() -> Command {
 pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let is_invert = args.get_flag("invert");
 
-    let reader = intspan::reader(args.get_one::<String>("infile").unwrap());
+    let infile = args.get_one::<String>("infile").unwrap();
+    let list_path = args.get_one::<String>("list.txt").unwrap();
+    if infile == "stdin" && hnsm::is_stdin(list_path) {
+        return Err(anyhow::anyhow!(
+            "<infile> and <list.txt> cannot both read from stdin"
+        ));
+    }
+
+    let reader = intspan::reader(infile);
     let mut fa_in = fasta::io::Reader::new(reader);
 
     let writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
@@ -47,20 +84,59 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         .set_line_base_count(usize::MAX)
         .build_from_writer(writer);
 
-    let set_list: HashSet<String> =
-        intspan::read_first_column(args.get_one::<String>("list.txt").unwrap())
-            .into_iter()
-            .collect();
+    let set_list: HashSet<String> = hnsm::read_name_list(list_path).into_iter().collect();
+
+    let opt_fuzzy = args.get_one::<usize>("fuzzy").copied();
+
+    let is_strict = args.get_flag("strict");
+    let mut opt_exclude = args
+        .get_one::<String>("exclude")
+        .map(|path| hnsm::ExcludeSet::new(path, is_strict));
 
     for result in fa_in.records() {
         // obtain record or fail with error
         let record = result?;
 
         let name = String::from_utf8(record.name().into()).unwrap();
-        if set_list.contains(&name) != is_invert {
+        let matched = match opt_fuzzy {
+            None => set_list.contains(&name),
+            Some(max_dist) => set_list
+                .iter()
+                .any(|wanted| levenshtein(&name, wanted) <= max_dist),
+        };
+        if let Some(exclude) = opt_exclude.as_mut() {
+            if exclude.contains(&name) {
+                continue;
+            }
+        }
+        if matched != is_invert {
             fa_out.write_record(&record)?;
         }
     }
 
+    if let Some(exclude) = &opt_exclude {
+        exclude.warn_unused();
+    }
+
     Ok(())
 }
+
+/// Computes the Levenshtein (edit) distance between two strings.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for (i, &ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
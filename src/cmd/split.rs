@@ -1,8 +1,8 @@
 use clap::*;
+use flate2::write::GzEncoder;
 use noodles_fasta as fasta;
 use std::collections::BTreeMap;
 use std::fs;
-use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
@@ -15,8 +15,14 @@ pub fn make_subcommand() -> Command {
 Modes
 
 * name  - using sequence names as file names
+    * --suffix, --gzip
 * about - about `count` bytes each by record
     * -c, -e, -m
+    * --digits, --name-prefix, --suffix control the generated filenames
+    * --group-by-prefix splits names on a separator first and keeps every
+      record sharing the resulting prefix in the same output file, even if
+      that overruns `-c`; a group bigger than `-c` gets a file to itself,
+      with a warning to stderr
 
 "#,
         )
@@ -70,6 +76,40 @@ Modes
                 .default_value("stdout")
                 .help("Output location. [stdout] for screen"),
         )
+        .arg(
+            Arg::new("digits")
+                .long("digits")
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .help("Zero-padding width of `about`'s file serial numbers. Default is derived from --maxpart"),
+        )
+        .arg(
+            Arg::new("name-prefix")
+                .long("name-prefix")
+                .num_args(1)
+                .default_value("")
+                .help("Prepend this string to `about`'s generated filenames"),
+        )
+        .arg(
+            Arg::new("suffix")
+                .long("suffix")
+                .num_args(1)
+                .default_value(".fa")
+                .help("File extension for the split files"),
+        )
+        .arg(
+            Arg::new("group-by-prefix")
+                .long("group-by-prefix")
+                .num_args(1)
+                .help("With `about`, split names on this separator and never let a shared prefix span two output files"),
+        )
+        .arg(
+            Arg::new("gzip")
+                .long("gzip")
+                .short('z')
+                .action(ArgAction::SetTrue)
+                .help("Compress `name`'s individual files with gzip"),
+        )
 }
 
 // command implementation
@@ -84,7 +124,10 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         fs::create_dir_all(outdir)?;
     }
 
-    let mut fh_of: BTreeMap<String, File> = BTreeMap::new();
+    let suffix = args.get_one::<String>("suffix").unwrap();
+    let is_gzip = args.get_flag("gzip");
+
+    let mut fh_of: BTreeMap<String, Box<dyn Write>> = BTreeMap::new();
 
     //----------------------------
     // Operating
@@ -112,8 +155,8 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
                         .clone()
                         .replace(['(', ')', ':'], "_")
                         .replace("__", "_");
-                    gen_fh(outdir, &mut fh_of, &filename)?;
-                    write!(fh_of.get(&filename).unwrap(), ">{}\n{}\n", name, seq_str)?;
+                    gen_fh(outdir, &mut fh_of, &filename, suffix, is_gzip)?;
+                    write!(fh_of.get_mut(&filename).unwrap(), ">{}\n{}\n", name, seq_str)?;
                 }
             }
         }
@@ -125,11 +168,81 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         };
         let is_even = args.get_flag("even");
         let opt_maxpart = *args.get_one::<usize>("maxpart").unwrap();
+        let opt_digits = args.get_one::<usize>("digits").copied();
+        let name_prefix = args.get_one::<String>("name-prefix").unwrap();
+        let opt_group_sep = args.get_one::<String>("group-by-prefix").map(|s| s.as_str());
+
+        let part_width =
+            opt_digits.unwrap_or((opt_maxpart.checked_ilog10().unwrap_or(0) + 1) as usize);
+
+        if let Some(sep) = opt_group_sep {
+            // Grouping must see every record before packing, so buffer them
+            // all up front, keyed by prefix in order of first appearance.
+            let mut group_order: Vec<String> = vec![];
+            let mut groups: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+            let mut first_seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+            for infile in args.get_many::<String>("infiles").unwrap() {
+                let reader = intspan::reader(infile);
+                let mut fa_in = fasta::io::Reader::new(reader);
+
+                for result in fa_in.records() {
+                    let record = result?;
+                    let name = String::from_utf8(record.name().into()).unwrap();
+                    let seq = record.sequence();
+                    let seq_str = String::from_utf8(seq.get(..).unwrap().to_vec()).unwrap();
+
+                    let prefix = name.split(sep).next().unwrap_or(&name).to_string();
+                    if first_seen.insert(prefix.clone()) {
+                        group_order.push(prefix.clone());
+                    }
+                    groups.entry(prefix).or_default().push((name, seq_str));
+                }
+            }
+
+            let mut cur_cnt = 0usize;
+            let mut file_sn = 0usize;
+            for prefix in &group_order {
+                if file_sn > opt_maxpart {
+                    break;
+                }
+
+                let records = groups.get(prefix).unwrap();
+                let group_size: usize = records.iter().map(|(_, s)| s.len()).sum();
+                if cur_cnt > 0 && cur_cnt + group_size > opt_count {
+                    file_sn += 1;
+                    cur_cnt = 0;
+                }
+                if group_size > opt_count {
+                    eprintln!(
+                        "==> group `{prefix}` is {group_size} bp, over --count {opt_count} bp; giving it its own file"
+                    );
+                }
+
+                for (name, seq_str) in records {
+                    if outdir == "stdout" {
+                        print!(">{}\n{}\n", name, seq_str);
+                    } else {
+                        let filename =
+                            format!("{}{:0width$}", name_prefix, file_sn, width = part_width);
+                        gen_fh(outdir, &mut fh_of, &filename, suffix, is_gzip)?;
+                        write!(fh_of.get_mut(&filename).unwrap(), ">{}\n{}\n", name, seq_str)?;
+                    }
+                }
+                cur_cnt += group_size;
+
+                if group_size > opt_count {
+                    file_sn += 1;
+                    cur_cnt = 0;
+                }
+            } // group
+
+            return Ok(());
+        }
 
         let mut cur_cnt = 0;
         let mut record_sn = 0;
         let mut file_sn = 0;
-        let part_width = (opt_maxpart.checked_ilog10().unwrap_or(0) + 1) as usize;
 
         'outer: for infile in args.get_many::<String>("infiles").unwrap() {
             let reader = intspan::reader(infile);
@@ -154,9 +267,9 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
                 if outdir == "stdout" {
                     print!(">{}\n{}\n", name, seq_str);
                 } else {
-                    let filename = format!("{:0width$}", file_sn, width = part_width);
-                    gen_fh(outdir, &mut fh_of, &filename)?;
-                    write!(fh_of.get(&filename).unwrap(), ">{}\n{}\n", name, seq_str)?;
+                    let filename = format!("{}{:0width$}", name_prefix, file_sn, width = part_width);
+                    gen_fh(outdir, &mut fh_of, &filename, suffix, is_gzip)?;
+                    write!(fh_of.get_mut(&filename).unwrap(), ">{}\n{}\n", name, seq_str)?;
                 }
                 cur_cnt += seq.len();
                 record_sn += 1;
@@ -179,17 +292,30 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
 
 fn gen_fh(
     outdir: &String,
-    fh_of: &mut BTreeMap<String, File>,
+    fh_of: &mut BTreeMap<String, Box<dyn Write>>,
     filename: &String,
+    suffix: &str,
+    is_gzip: bool,
 ) -> Result<(), Error> {
     if !fh_of.contains_key(filename) {
-        let path = Path::new(outdir).join(filename.clone() + ".fa");
+        let ext = if is_gzip && !suffix.ends_with(".gz") {
+            format!("{}.gz", suffix)
+        } else {
+            suffix.to_string()
+        };
+        let path = Path::new(outdir).join(filename.clone() + &ext);
         let file = fs::OpenOptions::new()
             .create(true)
             .write(true)
             .truncate(true)
             .open(path)?;
-        fh_of.insert(filename.clone(), file);
+
+        let writer: Box<dyn Write> = if is_gzip {
+            Box::new(GzEncoder::new(file, flate2::Compression::default()))
+        } else {
+            Box::new(file)
+        };
+        fh_of.insert(filename.clone(), writer);
     }
     Ok(())
 }
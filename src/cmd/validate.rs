@@ -0,0 +1,215 @@
+use clap::*;
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("validate")
+        .about("Streaming validation of FA/FQ file(s)")
+        .after_help(
+            r###"
+* Checks, reported with the 1-based line number of the offending record:
+    * duplicate sequence names (across all <infiles>)
+    * empty sequences
+    * non-IUPAC characters in the sequence
+    * FASTQ records whose sequence and quality strings differ in length
+* Exits non-zero if any issue was found, unless --warn-only is set
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input file(s) to use"),
+        )
+        .arg(
+            Arg::new("warn_only")
+                .long("warn-only")
+                .action(ArgAction::SetTrue)
+                .help("Report issues but always exit 0"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    let is_warn_only = args.get_flag("warn_only");
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    let mut seen_names: HashSet<String> = HashSet::new();
+    let mut issue_count = 0usize;
+
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        if hnsm::is_fq(infile) {
+            validate_fq(infile, &mut seen_names, &mut issue_count, &mut *writer)?;
+        } else {
+            validate_fa(infile, &mut seen_names, &mut issue_count, &mut *writer)?;
+        }
+    }
+
+    writer.write_fmt(format_args!("{} issue(s) found\n", issue_count))?;
+
+    if issue_count > 0 && !is_warn_only {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn report(
+    writer: &mut dyn Write,
+    issue_count: &mut usize,
+    infile: &str,
+    line_number: usize,
+    name: &str,
+    message: &str,
+) -> anyhow::Result<()> {
+    *issue_count += 1;
+    writer.write_fmt(format_args!(
+        "{}:{}\t{}\t{}\n",
+        infile, line_number, name, message
+    ))?;
+    Ok(())
+}
+
+fn check_seq(
+    writer: &mut dyn Write,
+    issue_count: &mut usize,
+    infile: &str,
+    line_number: usize,
+    name: &str,
+    seq: &str,
+) -> anyhow::Result<()> {
+    if seq.is_empty() {
+        report(writer, issue_count, infile, line_number, name, "empty sequence")?;
+    }
+    for &nt in seq.as_bytes() {
+        if matches!(hnsm::to_nt(nt), hnsm::Nt::Invalid) && nt != b'-' {
+            report(
+                writer,
+                issue_count,
+                infile,
+                line_number,
+                name,
+                &format!("non-IUPAC character '{}'", nt as char),
+            )?;
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn validate_fa(
+    infile: &str,
+    seen_names: &mut HashSet<String>,
+    issue_count: &mut usize,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let reader = intspan::reader(infile);
+
+    let mut cur_name: Option<String> = None;
+    let mut cur_header_line = 0usize;
+    let mut cur_seq = String::new();
+    let mut line_number = 0usize;
+
+    macro_rules! flush {
+        () => {
+            if let Some(name) = cur_name.take() {
+                if !seen_names.insert(name.clone()) {
+                    report(
+                        writer,
+                        issue_count,
+                        infile,
+                        cur_header_line,
+                        &name,
+                        "duplicate name",
+                    )?;
+                }
+                check_seq(writer, issue_count, infile, cur_header_line, &name, &cur_seq)?;
+                cur_seq.clear();
+            }
+        };
+    }
+
+    for line in reader.lines() {
+        let line = line?;
+        line_number += 1;
+
+        if let Some(header) = line.strip_prefix('>') {
+            flush!();
+            cur_name = Some(header.split_whitespace().next().unwrap_or("").to_string());
+            cur_header_line = line_number;
+        } else {
+            cur_seq.push_str(line.trim_end());
+        }
+    }
+    flush!();
+
+    Ok(())
+}
+
+fn validate_fq(
+    infile: &str,
+    seen_names: &mut HashSet<String>,
+    issue_count: &mut usize,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let reader = intspan::reader(infile);
+    let mut lines = reader.lines();
+    let mut line_number = 0usize;
+
+    loop {
+        let header = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        line_number += 1;
+        let header_line = line_number;
+
+        let seq = lines
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("{}: truncated FASTQ record", infile))??;
+        line_number += 1;
+        let _plus = lines
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("{}: truncated FASTQ record", infile))??;
+        line_number += 1;
+        let qual = lines
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("{}: truncated FASTQ record", infile))??;
+        line_number += 1;
+
+        let name = header
+            .trim_start_matches('@')
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .to_string();
+
+        if !seen_names.insert(name.clone()) {
+            report(writer, issue_count, infile, header_line, &name, "duplicate name")?;
+        }
+        check_seq(writer, issue_count, infile, header_line, &name, &seq)?;
+        if seq.len() != qual.len() {
+            report(
+                writer,
+                issue_count,
+                infile,
+                header_line,
+                &name,
+                "sequence/quality length mismatch",
+            )?;
+        }
+    }
+
+    Ok(())
+}
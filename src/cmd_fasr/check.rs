@@ -57,9 +57,10 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     // Operating
     //----------------------------
     for infile in args.get_many::<String>("infiles").unwrap() {
-        let mut reader = intspan::reader(infile);
+        let reader = intspan::reader(infile);
 
-        while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
             let block_names = block.names;
 
             if args.contains_id("name") {
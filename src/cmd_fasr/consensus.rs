@@ -16,6 +16,12 @@ pub fn make_subcommand() -> Command {
 * Running in parallel mode with 1 reader, 1 writer and the corresponding number of workers
     * The order of output may be different from the original
 
+* `--method majority` computes the plain majority base per column instead of running
+  `spoa`, so it needs no external binary. Ties among bases are broken by emitting the
+  IUPAC ambiguity code for the tied set. `--gap-char` (default `-`) is the character
+  written when gaps are the majority in a column; it is otherwise treated like any
+  other symbol competing for the majority
+
 "###,
         )
         .arg(
@@ -25,6 +31,24 @@ pub fn make_subcommand() -> Command {
                 .index(1)
                 .help("Set the input files to use"),
         )
+        .arg(
+            Arg::new("method")
+                .long("method")
+                .num_args(1)
+                .value_parser([
+                    builder::PossibleValue::new("poa"),
+                    builder::PossibleValue::new("majority"),
+                ])
+                .default_value("poa")
+                .help("Consensus method"),
+        )
+        .arg(
+            Arg::new("gap_char")
+                .long("gap-char")
+                .num_args(1)
+                .default_value("-")
+                .help("With --method majority, the character emitted when gaps are the majority in a column"),
+        )
         .arg(
             Arg::new("cname")
                 .long("cname")
@@ -71,8 +95,9 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
 
         for infile in args.get_many::<String>("infiles").unwrap() {
-            let mut reader = intspan::reader(infile);
-            while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+            let reader = intspan::reader(infile);
+            for result in hnsm::FasBlockReader::new(reader) {
+                let block = result?;
                 let out_string = proc_block(&block, args)?;
                 writer.write_all(out_string.as_ref())?;
             }
@@ -90,6 +115,8 @@ fn proc_block(block: &hnsm::FasBlock, args: &ArgMatches) -> anyhow::Result<Strin
     //----------------------------
     let cname = args.get_one::<String>("cname").unwrap();
     let has_outgroup = args.get_flag("has_outgroup");
+    let method = args.get_one::<String>("method").unwrap();
+    let gap_char = *args.get_one::<String>("gap_char").unwrap().as_bytes().first().unwrap_or(&b'-');
 
     //----------------------------
     // Operating
@@ -109,8 +136,14 @@ fn proc_block(block: &hnsm::FasBlock, args: &ArgMatches) -> anyhow::Result<Strin
         seqs.pop().unwrap();
     }
 
-    let mut cons = hnsm::get_consensus_poa(&seqs).unwrap();
-    cons = cons.replace('-', "");
+    let cons = match method.as_str() {
+        "majority" => majority_consensus(&seqs, gap_char),
+        _ => {
+            let mut c = hnsm::get_consensus_poa(&seqs).unwrap();
+            c = c.replace('-', "");
+            c
+        }
+    };
 
     let mut range = block.entries.first().unwrap().range().clone();
 
@@ -134,6 +167,64 @@ fn proc_block(block: &hnsm::FasBlock, args: &ArgMatches) -> anyhow::Result<Strin
     Ok(out_string)
 }
 
+/// Computes a plain majority-vote consensus over a set of same-length aligned
+/// sequences, one column at a time via [`consensus_column`].
+fn majority_consensus(seqs: &[&[u8]], gap_char: u8) -> String {
+    if seqs.is_empty() {
+        return String::new();
+    }
+    let len = seqs[0].len();
+
+    let mut out = Vec::with_capacity(len);
+    for col in 0..len {
+        let bases: Vec<u8> = seqs.iter().map(|s| s[col]).collect();
+        out.push(consensus_column(&bases, gap_char));
+    }
+
+    String::from_utf8(out).unwrap()
+}
+
+/// Picks the majority symbol of one alignment column: `gap_char` if gaps make
+/// up half or more of the column (the majority-gap rule), else the most
+/// common of `A`/`C`/`G`/`T`, breaking ties by the IUPAC ambiguity code for
+/// the tied bases (see [`hnsm::iupac_code`]).
+fn consensus_column(column: &[u8], gap_char: u8) -> u8 {
+    let gaps = column.iter().filter(|&&b| b == gap_char).count();
+    if gaps * 2 >= column.len() {
+        return gap_char;
+    }
+
+    let bases = [b'A', b'C', b'G', b'T'];
+    let mut counts = [0usize; 4];
+    for &b in column {
+        match b.to_ascii_uppercase() {
+            b'A' => counts[0] += 1,
+            b'C' => counts[1] += 1,
+            b'G' => counts[2] += 1,
+            b'T' | b'U' => counts[3] += 1,
+            _ => {}
+        }
+    }
+
+    let max = *counts.iter().max().unwrap();
+    if max == 0 {
+        return b'N';
+    }
+
+    let winners: Vec<u8> = bases
+        .iter()
+        .zip(counts.iter())
+        .filter(|(_, &c)| c == max)
+        .map(|(&b, _)| b)
+        .collect();
+
+    if winners.len() == 1 {
+        winners[0]
+    } else {
+        hnsm::iupac_code(&winners)
+    }
+}
+
 // Adopt from https://rust-lang-nursery.github.io/rust-cookbook/concurrency/threads.html#create-a-parallel-pipeline
 fn proc_block_p(args: &ArgMatches) -> anyhow::Result<()> {
     let parallel = *args.get_one::<usize>("parallel").unwrap();
@@ -150,8 +241,9 @@ fn proc_block_p(args: &ArgMatches) -> anyhow::Result<()> {
         //----------------------------
         s.spawn(|_| {
             for infile in args.get_many::<String>("infiles").unwrap() {
-                let mut reader = intspan::reader(infile);
-                while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+                let reader = intspan::reader(infile);
+                for result in hnsm::FasBlockReader::new(reader) {
+                    let block = result.unwrap();
                     snd1.send(block).unwrap();
                 }
             }
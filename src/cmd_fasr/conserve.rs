@@ -0,0 +1,146 @@
+use clap::*;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("conserve")
+        .about("Compute a per-column conservation score for block fasta")
+        .after_help(
+            r###"
+* <infiles> are paths to block fasta files, .fas.gz is supported
+    * infile == stdin means reading from STDIN
+
+* For each alignment column, `entropy` is the Shannon entropy (in bits) of the
+  base composition across all sequences in the block; gaps (`-`/`.`) and
+  non-ACGT characters are excluded from the count. A fully conserved column
+  scores 0.0; a column split evenly among all 4 bases scores 2.0
+
+* `--window W` reports the mean entropy over a centered window of W columns
+  instead of the raw per-column value, which smooths the signal for
+  visualizing conserved stretches; W must be odd
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input files to use"),
+        )
+        .arg(
+            Arg::new("window")
+                .long("window")
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .help("Smooth entropy over a centered window of this many columns"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+fn column_entropy(column: &[u8]) -> f64 {
+    let mut counts = [0usize; 4];
+    let mut total = 0usize;
+    for &b in column {
+        let idx = match b.to_ascii_uppercase() {
+            b'A' => 0,
+            b'C' => 1,
+            b'G' => 2,
+            b'T' => 3,
+            _ => continue,
+        };
+        counts[idx] += 1;
+        total += 1;
+    }
+
+    if total == 0 {
+        return 0.0;
+    }
+
+    let mut entropy = 0.0;
+    for &count in &counts {
+        if count == 0 {
+            continue;
+        }
+        let p = count as f64 / total as f64;
+        entropy -= p * p.log2();
+    }
+    entropy
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+    let opt_window = args.get_one::<usize>("window").copied();
+    if let Some(window) = opt_window {
+        if window % 2 == 0 {
+            return Err(anyhow::anyhow!("--window must be an odd number"));
+        }
+    }
+
+    let field_names = vec!["#target", "chr", "chr_pos", "column", "entropy"];
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    writer.write_all(format!("{}\n", field_names.join("\t")).as_ref())?;
+
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
+            let mut seqs: Vec<&[u8]> = vec![];
+            for entry in &block.entries {
+                seqs.push(entry.seq().as_ref());
+            }
+
+            let trange = block.entries.first().unwrap().range().clone();
+            let t_ints_seq = hnsm::seq_intspan(block.entries.first().unwrap().seq());
+            let length = seqs[0].len();
+
+            let entropies: Vec<f64> = (0..length)
+                .map(|pos| {
+                    let column: Vec<u8> = seqs.iter().map(|seq| seq[pos]).collect();
+                    column_entropy(&column)
+                })
+                .collect();
+
+            for pos in 0..length {
+                let column = (pos + 1) as i32;
+
+                let score = match opt_window {
+                    None => entropies[pos],
+                    Some(window) => {
+                        let half = window / 2;
+                        let lo = pos.saturating_sub(half);
+                        let hi = (pos + half + 1).min(length);
+                        let slice = &entropies[lo..hi];
+                        slice.iter().sum::<f64>() / slice.len() as f64
+                    }
+                };
+
+                let chr = trange.chr();
+                let chr_pos =
+                    hnsm::align_to_chr(&t_ints_seq, column, trange.start, trange.strand())
+                        .unwrap();
+
+                writer.write_all(
+                    format!("{}\t{}\t{}\t{}\t{:.4}\n", trange, chr, chr_pos, column, score)
+                        .as_ref(),
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
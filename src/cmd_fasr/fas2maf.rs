@@ -0,0 +1,89 @@
+use clap::*;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("fas2maf")
+        .about("Convert block fasta to maf")
+        .after_help(
+            r###"
+* <infiles> are paths to block fasta files, .fas.gz is supported
+    * infile == stdin means reading from STDIN
+
+* We need the chr.sizes file because without it we cannot compute the MAF
+  start coordinate on the `-` strand
+    * Each line is `name.chr\tsize`, where `name.chr` is the range's species
+      name and chromosome joined with a dot, matching the MAF `src` field
+
+* --min-seqs skips blocks with fewer than this many sequences
+
+"###,
+        )
+        .arg(
+            Arg::new("chr.sizes")
+                .required(true)
+                .index(1)
+                .num_args(1)
+                .help("The path to the chr.sizes file"),
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(2)
+                .help("Set the input files to use"),
+        )
+        .arg(
+            Arg::new("min_seqs")
+                .long("min-seqs")
+                .num_args(1)
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("Skip blocks with fewer than this many sequences"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    let sizes = intspan::read_sizes(args.get_one::<String>("chr.sizes").unwrap());
+    let opt_min_seqs = *args.get_one::<usize>("min_seqs").unwrap();
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    writer.write_all("##maf version=1\n".as_ref())?;
+
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
+            if block.entries.len() < opt_min_seqs {
+                continue;
+            }
+
+            writer.write_all("a score=0\n".as_ref())?;
+            for entry in &block.entries {
+                let maf_entry = hnsm::fas_entry_to_maf(entry, &sizes)?;
+                writer.write_fmt(format_args!("{}\n", maf_entry))?;
+            }
+
+            // end of a block
+            writer.write_all("\n".as_ref())?;
+        }
+    }
+
+    Ok(())
+}
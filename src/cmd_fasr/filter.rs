@@ -11,6 +11,13 @@ pub fn make_subcommand() -> Command {
 
 * If `--name` is not specified, it defaults to the first one in each block
 
+* `--codon` degaps a block to codon boundaries: columns where every sequence
+  has a gap are dropped first, then each sequence is truncated from the end
+  to a multiple of 3, so every output sequence in the block has the same
+  length and it's divisible by 3. Combine with `--mask-stops` to replace
+  internal (non-terminal) stop codons with `NNN`, e.g. for downstream dN/dS
+  tools that choke on premature stops
+
 "###,
         )
         .arg(
@@ -58,6 +65,18 @@ pub fn make_subcommand() -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Remove dashes '-'"),
         )
+        .arg(
+            Arg::new("codon")
+                .long("codon")
+                .action(ArgAction::SetTrue)
+                .help("Degap to codon boundaries, dropping all-gap columns and trimming incomplete terminal codons"),
+        )
+        .arg(
+            Arg::new("mask_stops")
+                .long("mask-stops")
+                .action(ArgAction::SetTrue)
+                .help("Mask internal stop codons to NNN; requires --codon"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -77,14 +96,17 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let is_upper = args.get_flag("upper");
     // let is_n = args.get_flag("N");
     let is_dash = args.get_flag("dash");
+    let is_codon = args.get_flag("codon");
+    let is_mask_stops = args.get_flag("mask_stops");
 
     //----------------------------
     // Operating
     //----------------------------
     for infile in args.get_many::<String>("infiles").unwrap() {
-        let mut reader = intspan::reader(infile);
+        let reader = intspan::reader(infile);
 
-        'BLOCK: while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+        'BLOCK: for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
             // --name
             let idx = if args.contains_id("name") {
                 let name = args.get_one::<String>("name").unwrap();
@@ -114,15 +136,26 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
                 }
             }
 
-            for entry in &block.entries {
-                let mut out_seq: Vec<u8> = vec![];
+            // --codon: drop all-gap columns, then trim to a common multiple of 3
+            let codon_seqs = if is_codon {
+                Some(codon_degap(&block.entries, is_mask_stops))
+            } else {
+                None
+            };
 
-                for char in entry.seq() {
-                    if is_dash && *char == b'-' {
-                        continue;
+            for (i, entry) in block.entries.iter().enumerate() {
+                let mut out_seq: Vec<u8> = if let Some(seqs) = &codon_seqs {
+                    seqs[i].clone()
+                } else {
+                    let mut seq = vec![];
+                    for char in entry.seq() {
+                        if is_dash && *char == b'-' {
+                            continue;
+                        }
+                        seq.push(*char);
                     }
-                    out_seq.push(*char);
-                }
+                    seq
+                };
 
                 let out_seq = if is_upper {
                     out_seq.to_ascii_uppercase()
@@ -144,3 +177,51 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Degaps a block to codon boundaries: columns where every entry has a gap
+/// are dropped, then every sequence is trimmed from the end to the largest
+/// shared multiple of 3. Returns one output sequence per entry, in the same
+/// order as `entries`. When `mask_stops` is set, internal (non-terminal)
+/// stop codons are replaced with `NNN`.
+fn codon_degap(entries: &[hnsm::FasEntry], mask_stops: bool) -> Vec<Vec<u8>> {
+    let length = entries[0].seq().len();
+
+    let keep: Vec<bool> = (0..length)
+        .map(|pos| entries.iter().any(|entry| entry.seq()[pos] != b'-'))
+        .collect();
+
+    let mut degapped: Vec<Vec<u8>> = entries
+        .iter()
+        .map(|entry| {
+            entry
+                .seq()
+                .iter()
+                .enumerate()
+                .filter(|(pos, _)| keep[*pos])
+                .map(|(_, &nt)| nt)
+                .collect()
+        })
+        .collect();
+
+    let common_len = degapped.iter().map(|seq| seq.len()).min().unwrap_or(0);
+    let codon_len = common_len - common_len % 3;
+    for seq in &mut degapped {
+        seq.truncate(codon_len);
+    }
+
+    if mask_stops {
+        for seq in &mut degapped {
+            let codon_count = seq.len() / 3;
+            for i in 0..codon_count.saturating_sub(1) {
+                let triplet = &seq[i * 3..i * 3 + 3];
+                if hnsm::translate(triplet) == "*" {
+                    seq[i * 3] = b'N';
+                    seq[i * 3 + 1] = b'N';
+                    seq[i * 3 + 2] = b'N';
+                }
+            }
+        }
+    }
+
+    degapped
+}
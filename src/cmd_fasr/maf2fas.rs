@@ -9,6 +9,8 @@ pub fn make_subcommand() -> Command {
 * <infiles> are paths to maf files, .maf.gz is supported
     * infile == stdin means reading from STDIN
 
+* --min-seqs skips blocks with fewer than this many sequences
+
 "###,
         )
         .arg(
@@ -18,6 +20,14 @@ pub fn make_subcommand() -> Command {
                 .index(1)
                 .help("Set the input files to use"),
         )
+        .arg(
+            Arg::new("min_seqs")
+                .long("min-seqs")
+                .num_args(1)
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("Skip blocks with fewer than this many sequences"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -34,6 +44,7 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     // Args
     //----------------------------
     let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+    let opt_min_seqs = *args.get_one::<usize>("min_seqs").unwrap();
 
     //----------------------------
     // Operating
@@ -42,6 +53,10 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         let mut reader = intspan::reader(infile);
 
         while let Ok(block) = hnsm::next_maf_block(&mut reader) {
+            if block.entries.len() < opt_min_seqs {
+                continue;
+            }
+
             // Can't use reference as entry.alignment does not Copy
             for entry in block.entries {
                 let range = entry.to_range();
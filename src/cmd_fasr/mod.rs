@@ -3,9 +3,11 @@
 pub mod axt2fas;
 pub mod check;
 pub mod concat;
+pub mod conserve;
 pub mod consensus;
 pub mod cover;
 pub mod create;
+pub mod fas2maf;
 pub mod filter;
 pub mod join;
 pub mod link;
@@ -16,8 +18,11 @@ pub mod refine;
 pub mod replace;
 pub mod separate;
 pub mod slice;
+pub mod snp;
 pub mod split;
 pub mod stat;
 pub mod subset;
+pub mod trim;
 pub mod variation;
+pub mod vcf;
 pub mod xlsx;
@@ -108,8 +108,9 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
 
         for infile in args.get_many::<String>("infiles").unwrap() {
-            let mut reader = intspan::reader(infile);
-            while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+            let reader = intspan::reader(infile);
+            for result in hnsm::FasBlockReader::new(reader) {
+                let block = result?;
                 let out_string = proc_block(&block, args)?;
                 writer.write_all(out_string.as_ref())?;
             }
@@ -199,8 +200,9 @@ fn proc_block_p(args: &ArgMatches) -> anyhow::Result<()> {
         //----------------------------
         s.spawn(|_| {
             for infile in args.get_many::<String>("infiles").unwrap() {
-                let mut reader = intspan::reader(infile);
-                while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+                let reader = intspan::reader(infile);
+                for result in hnsm::FasBlockReader::new(reader) {
+                    let block = result.unwrap();
                     snd1.send(block).unwrap();
                 }
             }
@@ -72,9 +72,10 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     // Operating
     //----------------------------
     for infile in args.get_many::<String>("infiles").unwrap() {
-        let mut reader = intspan::reader(infile);
+        let reader = intspan::reader(infile);
 
-        while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
             let originals = block.headers.clone();
 
             let matched: Vec<String> = replace_of
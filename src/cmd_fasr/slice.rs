@@ -11,13 +11,17 @@ pub fn make_subcommand() -> Command {
 * <infiles> are paths to block fasta files, .fas.gz is supported
     * infile == stdin means reading from STDIN
 
+* `--range start-end` slices by a plain reference range instead of a runlist.json,
+  applied against each block's own chromosome; a range landing inside a gap of the
+  reference sequence is snapped to the nearest real column, with a warning on stderr
+
 "###,
         )
         .arg(
             Arg::new("runlist.json")
-                .required(true)
                 .index(1)
                 .num_args(1)
+                .required_unless_present("range")
                 .help("Set the runlist file to use"),
         )
         .arg(
@@ -30,9 +34,17 @@ pub fn make_subcommand() -> Command {
         .arg(
             Arg::new("name")
                 .long("name")
+                .visible_alias("ref")
                 .num_args(1)
                 .help("According to this species. Default is the first one"),
         )
+        .arg(
+            Arg::new("range")
+                .long("range")
+                .num_args(1)
+                .conflicts_with("runlist.json")
+                .help("A `start-end` range on the reference, in place of a runlist file"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -50,8 +62,19 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     //----------------------------
     let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
 
-    let json = intspan::read_json(args.get_one::<String>("runlist.json").unwrap());
-    let set = intspan::json2set(&json);
+    let opt_range: Option<(i32, i32)> = args.get_one::<String>("range").map(|s| {
+        let mut parts = s.splitn(2, '-');
+        let start = parts.next().unwrap().parse().unwrap();
+        let end = parts.next().unwrap().parse().unwrap();
+        (start, end)
+    });
+
+    let set = if opt_range.is_none() {
+        let json = intspan::read_json(args.get_one::<String>("runlist.json").unwrap());
+        Some(intspan::json2set(&json))
+    } else {
+        None
+    };
 
     let mut name = if args.contains_id("name") {
         args.get_one::<String>("name").unwrap().to_string()
@@ -63,9 +86,10 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     // Operating
     //----------------------------
     for infile in args.get_many::<String>("infiles").unwrap() {
-        let mut reader = intspan::reader(infile);
+        let reader = intspan::reader(infile);
 
-        while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
             // the first name of the first block
             if name.is_empty() {
                 name = block.names.first().unwrap().to_string();
@@ -77,16 +101,25 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
             }
             let trange = block.entries.get(idx.unwrap()).unwrap().range().clone();
 
-            // chr present
-            if !set.contains_key(trange.chr()) {
-                continue;
-            }
-            if set.get(trange.chr()).unwrap().is_empty() {
-                continue;
-            }
+            // has intersect, either against the runlist.json or a plain `--range start-end`
+            // synthesized as a single-span IntSpan for every chromosome
+            let range_ints;
+            let chr_ints = match &set {
+                Some(set) => {
+                    if !set.contains_key(trange.chr()) || set.get(trange.chr()).unwrap().is_empty()
+                    {
+                        continue;
+                    }
+                    set.get(trange.chr()).unwrap()
+                }
+                None => {
+                    let (start, end) = opt_range.unwrap();
+                    range_ints = intspan::IntSpan::from_pair(start, end);
+                    &range_ints
+                }
+            };
 
-            // has intersect
-            let i_ints_chr = trange.intspan().intersect(set.get(trange.chr()).unwrap());
+            let i_ints_chr = trange.intspan().intersect(chr_ints);
             if i_ints_chr.is_empty() {
                 continue;
             }
@@ -117,11 +150,18 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
                 }
                 let mut ss_ints = intspan::IntSpan::from_pair(ss_start, ss_end);
 
-                // borders of subslice inside an indel
+                // borders of subslice inside an indel; snap to the nearest real column
                 for n in [ss_start, ss_end] {
                     if indel_ints.contains(n) {
                         let island = indel_ints.find_islands_n(n);
                         ss_ints.subtract(&island);
+                        eprintln!(
+                            "fasr slice: `{}` column {} falls inside a gap; snapped to {}-{}",
+                            trange.chr(),
+                            n,
+                            ss_ints.min(),
+                            ss_ints.max()
+                        );
                     }
                 }
                 sub_slices.push(ss_ints);
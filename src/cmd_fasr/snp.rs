@@ -0,0 +1,179 @@
+use clap::*;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("snp")
+        .about("Extract a SNP matrix (samples x positions) from block fasta files")
+        .after_help(
+            r###"
+* <infiles> are paths to block fasta files, .fas.gz is supported
+    * infile == stdin means reading from STDIN
+
+* Output is a TSV: the header row lists sample names (and `obase` when
+  `--outgroup` is set), each following row is one variant position with the
+  base of every sample at that position
+
+* `--nocomplex` and `--nosingle` mirror the `tsv-filter -H --ne freq:-1` /
+  `tsv-filter -H --ne freq:1` idioms documented for `fasr variation`, applied
+  in-process instead of via an external pipe
+
+* `--min` and `--max` bound `freq`, the count of samples carrying the minor
+  allele; they have no effect on complex (`freq == -1`) sites, which
+  `--nocomplex` handles separately
+
+* `--outgroups N` treats the last N sequences of each block as outgroups
+  instead of just the last one; the ancestral `obase` is then the base
+  agreed on by a majority of them, with ties left unpolarized. Implies
+  `--outgroup`
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input files to use"),
+        )
+        .arg(
+            Arg::new("has_outgroup")
+                .long("outgroup")
+                .action(ArgAction::SetTrue)
+                .help("There are outgroups at the end of each block"),
+        )
+        .arg(
+            Arg::new("outgroups")
+                .long("outgroups")
+                .value_parser(value_parser!(usize))
+                .num_args(1)
+                .default_value("1")
+                .help("Number of outgroups at the end of each block, polarized by majority rule"),
+        )
+        .arg(
+            Arg::new("min")
+                .long("min")
+                .value_parser(value_parser!(i32))
+                .num_args(1)
+                .help("Minimum freq of the minor allele"),
+        )
+        .arg(
+            Arg::new("max")
+                .long("max")
+                .value_parser(value_parser!(i32))
+                .num_args(1)
+                .help("Maximum freq of the minor allele"),
+        )
+        .arg(
+            Arg::new("nosingle")
+                .long("nosingle")
+                .action(ArgAction::SetTrue)
+                .help("Ignore singleton variations, i.e. freq == 1"),
+        )
+        .arg(
+            Arg::new("nocomplex")
+                .long("nocomplex")
+                .action(ArgAction::SetTrue)
+                .help("Ignore complex variations, i.e. freq == -1"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+    let has_outgroup = args.get_flag("has_outgroup");
+    let outgroup_count = args.get_one::<usize>("outgroups").copied().unwrap_or(1).max(1);
+    let min = args.get_one::<i32>("min").copied();
+    let max = args.get_one::<i32>("max").copied();
+    let nosingle = args.get_flag("nosingle");
+    let nocomplex = args.get_flag("nocomplex");
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    let mut wrote_header = false;
+
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
+            let mut seqs: Vec<&[u8]> = vec![];
+            for entry in &block.entries {
+                seqs.push(entry.seq().as_ref());
+            }
+
+            let seq_count = seqs.len();
+            let names: Vec<String> = if has_outgroup {
+                block.entries[..(seq_count - outgroup_count)]
+                    .iter()
+                    .map(|entry| entry.range().name().to_string())
+                    .collect()
+            } else {
+                block
+                    .entries
+                    .iter()
+                    .map(|entry| entry.range().name().to_string())
+                    .collect()
+            };
+
+            if !wrote_header {
+                let mut field_names = vec!["#pos".to_string()];
+                field_names.extend(names.iter().cloned());
+                if has_outgroup {
+                    field_names.push("obase".to_string());
+                }
+                writer.write_all(format!("{}\n", field_names.join("\t")).as_ref())?;
+                wrote_header = true;
+            }
+
+            let subs = if has_outgroup {
+                let mut unpolarized = hnsm::get_subs(&seqs[..(seq_count - outgroup_count)]).unwrap();
+                hnsm::polarize_subs_multi(&mut unpolarized, &seqs[(seq_count - outgroup_count)..]);
+                unpolarized
+            } else {
+                hnsm::get_subs(&seqs).unwrap()
+            };
+
+            for s in subs {
+                if nocomplex && s.freq == -1 {
+                    continue;
+                }
+                if nosingle && s.freq == 1 {
+                    continue;
+                }
+                if s.freq >= 0 {
+                    if let Some(min) = min {
+                        if s.freq < min {
+                            continue;
+                        }
+                    }
+                    if let Some(max) = max {
+                        if s.freq > max {
+                            continue;
+                        }
+                    }
+                }
+
+                let mut fields = vec![s.pos.to_string()];
+                fields.extend(s.bases.chars().map(|c| c.to_string()));
+                if has_outgroup {
+                    fields.push(s.obase.clone());
+                }
+                writer.write_all(format!("{}\n", fields.join("\t")).as_ref())?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -13,6 +13,13 @@ pub fn make_subcommand() -> Command {
             r###"
 * <infiles> are paths to block fasta files, .fas.gz is supported
     * infile == stdin means reading from STDIN
+* --by block|name switches to an alternative splitting scheme and writes an
+  `index.tsv` (block id -> original range) alongside the split files
+    * by block - one file per block, named `block_NNNNNN<suffix>`
+    * by name  - one file per species, named `<species><suffix>`, containing
+      that species' sequence from every block in order; --fill pads blocks
+      where the species is absent with a gap-only sequence instead of
+      skipping them
 
 "###,
         )
@@ -51,6 +58,23 @@ pub fn make_subcommand() -> Command {
                 .default_value("stdout")
                 .help("Output location. [stdout] for screen"),
         )
+        .arg(
+            Arg::new("by")
+                .long("by")
+                .num_args(1)
+                .value_parser([
+                    builder::PossibleValue::new("block"),
+                    builder::PossibleValue::new("name"),
+                ])
+                .conflicts_with_all(["chr"])
+                .help("Split `by block` (one file per block) or `by name` (one file per species)"),
+        )
+        .arg(
+            Arg::new("fill")
+                .long("fill")
+                .action(ArgAction::SetTrue)
+                .help("With `--by name`, pad blocks missing a species with a gap-only sequence"),
+        )
 }
 
 // command implementation
@@ -67,15 +91,21 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let is_chr = args.get_flag("chr");
     let is_simple = args.get_flag("simple");
 
+    if let Some(by) = args.get_one::<String>("by") {
+        let is_fill = args.get_flag("fill");
+        return split_by(args, outdir, suffix, by, is_fill);
+    }
+
     let mut file_of: BTreeMap<String, File> = BTreeMap::new();
 
     //----------------------------
     // Operating
     //----------------------------
     for infile in args.get_many::<String>("infiles").unwrap() {
-        let mut reader = intspan::reader(infile);
+        let reader = intspan::reader(infile);
 
-        while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
             let filename = if is_chr {
                 let tname = block.entries.first().unwrap().range().name();
                 let tchr = block.entries.first().unwrap().range().chr();
@@ -126,3 +156,127 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Backing implementation for `--by block|name`, buffering all blocks so `by name`
+/// can lay out one file per species across the whole run. Also emits `index.tsv`
+/// (block id -> original range) alongside the split files.
+fn split_by(
+    args: &ArgMatches,
+    outdir: &str,
+    suffix: &str,
+    by: &str,
+    is_fill: bool,
+) -> anyhow::Result<()> {
+    let mut blocks: Vec<hnsm::FasBlock> = vec![];
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+        for result in hnsm::FasBlockReader::new(reader) {
+            blocks.push(result?);
+        }
+    }
+
+    let mut index_lines: Vec<String> = vec![];
+
+    if by == "block" {
+        for (i, block) in blocks.iter().enumerate() {
+            let block_id = format!("block_{:06}", i + 1);
+            let range = block.entries.first().unwrap().range().to_string();
+            index_lines.push(format!("{}\t{}", block_id, range));
+
+            if outdir == "stdout" {
+                for entry in &block.entries {
+                    let seq = std::str::from_utf8(entry.seq()).unwrap();
+                    print!(">{}\n{}\n", entry.range(), seq);
+                }
+                println!();
+            } else {
+                let path = Path::new(outdir).join(block_id + suffix);
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .truncate(true)
+                    .open(path)?;
+                for entry in &block.entries {
+                    let seq = std::str::from_utf8(entry.seq()).unwrap();
+                    write!(file, ">{}\n{}\n", entry.range(), seq)?;
+                }
+            }
+        }
+    } else {
+        // by == "name"
+        let mut names: Vec<String> = vec![];
+        for block in &blocks {
+            for entry in &block.entries {
+                let name = entry.range().name().to_string();
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+
+        let mut file_of: BTreeMap<String, File> = BTreeMap::new();
+        if outdir != "stdout" {
+            for name in &names {
+                let filename = name.replace(['(', ')', ':'], "_").replace("__", "_");
+                let path = Path::new(outdir).join(filename + suffix);
+                let file = OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .truncate(true)
+                    .open(path)?;
+                file_of.insert(name.clone(), file);
+            }
+        }
+
+        for (i, block) in blocks.iter().enumerate() {
+            let block_id = format!("block_{:06}", i + 1);
+            let range = block.entries.first().unwrap().range().to_string();
+            index_lines.push(format!("{}\t{}", block_id, range));
+
+            let block_len = block.entries.first().unwrap().seq().len();
+
+            for name in &names {
+                let entry = block.entries.iter().find(|e| e.range().name() == name);
+                match entry {
+                    Some(entry) => {
+                        let seq = std::str::from_utf8(entry.seq()).unwrap();
+                        if outdir == "stdout" {
+                            print!(">{} {}\n{}\n", name, entry.range(), seq);
+                        } else {
+                            write!(
+                                file_of.get(name).unwrap(),
+                                ">{} {}\n{}\n",
+                                name,
+                                entry.range(),
+                                seq
+                            )?;
+                        }
+                    }
+                    None if is_fill => {
+                        let seq = "-".repeat(block_len);
+                        if outdir == "stdout" {
+                            print!(">{} {}\n{}\n", name, block_id, seq);
+                        } else {
+                            write!(file_of.get(name).unwrap(), ">{} {}\n{}\n", name, block_id, seq)?;
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    if outdir != "stdout" {
+        let index_path = Path::new(outdir).join("index.tsv");
+        let mut index_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(index_path)?;
+        for line in &index_lines {
+            writeln!(index_file, "{}", line)?;
+        }
+    }
+
+    Ok(())
+}
@@ -8,6 +8,11 @@ pub fn make_subcommand() -> Command {
             r###"
 * <infiles> are paths to block fasta files, .fas.gz is supported
     * infile == stdin means reading from STDIN
+* --diversity reports segregating sites, nucleotide diversity (pi),
+  Watterson's theta, and Tajima's D per block instead of the default
+  columns, plus a `total` row weighted by each block's comparable length.
+  Gap-containing/ambiguous columns are excluded from `comparable`; blocks
+  with fewer than 4 sequences (or no segregating sites) report `NA` for D
 
 "###,
         )
@@ -24,6 +29,12 @@ pub fn make_subcommand() -> Command {
                 .action(ArgAction::SetTrue)
                 .help("There are outgroups at the end of each block"),
         )
+        .arg(
+            Arg::new("diversity")
+                .long("diversity")
+                .action(ArgAction::SetTrue)
+                .help("Report pi/theta/Tajima's D per block instead of the default columns"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
@@ -41,27 +52,50 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     //----------------------------
     let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
     let has_outgroup = args.get_flag("has_outgroup");
+    let is_diversity = args.get_flag("diversity");
 
-    let field_names = vec![
-        "target",
-        "length",
-        "comparable",
-        "difference",
-        "gap",
-        "ambiguous",
-        "D",
-        "indel",
-    ];
+    let field_names: Vec<&str> = if is_diversity {
+        vec![
+            "target",
+            "length",
+            "count",
+            "comparable",
+            "segregating",
+            "pi",
+            "theta",
+            "D",
+        ]
+    } else {
+        vec![
+            "target",
+            "length",
+            "comparable",
+            "difference",
+            "gap",
+            "ambiguous",
+            "D",
+            "indel",
+        ]
+    };
 
     //----------------------------
     // Operating
     //----------------------------
     writer.write_all(format!("{}\n", field_names.join("\t")).as_ref())?;
 
+    // weighted total, --diversity only
+    let mut total_length = 0i32;
+    let mut total_comparable = 0i32;
+    let mut total_segregating = 0i32;
+    let mut weighted_pi = 0f64;
+    let mut weighted_theta = 0f64;
+    let mut last_seq_count = 0usize;
+
     for infile in args.get_many::<String>("infiles").unwrap() {
-        let mut reader = intspan::reader(infile);
+        let reader = intspan::reader(infile);
 
-        while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
             let target = block.entries.first().unwrap().range().to_string();
 
             let mut seqs: Vec<&[u8]> = vec![];
@@ -73,30 +107,85 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
                 seqs.pop();
             }
 
-            // let (length, comparable, difference, gap, ambiguous, mean_d) = alignment_stat(&seqs);
-            let result = hnsm::alignment_stat(&seqs);
+            if is_diversity {
+                let stat = hnsm::diversity_stat(&seqs);
 
-            let mut indel_ints = intspan::IntSpan::new();
-            for seq in seqs {
-                indel_ints.merge(&hnsm::indel_intspan(seq));
-            }
+                total_length += stat.length;
+                total_comparable += stat.comparable;
+                total_segregating += stat.segregating;
+                weighted_pi += stat.pi * stat.comparable as f64;
+                weighted_theta += stat.theta * stat.comparable as f64;
+                last_seq_count = stat.seq_count;
+
+                writer.write_all(
+                    format!(
+                        "{}\t{}\t{}\t{}\t{}\t{:.4}\t{:.4}\t{}\n",
+                        target,
+                        stat.length,
+                        stat.seq_count,
+                        stat.comparable,
+                        stat.segregating,
+                        stat.pi,
+                        stat.theta,
+                        fmt_tajima_d(stat.tajima_d),
+                    )
+                    .as_ref(),
+                )?;
+            } else {
+                // let (length, comparable, difference, gap, ambiguous, mean_d) = alignment_stat(&seqs);
+                let result = hnsm::alignment_stat(&seqs);
+
+                let mut indel_ints = intspan::IntSpan::new();
+                for seq in seqs {
+                    indel_ints.merge(&hnsm::indel_intspan(seq));
+                }
 
-            writer.write_all(
-                format!(
-                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
-                    target,
-                    result.0,
-                    result.1,
-                    result.2,
-                    result.3,
-                    result.4,
-                    result.5,
-                    indel_ints.span_size(),
-                )
-                .as_ref(),
-            )?;
+                writer.write_all(
+                    format!(
+                        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                        target,
+                        result.0,
+                        result.1,
+                        result.2,
+                        result.3,
+                        result.4,
+                        result.5,
+                        indel_ints.span_size(),
+                    )
+                    .as_ref(),
+                )?;
+            }
         }
     }
 
+    if is_diversity && total_comparable > 0 {
+        let pi_total = weighted_pi / total_comparable as f64;
+        let theta_total = weighted_theta / total_comparable as f64;
+        let raw_k_total = pi_total * total_comparable as f64;
+        let tajima_d_total = hnsm::tajima_d(last_seq_count, total_segregating, raw_k_total);
+
+        writer.write_all(
+            format!(
+                "{}\t{}\t{}\t{}\t{}\t{:.4}\t{:.4}\t{}\n",
+                "total",
+                total_length,
+                last_seq_count,
+                total_comparable,
+                total_segregating,
+                pi_total,
+                theta_total,
+                fmt_tajima_d(tajima_d_total),
+            )
+            .as_ref(),
+        )?;
+    }
+
     Ok(())
 }
+
+fn fmt_tajima_d(d: Option<f64>) -> String {
+    match d {
+        Some(d) => format!("{:.4}", d),
+        None => "NA".to_string(),
+    }
+}
@@ -0,0 +1,115 @@
+use clap::*;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("trim")
+        .about("Remove gap-heavy columns from block fasta alignments")
+        .after_help(
+            r###"
+* <infiles> are paths to block fasta files, .fas.gz is supported
+    * infile == stdin means reading from STDIN
+
+* `--max-gap FRAC` removes every alignment column whose gap fraction (the number of
+  sequences with a `-` at that column, divided by the number of sequences) exceeds
+  FRAC. All sequences in a block stay the same length after trimming, and each
+  sequence's `range()` start/end is recomputed to match the columns that remain
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input files to use"),
+        )
+        .arg(
+            Arg::new("max_gap")
+                .long("max-gap")
+                .num_args(1)
+                .default_value("0.5")
+                .value_parser(value_parser!(f32))
+                .help("Remove columns whose gap fraction exceeds this"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+    let max_gap = *args.get_one::<f32>("max_gap").unwrap();
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
+            let n = block.entries.len();
+            let len = block.entries[0].seq().len();
+
+            let keep_cols: Vec<usize> = (1..=len)
+                .filter(|&col| {
+                    let gaps = block
+                        .entries
+                        .iter()
+                        .filter(|e| e.seq()[col - 1] == b'-')
+                        .count();
+                    gaps as f32 / n as f32 <= max_gap
+                })
+                .collect();
+            if keep_cols.is_empty() {
+                continue;
+            }
+            let lower = *keep_cols.first().unwrap();
+            let upper = *keep_cols.last().unwrap();
+
+            for entry in &block.entries {
+                let seq = entry.seq();
+                let range = entry.range();
+                let ints_seq = hnsm::seq_intspan(seq);
+
+                let start =
+                    hnsm::align_to_chr(&ints_seq, lower as i32, range.start, range.strand())
+                        .unwrap();
+                let end =
+                    hnsm::align_to_chr(&ints_seq, upper as i32, range.start, range.strand())
+                        .unwrap();
+                let trimmed_range = intspan::Range::from_full(
+                    range.name(),
+                    range.chr(),
+                    range.strand(),
+                    start,
+                    end,
+                );
+
+                let trimmed_seq: Vec<u8> = keep_cols.iter().map(|&col| seq[col - 1]).collect();
+
+                writer.write_all(
+                    format!(
+                        ">{}\n{}\n",
+                        trimmed_range,
+                        std::str::from_utf8(&trimmed_seq).unwrap()
+                    )
+                    .as_ref(),
+                )?;
+            }
+
+            writer.write_all("\n".as_ref())?;
+        } // block
+    } // infile
+
+    Ok(())
+}
@@ -75,9 +75,10 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     writer.write_all(format!("{}\n", field_names.join("\t")).as_ref())?;
 
     for infile in args.get_many::<String>("infiles").unwrap() {
-        let mut reader = intspan::reader(infile);
+        let reader = intspan::reader(infile);
 
-        while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
             let mut seqs: Vec<&[u8]> = vec![];
             for entry in &block.entries {
                 seqs.push(entry.seq().as_ref());
@@ -0,0 +1,247 @@
+use clap::*;
+use std::collections::BTreeSet;
+use std::io::Write;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("vcf")
+        .about("Emit a minimal VCF of substitutions/indels against a reference sample")
+        .after_help(
+            r###"
+* <infiles> are paths to block fasta files, .fas.gz is supported
+    * infile == stdin means reading from STDIN
+
+* `--ref NAME` selects the sample whose sequence anchors `POS`/`REF`; every
+  other (non-outgroup) sample in the block becomes a VCF sample column with
+  a `GT` field
+
+* Indels are anchored on the base preceding the event, as VCF requires
+
+"###,
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(1)
+                .help("Set the input files to use"),
+        )
+        .arg(
+            Arg::new("ref")
+                .long("ref")
+                .num_args(1)
+                .required(true)
+                .help("Name of the sample to use as the reference"),
+        )
+        .arg(
+            Arg::new("has_outgroup")
+                .long("outgroup")
+                .action(ArgAction::SetTrue)
+                .help("There are outgroups at the end of each block"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    //----------------------------
+    // Args
+    //----------------------------
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+    let ref_name = args.get_one::<String>("ref").unwrap();
+    let has_outgroup = args.get_flag("has_outgroup");
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    let mut wrote_header = false;
+
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let reader = intspan::reader(infile);
+
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
+            let seq_count = block.entries.len();
+            let ingroup_count = if has_outgroup {
+                seq_count - 1
+            } else {
+                seq_count
+            };
+
+            let ref_idx = (0..ingroup_count)
+                .find(|&i| block.entries[i].range().name() == ref_name)
+                .ok_or_else(|| anyhow::anyhow!("sample `{}` not found in block", ref_name))?;
+
+            // Reorder so the reference sample comes first, keeping the outgroup last
+            let mut order: Vec<usize> = vec![ref_idx];
+            order.extend((0..ingroup_count).filter(|&i| i != ref_idx));
+            if has_outgroup {
+                order.push(seq_count - 1);
+            }
+
+            let seqs: Vec<&[u8]> = order
+                .iter()
+                .map(|&i| block.entries[i].seq().as_ref())
+                .collect();
+            let sample_names: Vec<String> = order[..ingroup_count]
+                .iter()
+                .map(|&i| block.entries[i].range().name().to_string())
+                .collect();
+
+            if !wrote_header {
+                write_vcf_header(&mut writer, &sample_names)?;
+                wrote_header = true;
+            }
+
+            let ref_range = block.entries[order[0]].range().clone();
+            let chr = ref_range.chr().to_string();
+            let t_ints_seq = hnsm::seq_intspan(seqs[0]);
+
+            let ingroup_seqs = &seqs[..ingroup_count];
+            let subs = if has_outgroup {
+                let mut unpolarized = hnsm::get_subs(ingroup_seqs).unwrap();
+                hnsm::polarize_subs(&mut unpolarized, seqs[seq_count - 1]);
+                unpolarized
+            } else {
+                hnsm::get_subs(ingroup_seqs).unwrap()
+            };
+            for s in &subs {
+                let chr_pos =
+                    hnsm::align_to_chr(&t_ints_seq, s.pos, ref_range.start, ref_range.strand())?;
+
+                let ref_base = s.tbase.chars().next().unwrap();
+                let alt_bases: Vec<char> = s
+                    .bases
+                    .chars()
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .filter(|&c| c != ref_base)
+                    .collect();
+                if alt_bases.is_empty() {
+                    continue;
+                }
+                let alt = alt_bases.iter().collect::<String>();
+
+                let genotypes: Vec<String> = s
+                    .bases
+                    .chars()
+                    .map(|c| {
+                        if c == ref_base {
+                            "0".to_string()
+                        } else {
+                            (alt_bases.iter().position(|&a| a == c).unwrap() + 1).to_string()
+                        }
+                    })
+                    .collect();
+
+                writer.write_all(
+                    format!(
+                        "{}\t{}\t.\t{}\t{}\t.\t.\t.\tGT\t{}\n",
+                        chr,
+                        chr_pos,
+                        ref_base,
+                        alt,
+                        genotypes.join("\t")
+                    )
+                    .as_ref(),
+                )?;
+            }
+
+            let indels = hnsm::get_indels(ingroup_seqs).unwrap();
+            for indel in &indels {
+                let chr_pos = hnsm::align_to_chr(
+                    &t_ints_seq,
+                    indel.start,
+                    ref_range.start,
+                    ref_range.strand(),
+                )?;
+
+                let anchor_col = preceding_col(ingroup_seqs[0], (indel.start - 1) as usize);
+                let anchor = ingroup_seqs[0][anchor_col].to_ascii_uppercase() as char;
+                let anchor_pos = hnsm::align_to_chr(
+                    &t_ints_seq,
+                    anchor_col as i32 + 1,
+                    ref_range.start,
+                    ref_range.strand(),
+                )
+                .unwrap_or(chr_pos - 1);
+
+                let ref_present = indel.bases.as_bytes()[0] == b'1';
+                let src_idx = if ref_present { 0 } else { first_present(indel) };
+                let event: String = (indel.start - 1..indel.end)
+                    .map(|i| ingroup_seqs[src_idx][i as usize].to_ascii_uppercase() as char)
+                    .collect();
+
+                let (vcf_ref, vcf_alt) = if ref_present {
+                    (format!("{}{}", anchor, event), anchor.to_string())
+                } else {
+                    (anchor.to_string(), format!("{}{}", anchor, event))
+                };
+
+                let ref_char = indel.bases.as_bytes()[0];
+                let genotypes: Vec<String> = indel
+                    .bases
+                    .bytes()
+                    .map(|b| if b == ref_char { "0".to_string() } else { "1".to_string() })
+                    .collect();
+
+                writer.write_all(
+                    format!(
+                        "{}\t{}\t.\t{}\t{}\t.\t.\t.\tGT\t{}\n",
+                        chr,
+                        anchor_pos,
+                        vcf_ref,
+                        vcf_alt,
+                        genotypes.join("\t")
+                    )
+                    .as_ref(),
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_vcf_header(writer: &mut Box<dyn Write>, sample_names: &[String]) -> anyhow::Result<()> {
+    writer.write_all(b"##fileformat=VCFv4.2\n")?;
+    writer.write_all(
+        format!(
+            "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\t{}\n",
+            sample_names.join("\t")
+        )
+        .as_ref(),
+    )?;
+    Ok(())
+}
+
+/// Walks backward from `col` (0-based, inclusive) to find the nearest non-gap column of
+/// `seq`, for anchoring an indel's `REF`/`ALT` on the preceding base
+fn preceding_col(seq: &[u8], col: usize) -> usize {
+    let mut i = col;
+    loop {
+        if seq[i] != b'-' {
+            return i;
+        }
+        if i == 0 {
+            return 0;
+        }
+        i -= 1;
+    }
+}
+
+fn first_present(indel: &hnsm::Indel) -> usize {
+    indel
+        .bases
+        .as_bytes()
+        .iter()
+        .position(|&b| b == b'1')
+        .unwrap()
+}
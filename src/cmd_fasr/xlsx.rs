@@ -11,6 +11,21 @@ pub fn make_subcommand() -> Command {
             r###"
 * <infiles> are paths to block fasta files, .fas.gz is supported
     * infile == stdin means reading from STDIN
+* --tsv writes the same variations, one row per sub/indel, to a
+  machine-readable TSV, built from the same `subs` as the workbook so the
+  two outputs always agree
+* When --outfile is `-` or omitted while --tsv is set, the xlsx workbook is
+  skipped entirely, so this command can run headless with --tsv alone
+* --summary adds a second worksheet with one row per block (range, sequence
+  count, alignment length, substitution count, indel count) plus a hyperlink
+  back to that block's first cell on the main sheet; it has no effect in
+  headless mode
+
+* --outgroups N treats the last N sequences of each block as outgroups
+  instead of just the last one, deriving the ancestral obase by majority
+  rule (ties left unpolarized). Implies --outgroup. Only the majority obase
+  row is colored; the other outgroup sequences still get a name row but no
+  per-base coloring
 
 "###,
         )
@@ -41,13 +56,32 @@ pub fn make_subcommand() -> Command {
                 .action(ArgAction::SetTrue)
                 .help("There are outgroups at the end of each block"),
         )
+        .arg(
+            Arg::new("outgroups")
+                .long("outgroups")
+                .value_parser(value_parser!(usize))
+                .num_args(1)
+                .default_value("1")
+                .help("Number of outgroups at the end of each block, polarized by majority rule"),
+        )
         .arg(
             Arg::new("outfile")
                 .long("outfile")
                 .short('o')
                 .num_args(1)
-                .default_value("variations.xlsx")
-                .help("Output filename"),
+                .help("Output xlsx filename. [variations.xlsx] unless --tsv is also set; `-` skips the workbook"),
+        )
+        .arg(
+            Arg::new("tsv")
+                .long("tsv")
+                .num_args(1)
+                .help("Also (or instead) write one row per variation to this TSV file"),
+        )
+        .arg(
+            Arg::new("summary")
+                .long("summary")
+                .action(ArgAction::SetTrue)
+                .help("Add a second worksheet summarizing each block, linked to the main sheet"),
         )
 }
 
@@ -56,8 +90,18 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     //----------------------------
     // Args
     //----------------------------
-    let outfile = args.get_one::<String>("outfile").unwrap();
+    let opt_outfile = args.get_one::<String>("outfile");
+    let opt_tsv = args.get_one::<String>("tsv");
     let has_outgroup = args.get_flag("has_outgroup");
+    let outgroup_count = args.get_one::<usize>("outgroups").copied().unwrap_or(1).max(1);
+    let is_summary = args.get_flag("summary");
+
+    // Headless mode: --outfile is `-` or omitted while --tsv is set, so
+    // there's no reason to build a workbook at all.
+    let do_xlsx = !(opt_tsv.is_some() && matches!(opt_outfile.map(|s| s.as_str()), None | Some("-")));
+    let outfile = opt_outfile
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "variations.xlsx".to_string());
 
     //----------------------------
     // Operating
@@ -65,7 +109,21 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
 
     // Create workbook and worksheet objects
     let mut workbook = Workbook::new();
-    let worksheet = workbook.add_worksheet();
+    let mut worksheet = if do_xlsx {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("Variations")?;
+        Some(sheet)
+    } else {
+        None
+    };
+    let do_summary = do_xlsx && is_summary;
+    // (range, seq_count, aln_len, sub_count, indel_count, main-sheet row of the block's first entry)
+    let mut summary_rows: Vec<(String, usize, usize, usize, usize, u32)> = vec![];
+
+    let mut tsv_writer = opt_tsv.map(|s| intspan::writer(s));
+    if let Some(w) = tsv_writer.as_mut() {
+        w.write_all("block\ttype\tpos\ttbase\tqbase\tbases\tmutant_to\tfreq\tpattern\tobase\n".as_ref())?;
+    }
 
     let format_of: BTreeMap<String, Format> = create_formats();
     let mut max_name_len = 1;
@@ -76,9 +134,10 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     // eprintln!("format_of = {:#?}", format_of.keys());
 
     for infile in args.get_many::<String>("infiles").unwrap() {
-        let mut reader = intspan::reader(infile);
+        let reader = intspan::reader(infile);
 
-        while let Ok(block) = hnsm::next_fas_block(&mut reader) {
+        for result in hnsm::FasBlockReader::new(reader) {
+            let block = result?;
             let mut seqs: Vec<&[u8]> = vec![];
             for entry in &block.entries {
                 seqs.push(entry.seq().as_ref());
@@ -86,107 +145,176 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
 
             // pos, tbase, qbase, bases, mutant_to, freq, pattern, obase
             //   0,     1,     2,     3,         4,    5,       6,     7
-            let mut seq_count = seqs.len();
+            let seq_count = seqs.len();
             let subs = if has_outgroup {
-                let mut unpolarized = hnsm::get_subs(&seqs[..(seq_count - 1)]).unwrap();
-                hnsm::polarize_subs(&mut unpolarized, seqs[seq_count - 1]);
+                let mut unpolarized = hnsm::get_subs(&seqs[..(seq_count - outgroup_count)]).unwrap();
+                hnsm::polarize_subs_multi(&mut unpolarized, &seqs[(seq_count - outgroup_count)..]);
                 unpolarized
             } else {
                 hnsm::get_subs(&seqs).unwrap()
             };
 
-            let sec_height = seq_count + 2; // 1 for pos, 1 for spacing
-            let mut col_cursor = 1;
-
-            // each section
-            // write names
-            for i in 1..=block.entries.len() {
-                let pos_row = sec_height * (sec_cursor - 1);
-
-                let rg = block.entries[i - 1].range().to_string();
-                worksheet.write_with_format(
-                    (pos_row + i) as u32,
-                    0,
-                    rg.clone(),
-                    format_of.get("name").unwrap(),
-                )?;
-
-                // record max length
-                max_name_len = max(rg.len(), max_name_len);
+            if let Some(w) = tsv_writer.as_mut() {
+                let block_id = block.entries.first().unwrap().range().to_string();
+                for s in subs.iter() {
+                    w.write_all(format!("{}\tsub\t{}\n", block_id, s).as_ref())?;
+                }
             }
 
-            if has_outgroup {
-                seq_count -= 1;
-            }
+            if let Some(worksheet) = worksheet.as_deref_mut() {
+                let sec_height = seq_count + 2; // 1 for pos, 1 for spacing
+                let mut col_cursor = 1;
+                let block_pos_row = sec_height * (sec_cursor - 1);
+
+                // each section
+                // write names
+                for i in 1..=block.entries.len() {
+                    let pos_row = sec_height * (sec_cursor - 1);
 
-            for s in subs.iter() {
-                // eprintln!("s = {:#?}", s.to_string());
-                let pos_row = sec_height * (sec_cursor - 1);
-
-                // write position
-                worksheet.write_with_format(
-                    pos_row as u32,
-                    col_cursor,
-                    s.pos,
-                    format_of.get("pos").unwrap(),
-                )?;
-
-                for i in 1..=seq_count {
-                    let base = s.bases.chars().nth(i - 1).unwrap();
-                    let occurred = if s.pattern == "unknown" {
-                        '0'
-                    } else {
-                        s.pattern.chars().nth(i - 1).unwrap()
-                    };
-
-                    let base_color = if occurred == '1' {
-                        let bg_idx = u32::from_str_radix(&s.pattern, 2).unwrap() % color_loop;
-                        format!("sub_{}_{}", base, bg_idx)
-                    } else {
-                        format!("sub_{}_unknown", base)
-                    };
-                    let format = format_of.get(&base_color).unwrap();
+                    let rg = block.entries[i - 1].range().to_string();
                     worksheet.write_with_format(
                         (pos_row + i) as u32,
-                        col_cursor,
-                        base.to_string(),
-                        format,
+                        0,
+                        rg.clone(),
+                        format_of.get("name").unwrap(),
                     )?;
+
+                    // record max length
+                    max_name_len = max(rg.len(), max_name_len);
                 }
 
-                // outgroup bases with no bg colors
+                if do_summary {
+                    let mut indel_ints = intspan::IntSpan::new();
+                    for seq in seqs.iter().copied() {
+                        indel_ints.merge(&hnsm::indel_intspan(seq));
+                    }
+                    summary_rows.push((
+                        block.entries.first().unwrap().range().to_string(),
+                        seq_count,
+                        block.entries.first().unwrap().seq().len(),
+                        subs.len(),
+                        indel_ints.span_size() as usize,
+                        (block_pos_row + 1) as u32,
+                    ));
+                }
+
+                let mut sub_seq_count = seq_count;
                 if has_outgroup {
-                    let base_color = format!("sub_{}_unknown", s.obase);
-                    let format = format_of.get(&base_color).unwrap();
-                    worksheet.write_with_format(
-                        (pos_row + seq_count + 1) as u32,
-                        col_cursor,
-                        s.obase.clone(),
-                        format,
-                    )?;
+                    sub_seq_count -= outgroup_count;
                 }
 
-                // increase column cursor
-                col_cursor += 1;
+                for s in subs.iter() {
+                    // eprintln!("s = {:#?}", s.to_string());
+                    let pos_row = sec_height * (sec_cursor - 1);
 
-                // wrap
-                if col_cursor > *wrap as u16 {
-                    col_cursor = 1;
-                    sec_cursor += 1;
-                }
-            } // vars
+                    // write position
+                    worksheet.write_with_format(
+                        pos_row as u32,
+                        col_cursor,
+                        s.pos,
+                        format_of.get("pos").unwrap(),
+                    )?;
 
-            sec_cursor += 1;
+                    for i in 1..=sub_seq_count {
+                        let base = s.bases.chars().nth(i - 1).unwrap();
+                        let occurred = if s.polarity == hnsm::Polarity::Unknown {
+                            '0'
+                        } else {
+                            s.pattern.chars().nth(i - 1).unwrap()
+                        };
+
+                        let base_color = if occurred == '1' {
+                            let bg_idx = u32::from_str_radix(&s.pattern, 2).unwrap() % color_loop;
+                            format!("sub_{}_{}", base, bg_idx)
+                        } else {
+                            format!("sub_{}_unknown", base)
+                        };
+                        let format = format_of.get(&base_color).unwrap();
+                        worksheet.write_with_format(
+                            (pos_row + i) as u32,
+                            col_cursor,
+                            base.to_string(),
+                            format,
+                        )?;
+                    }
+
+                    // outgroup bases with no bg colors
+                    if has_outgroup {
+                        let base_color = format!("sub_{}_unknown", s.obase);
+                        let format = format_of.get(&base_color).unwrap();
+                        worksheet.write_with_format(
+                            (pos_row + sub_seq_count + 1) as u32,
+                            col_cursor,
+                            s.obase.clone(),
+                            format,
+                        )?;
+                    }
+
+                    // increase column cursor
+                    col_cursor += 1;
+
+                    // wrap
+                    if col_cursor > *wrap as u16 {
+                        col_cursor = 1;
+                        sec_cursor += 1;
+                    }
+                } // vars
+
+                sec_cursor += 1;
+            }
         } // block
     }
 
-    worksheet.set_column_width(0, max_name_len as f64)?;
-    for i in 1..=(*wrap + 3) {
-        worksheet.set_column_width(i as u16, 1.6)?;
+    if let Some(worksheet) = worksheet.as_deref_mut() {
+        worksheet.set_column_width(0, max_name_len as f64)?;
+        for i in 1..=(*wrap + 3) {
+            worksheet.set_column_width(i as u16, 1.6)?;
+        }
+    }
+
+    if do_summary {
+        let summary = workbook.add_worksheet();
+        summary.set_name("Summary")?;
+
+        let header = [
+            "range",
+            "seq_count",
+            "aln_len",
+            "sub_count",
+            "indel_count",
+            "block",
+        ];
+        for (col, name) in header.iter().enumerate() {
+            summary.write_with_format(0, col as u16, *name, format_of.get("name").unwrap())?;
+        }
+
+        for (row, (range, seq_count, aln_len, sub_count, indel_count, main_row)) in
+            summary_rows.iter().enumerate()
+        {
+            let row = (row + 1) as u32;
+            summary.write_with_format(row, 0, range.as_str(), format_of.get("name").unwrap())?;
+            summary.write_number(row, 1, *seq_count as f64)?;
+            summary.write_number(row, 2, *aln_len as f64)?;
+            summary.write_number(row, 3, *sub_count as f64)?;
+            summary.write_number(row, 4, *indel_count as f64)?;
+            summary.write_url_with_text(
+                row,
+                5,
+                Url::new(format!("internal:'Variations'!A{}", main_row + 1)),
+                "view",
+            )?;
+        }
+
+        summary.set_column_width(0, max_name_len as f64)?;
+        for col in 1..=5u16 {
+            summary.set_column_width(col, 10.0)?;
+        }
     }
 
     // Save the file to disk.
-    workbook.save(outfile)?;
+    if do_xlsx {
+        workbook.save(&outfile)?;
+    }
 
     Ok(())
 }
@@ -1,5 +1,6 @@
 use clap::*;
 use cmd_lib::*;
+use rayon::prelude::*;
 
 // Create clap subcommand arguments
 pub fn make_subcommand() -> Command {
@@ -19,7 +20,9 @@ This command implements the UCSC pipeline for pairwise genome alignments, psl-ch
     * `loose` corresponds to chicken/human linear gap costs
     * `medium` corresponds to mouse/human linear gap costs
 
-* The following binaries from the kent-tools are required and should be found in $PATH:
+* The following binaries from the kent-tools are required and should be found in $PATH.
+  This is checked up front, before any work starts, and all missing binaries are
+  reported together:
     * axtChain
     * chainAntiRepeat
     * chainMergeSort
@@ -34,6 +37,66 @@ This command implements the UCSC pipeline for pairwise genome alignments, psl-ch
     * axtToMaf
     * netFilter
     * chainSplit
+    * faToTwoBit
+
+* `--keep-temp <dir>` keeps the working directory (normally a scratch tempdir that is
+  removed on exit) at the given path instead, for inspecting intermediate .chain/.net
+  files after a run or a failure
+
+* `--parallel N` runs the per-PSL axtChain/chainAntiRepeat step and the per-net
+  netToAxt/axtSort step across up to N files at once (each writes to a distinct
+  output file, so this is safe). Captured stderr from each concurrent invocation is
+  printed as a block prefixed with its stage and file so logs stay readable. A
+  failure in any one file aborts the run with a nonzero exit
+
+* `--native` replaces the axtChain/chainAntiRepeat step with a pure-Rust PSL
+  parser and chainer (see `hnsm::chain_psl`), so those two binaries aren't
+  required. It approximates `-linearGap`'s piecewise cost tables with a single
+  affine open/extend cost, and scores blocks as if they were perfect matches
+  (PSL doesn't carry per-base identity), so it is meant for small,
+  close-to-collinear genome pairs rather than as a drop-in replacement
+
+* `--native-2bit` writes target/query `.2bit` files with [`hnsm::write_two_bit`]
+  instead of calling `faToTwoBit`, so that binary isn't required either.
+  `--t2bit`/`--q2bit` still take priority when given, same as without this flag
+
+* `--stop-at chain` stops after chainMergeSort/chainPreNet produce the merged,
+  pre-net .chain file, writing it to <outdir>/all.pre.chain (or stdout) instead
+  of continuing on to chain-net/netToAxt/axt-maf. This chain file, `--native` or
+  not, can still be fed to the rest of the UCSC pipeline externally
+
+* `--tsizes/--qsizes/--t2bit/--q2bit` reuse precomputed `.sizes`/`.2bit` files instead
+  of recomputing them with `hnsm size`/`faToTwoBit`, useful when re-running against
+  the same genomes
+
+* `--resume <dir>` points at a previous `--keep-temp` (or `--resume`) directory;
+  stages whose output sentinel file already exists there are skipped instead of
+  redone. The `.sizes`/`.2bit` stage is additionally cache-invalidated by mtime:
+  it is only skipped if `target.chr.sizes` is at least as new as both
+  <target> and <query>, so an edited fasta file triggers a recompute instead of
+  silently chaining against stale sizes/2bit data. `--dry-run` prints which
+  stages would run or be skipped without doing any work; combine it with
+  `--resume` to preview a resumed run
+
+* `--check` only runs the upfront kent-tools preflight (respecting `--native`,
+  `--native-2bit`, and `--stop-at`) and reports OK or the missing binaries, then exits without
+  touching <target>/<query>/<psl> at all; use it to validate a machine before
+  committing to a long run
+
+* `--psl-min-match`/`--psl-min-identity` pre-filter PSL records in Rust before
+  they reach axtChain (or the `--native` chainer), dropping alignments below
+  the given matching-base count / identity fraction to reduce noisy chains.
+  Filtered files are written to <tempdir>/pslFiltered; the default (neither
+  flag set) skips this step and feeds the input PSL through unchanged
+
+* `--merge-batch N` (default 100) sets how many `.chain` files `chainMergeSort`
+  combines at a time; opening all of them at once can hit the OS's open-file
+  limit on a large run. Lowering it uses fewer file descriptors per batch at
+  the cost of an extra merge pass. If the Linux soft limit (`/proc/self/limits`)
+  can be read and `N` leaves fewer than 10 descriptors of headroom, the command
+  fails fast with a message suggesting a lower `--merge-batch` or `ulimit -n`
+  instead of failing partway through with `chainMergeSort`'s own "too many open
+  files" error
 
 Definitions:
 
@@ -53,21 +116,21 @@ References:
         )
         .arg(
             Arg::new("target")
-                .required(true)
+                .required_unless_present("check")
                 .num_args(1)
                 .index(1)
                 .help("Path to the target genome FA file"),
         )
         .arg(
             Arg::new("query")
-                .required(true)
+                .required_unless_present("check")
                 .num_args(1)
                 .index(2)
                 .help("Path to the query genome FA file"),
         )
         .arg(
             Arg::new("psl")
-                .required(true)
+                .required_unless_present("check")
                 .num_args(1)
                 .index(3)
                 .help("Path to the PSL file or directory containing PSL files"),
@@ -119,6 +182,329 @@ References:
                 .default_value("stdout")
                 .help("Output location. [stdout] for screen"),
         )
+        .arg(
+            Arg::new("keep_temp")
+                .long("keep-temp")
+                .num_args(1)
+                .help("Keep the working directory here instead of a removed tempdir"),
+        )
+        .arg(
+            Arg::new("parallel")
+                .long("parallel")
+                .num_args(1)
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("Number of files to process concurrently in the axtChain and netToAxt steps"),
+        )
+        .arg(
+            Arg::new("merge_batch")
+                .long("merge-batch")
+                .num_args(1)
+                .default_value("100")
+                .value_parser(value_parser!(usize))
+                .help("Number of .chain files chainMergeSort combines at a time"),
+        )
+        .arg(
+            Arg::new("native")
+                .long("native")
+                .action(ArgAction::SetTrue)
+                .help("Chain PSLs in pure Rust instead of calling axtChain/chainAntiRepeat"),
+        )
+        .arg(
+            Arg::new("native_2bit")
+                .long("native-2bit")
+                .action(ArgAction::SetTrue)
+                .help("Write target/query .2bit files in pure Rust instead of calling `faToTwoBit`"),
+        )
+        .arg(
+            Arg::new("psl_min_match")
+                .long("psl-min-match")
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .help("Drop PSL records with fewer than this many matching bases before axtChain"),
+        )
+        .arg(
+            Arg::new("psl_min_identity")
+                .long("psl-min-identity")
+                .num_args(1)
+                .value_parser(value_parser!(f64))
+                .help("Drop PSL records whose matches / (matches + mismatches) falls below this fraction before axtChain"),
+        )
+        .arg(
+            Arg::new("stop_at")
+                .long("stop-at")
+                .num_args(1)
+                .value_parser([
+                    builder::PossibleValue::new("chain"),
+                    builder::PossibleValue::new("maf"),
+                ])
+                .default_value("maf")
+                .help("Stop after the chain stage instead of running the full pipeline"),
+        )
+        .arg(
+            Arg::new("tsizes")
+                .long("tsizes")
+                .num_args(1)
+                .help("Reuse this precomputed target .sizes file instead of running `hnsm size`"),
+        )
+        .arg(
+            Arg::new("qsizes")
+                .long("qsizes")
+                .num_args(1)
+                .help("Reuse this precomputed query .sizes file instead of running `hnsm size`"),
+        )
+        .arg(
+            Arg::new("t2bit")
+                .long("t2bit")
+                .num_args(1)
+                .help("Reuse this precomputed target .2bit file instead of running `faToTwoBit`"),
+        )
+        .arg(
+            Arg::new("q2bit")
+                .long("q2bit")
+                .num_args(1)
+                .help("Reuse this precomputed query .2bit file instead of running `faToTwoBit`"),
+        )
+        .arg(
+            Arg::new("resume")
+                .long("resume")
+                .num_args(1)
+                .help("Resume a previous run from this --keep-temp directory, skipping stages whose sentinel file already exists"),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .help("Print the stage plan (run/skip) and exit without doing any work"),
+        )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .action(ArgAction::SetTrue)
+                .help("Only check that the required kent-tools binaries are on $PATH, then exit"),
+        )
+}
+
+/// Named boundary between stages of the psl -> chain -> net -> axt -> maf
+/// pipeline. Kept explicit (rather than implicit in the linear function body)
+/// so `--resume`/`--dry-run` have a stable set of steps to reason about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PipelineStage {
+    Sizes,
+    AxtChain,
+    ChainMergeSort,
+    ChainNet,
+    NetToAxt,
+    AxtMaf,
+}
+
+impl PipelineStage {
+    fn all() -> &'static [PipelineStage] {
+        &[
+            PipelineStage::Sizes,
+            PipelineStage::AxtChain,
+            PipelineStage::ChainMergeSort,
+            PipelineStage::ChainNet,
+            PipelineStage::NetToAxt,
+            PipelineStage::AxtMaf,
+        ]
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            PipelineStage::Sizes => "Target/query .sizes and .2bit",
+            PipelineStage::AxtChain => "axtChain",
+            PipelineStage::ChainMergeSort => "chainMergeSort and chainPreNet",
+            PipelineStage::ChainNet => "chain-net",
+            PipelineStage::NetToAxt => "netToAxt",
+            PipelineStage::AxtMaf => "axt-maf",
+        }
+    }
+
+    /// A file, relative to the working directory, whose presence means this
+    /// stage's output already exists and `--resume` can skip re-running it.
+    /// `AxtMaf` writes straight to `--outdir` rather than the tempdir and has
+    /// no reliable sentinel there, so it is always re-run.
+    fn sentinel(&self) -> Option<&'static str> {
+        match self {
+            PipelineStage::Sizes => Some("target.chr.sizes"),
+            PipelineStage::AxtChain => Some("pslChain"),
+            PipelineStage::ChainMergeSort => Some("all.pre.chain"),
+            PipelineStage::ChainNet => Some("over.chain"),
+            PipelineStage::NetToAxt => Some("axtNet"),
+            PipelineStage::AxtMaf => None,
+        }
+    }
+
+    /// Whether this stage's sentinel already exists under `dir`.
+    fn is_done(&self, dir: &str) -> bool {
+        match self.sentinel() {
+            Some(sentinel) => std::path::Path::new(dir).join(sentinel).exists(),
+            None => false,
+        }
+    }
+
+    /// Whether `dir`'s sentinel for this stage is not just present but still
+    /// fresh with respect to `inputs` (typically the run's `<target>`/`<query>`
+    /// fasta files): `Sizes` recomputes `.sizes`/`.2bit` from those files with
+    /// `hnsm size`/`faToTwoBit`, so a `--resume` that blindly trusted an
+    /// existing `target.chr.sizes` could silently chain against stale data
+    /// after the fasta files changed. Other stages have no independent
+    /// upstream inputs (they're derived from earlier stages within the same
+    /// `dir`), so for them this is equivalent to `is_done` — pass an empty
+    /// `inputs` slice.
+    fn is_fresh(&self, dir: &str, inputs: &[&str]) -> bool {
+        if !self.is_done(dir) {
+            return false;
+        }
+        if inputs.is_empty() {
+            return true;
+        }
+
+        let sentinel_mtime = match std::fs::metadata(std::path::Path::new(dir).join(self.sentinel().unwrap()))
+            .and_then(|m| m.modified())
+        {
+            Ok(t) => t,
+            Err(_) => return false,
+        };
+        inputs.iter().all(|input| {
+            std::fs::metadata(input)
+                .and_then(|m| m.modified())
+                .map(|input_mtime| sentinel_mtime >= input_mtime)
+                .unwrap_or(false)
+        })
+    }
+
+    /// Whether this stage still runs given `stop_at` ("chain" stops the
+    /// pipeline right after `ChainMergeSort`).
+    fn reachable(&self, stop_at: &str) -> bool {
+        if stop_at != "chain" {
+            return true;
+        }
+        matches!(self, PipelineStage::Sizes | PipelineStage::AxtChain | PipelineStage::ChainMergeSort)
+    }
+}
+
+/// Kent-tools binaries required by this pipeline, checked all at once so a user
+/// missing several of them isn't stuck fixing $PATH one failure at a time.
+const REQUIRED_BINS: &[&str] = &[
+    "axtChain",
+    "chainAntiRepeat",
+    "chainMergeSort",
+    "chainPreNet",
+    "chainNet",
+    "netSyntenic",
+    "netChainSubset",
+    "chainStitchId",
+    "netSplit",
+    "netToAxt",
+    "axtSort",
+    "axtToMaf",
+    "netFilter",
+    "chainSplit",
+    "faToTwoBit",
+];
+
+/// Narrows `REQUIRED_BINS` to the binaries actually needed for this run:
+/// `--native` drops axtChain/chainAntiRepeat (replaced by the pure-Rust
+/// chainer), `--native-2bit` drops `faToTwoBit` (replaced by
+/// [`hnsm::write_two_bit`]), and `--stop-at chain` drops everything past
+/// chainPreNet, since the run exits before reaching those stages.
+fn required_bins_for(is_native: bool, is_native_2bit: bool, stop_at: &str) -> Vec<&'static str> {
+    let mut bins: Vec<&'static str> = REQUIRED_BINS.to_vec();
+
+    if is_native {
+        bins.retain(|&b| b != "axtChain" && b != "chainAntiRepeat");
+    }
+    if is_native_2bit {
+        bins.retain(|&b| b != "faToTwoBit");
+    }
+    if stop_at == "chain" {
+        bins.retain(|&b| matches!(b, "axtChain" | "chainAntiRepeat" | "chainMergeSort" | "chainPreNet"));
+    }
+
+    bins
+}
+
+/// Checks that every binary in `bins` is on `$PATH`, reporting all that are
+/// missing at once instead of failing on the first one encountered.
+fn preflight_kent_tools(bins: &[&str]) -> anyhow::Result<()> {
+    let missing: Vec<&str> = bins
+        .iter()
+        .filter(|bin| which::which(bin).is_err())
+        .copied()
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Missing required kent-tools binaries in $PATH: {}",
+            missing.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Runs `f`, tagging any error with the pipeline stage it occurred in.
+fn run_stage(stage: &str, f: impl FnOnce() -> anyhow::Result<()>) -> anyhow::Result<()> {
+    f().map_err(|e| anyhow::anyhow!("stage \"{}\" failed: {}", stage, e))
+}
+
+/// Reads a file containing one concurrent invocation's captured stderr and
+/// prints it as a single prefixed block, so lines from different files don't
+/// interleave with each other under `--parallel`. The file is removed after
+/// being read; a missing or empty file (nothing was written to stderr) is
+/// silently skipped.
+fn log_stderr(lock: &std::sync::Mutex<()>, label: &str, path: &str) {
+    if let Ok(content) = std::fs::read_to_string(path) {
+        if !content.trim().is_empty() {
+            let _guard = lock.lock().unwrap();
+            for line in content.lines() {
+                eprintln!("[{}] {}", label, line);
+            }
+        }
+    }
+    let _ = std::fs::remove_file(path);
+}
+
+/// Whether `stage` can be skipped: only true under `--resume`, and only once
+/// its sentinel is actually present in `dir` and (for stages given `inputs`,
+/// currently just `Sizes`) no older than every one of those input files.
+fn should_skip(stage: PipelineStage, resume: Option<&String>, dir: &str, inputs: &[&str]) -> bool {
+    resume.is_some() && stage.is_fresh(dir, inputs)
+}
+
+/// Best-effort soft open-file-limit lookup, parsed from `/proc/self/limits`
+/// (Linux only). Returns `None` if the file can't be read/parsed or the
+/// limit is "unlimited", in which case `--merge-batch` isn't validated.
+fn soft_open_file_limit() -> Option<u64> {
+    let content = std::fs::read_to_string("/proc/self/limits").ok()?;
+    for line in content.lines() {
+        if line.starts_with("Max open files") {
+            let soft = line.split_whitespace().nth(3)?;
+            return soft.parse().ok();
+        }
+    }
+    None
+}
+
+/// Fails fast if `--merge-batch` would leave fewer than 10 file descriptors
+/// of headroom under the process's soft open-file limit, instead of letting
+/// `chainMergeSort` run partway through a merge pass and die with its own
+/// "too many open files" error.
+fn validate_merge_batch(merge_batch: usize) -> anyhow::Result<()> {
+    if let Some(limit) = soft_open_file_limit() {
+        if merge_batch as u64 + 10 > limit {
+            return Err(anyhow::anyhow!(
+                "--merge-batch {} leaves too little headroom under the soft open-file limit ({}); \
+                 lower --merge-batch or raise it with `ulimit -n`",
+                merge_batch,
+                limit
+            ));
+        }
+    }
+
+    Ok(())
 }
 
 // command implementation
@@ -126,6 +512,18 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     //----------------------------
     // Args
     //----------------------------
+    let opt_native = args.get_flag("native");
+    let opt_native_2bit = args.get_flag("native_2bit");
+    let opt_stop_at = args.get_one::<String>("stop_at").unwrap();
+    let opt_check = args.get_flag("check");
+
+    if opt_check {
+        let bins = required_bins_for(opt_native, opt_native_2bit, opt_stop_at);
+        preflight_kent_tools(&bins)?;
+        run_cmd!(info "==> All required kent-tools binaries found in $$PATH")?;
+        return Ok(());
+    }
+
     let outdir = args.get_one::<String>("outdir").unwrap();
     if outdir != "stdout" {
         std::fs::create_dir_all(outdir)?;
@@ -135,14 +533,71 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     let opt_minscore = *args.get_one::<usize>("minscore").unwrap();
 
     let is_syn = args.get_flag("syn");
+    let opt_keep_temp = args.get_one::<String>("keep_temp");
+    let opt_parallel = *args.get_one::<usize>("parallel").unwrap();
+
+    let opt_merge_batch = *args.get_one::<usize>("merge_batch").unwrap();
+    validate_merge_batch(opt_merge_batch)?;
+
+    let opt_psl_min_match = args.get_one::<u64>("psl_min_match").copied();
+    let opt_psl_min_identity = args.get_one::<f64>("psl_min_identity").copied();
+    let is_psl_filter = opt_psl_min_match.is_some() || opt_psl_min_identity.is_some();
+
+    let opt_tsizes = args.get_one::<String>("tsizes");
+    let opt_qsizes = args.get_one::<String>("qsizes");
+    let opt_t2bit = args.get_one::<String>("t2bit");
+    let opt_q2bit = args.get_one::<String>("q2bit");
+    let opt_resume = args.get_one::<String>("resume");
+    let opt_dry_run = args.get_flag("dry_run");
+
+    if opt_dry_run {
+        // Still relative to the original cwd here; `Sizes` is the only stage
+        // with independent inputs to check freshness against.
+        let dry_target = args.get_one::<String>("target").unwrap().as_str();
+        let dry_query = args.get_one::<String>("query").unwrap().as_str();
+
+        run_cmd!(info "==> Dry run: stage plan")?;
+        for stage in PipelineStage::all() {
+            if !stage.reachable(opt_stop_at) {
+                eprintln!("[dry-run] {} - not reached (--stop-at {})", stage.label(), opt_stop_at);
+                continue;
+            }
+            let inputs: &[&str] = match stage {
+                PipelineStage::Sizes => &[dry_target, dry_query],
+                _ => &[],
+            };
+            let status = match opt_resume {
+                Some(dir) if stage.is_fresh(dir, inputs) => "skip (resume)",
+                Some(dir) if stage.is_done(dir) => "run (stale, --resume)",
+                _ => "run",
+            };
+            eprintln!("[dry-run] {} - {}", stage.label(), status);
+        }
+        return Ok(());
+    }
+
+    preflight_kent_tools(&required_bins_for(opt_native, opt_native_2bit, opt_stop_at))?;
 
     //----------------------------
     // Paths
     //----------------------------
     let curdir = std::env::current_dir()?;
     let pgr = std::env::current_exe()?.display().to_string();
-    let tempdir = tempfile::Builder::new().prefix("pgr_chain_").tempdir()?;
-    let tempdir_str = tempdir.path().to_str().unwrap();
+
+    // With `--keep-temp` or `--resume`, the working directory is a plain, uncleaned
+    // directory at the given path; otherwise it is a `TempDir` removed when
+    // `_tempdir` drops at the end of this function.
+    let mut _tempdir: Option<tempfile::TempDir> = None;
+    let tempdir_str = if let Some(dir) = opt_resume.or(opt_keep_temp) {
+        std::fs::create_dir_all(dir)?;
+        intspan::absolute_path(dir)?.display().to_string()
+    } else {
+        let tempdir = tempfile::Builder::new().prefix("pgr_chain_").tempdir()?;
+        let s = tempdir.path().to_str().unwrap().to_string();
+        _tempdir = Some(tempdir);
+        s
+    };
+    let tempdir_str = tempdir_str.as_str();
 
     run_cmd!(info "==> Paths")?;
     run_cmd!(info "    \"pgr\"     = ${pgr}")?;
@@ -157,6 +612,23 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
         .display()
         .to_string();
 
+    let abs_tsizes = opt_tsizes
+        .map(intspan::absolute_path)
+        .transpose()?
+        .map(|p| p.display().to_string());
+    let abs_qsizes = opt_qsizes
+        .map(intspan::absolute_path)
+        .transpose()?
+        .map(|p| p.display().to_string());
+    let abs_t2bit = opt_t2bit
+        .map(intspan::absolute_path)
+        .transpose()?
+        .map(|p| p.display().to_string());
+    let abs_q2bit = opt_q2bit
+        .map(intspan::absolute_path)
+        .transpose()?
+        .map(|p| p.display().to_string());
+
     let opt_tname = if let Some(tname) = args.get_one::<String>("tname") {
         if tname.is_empty() {
             "".to_string()
@@ -197,16 +669,70 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     run_cmd!(info "==> Switch to tempdir")?;
     std::env::set_current_dir(tempdir_str)?;
 
-    run_cmd!(info "==> Target .sizes anc .2bit")?;
-    run_cmd!(
-        hnsm size ${abs_target} -o target.chr.sizes;
-        faToTwoBit ${abs_target} target.chr.2bit;
-    )?;
-    run_cmd!(info "==> Query .sizes anc .2bit")?;
-    run_cmd!(
-        hnsm size ${abs_query} -o query.chr.sizes;
-        faToTwoBit ${abs_query} query.chr.2bit;
-    )?;
+    // .2bit files are only needed by axtChain itself and by the later
+    // netToAxt/axtToMaf stages; a native, chain-stopping run needs neither.
+    let need_2bit = !(opt_native && opt_stop_at == "chain");
+
+    if should_skip(PipelineStage::Sizes, opt_resume, tempdir_str, &[abs_target.as_str(), abs_query.as_str()]) {
+        run_cmd!(info "==> Target/query .sizes and .2bit (skipped, --resume)")?;
+    } else {
+        run_cmd!(info "==> Target .sizes anc .2bit")?;
+        run_stage("Target .sizes and .2bit", || {
+            match &abs_tsizes {
+                Some(src) => std::fs::copy(src, "target.chr.sizes").map(|_| ())?,
+                None => run_cmd!(hnsm size ${abs_target} -o target.chr.sizes;)?,
+            }
+            if need_2bit {
+                match &abs_t2bit {
+                    Some(src) => std::fs::copy(src, "target.chr.2bit").map(|_| ())?,
+                    None if opt_native_2bit => hnsm::write_two_bit(&abs_target, "target.chr.2bit")?,
+                    None => run_cmd!(faToTwoBit ${abs_target} target.chr.2bit;)?,
+                }
+            }
+            Ok(())
+        })?;
+        run_cmd!(info "==> Query .sizes anc .2bit")?;
+        run_stage("Query .sizes and .2bit", || {
+            match &abs_qsizes {
+                Some(src) => std::fs::copy(src, "query.chr.sizes").map(|_| ())?,
+                None => run_cmd!(hnsm size ${abs_query} -o query.chr.sizes;)?,
+            }
+            if need_2bit {
+                match &abs_q2bit {
+                    Some(src) => std::fs::copy(src, "query.chr.2bit").map(|_| ())?,
+                    None if opt_native_2bit => hnsm::write_two_bit(&abs_query, "query.chr.2bit")?,
+                    None => run_cmd!(faToTwoBit ${abs_query} query.chr.2bit;)?,
+                }
+            }
+            Ok(())
+        })?;
+    }
+
+    // With `--psl-min-match`/`--psl-min-identity` set, replace `infiles` with
+    // filtered copies before axtChain (or the `--native` chainer) ever sees
+    // them, so default behavior (neither flag set) is unchanged.
+    let infiles = if is_psl_filter {
+        std::fs::create_dir_all("pslFiltered")?;
+        infiles
+            .iter()
+            .map(|infile| -> anyhow::Result<String> {
+                let stem = get_basename(infile).unwrap();
+                let filtered = format!("pslFiltered/{}.psl", stem);
+                let dropped = super::psl::filter_psl_file(
+                    infile,
+                    &filtered,
+                    opt_psl_min_match,
+                    opt_psl_min_identity,
+                )?;
+                if dropped > 0 {
+                    run_cmd!(info "    dropped $dropped weak alignments from $stem")?;
+                }
+                Ok(filtered)
+            })
+            .collect::<anyhow::Result<Vec<String>>>()?
+    } else {
+        infiles
+    };
 
     run_cmd!(info "==> axtChain")?;
     // axtChain - Chain together axt alignments.
@@ -222,220 +748,298 @@ pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
     // options:
     //    -minScore=N - minimum score (after repeat stuff) to pass
     //    -noCheckScore=N - score that will pass without checks (speed tweak)
-    std::fs::create_dir_all("pslChain")?;
-    for infile in infiles {
-        let stem = get_basename(&infile).unwrap();
-        run_cmd!(
-            axtChain -minScore=${opt_minscore} -linearGap=${opt_lineargap} -psl ${infile} target.chr.2bit query.chr.2bit stdout |
-                chainAntiRepeat target.chr.2bit query.chr.2bit stdin pslChain/${stem}.chain
-        )?;
-    }
-
-    run_cmd!(info "==> chainMergeSort and chainPreNet")?;
-    {
-        // This step would open all .chain files and reach system's maxfile limit.
-        // So merge 100 files a time.
-        //
-        // chainMergeSort - Combine sorted files into larger sorted file
-        // usage:
-        //    chainMergeSort file(s)
-        // Output goes to standard output
-        // options:
-        //    -saveId - keep the existing chain ids.
-        //    -inputList=somefile - somefile contains list of input chain files.
-        //    -tempDir=somedir/ - somedir has space for temporary sorting data, default ./
-        let mut files = list_files_ext("pslChain", "chain")?;
-        let mut sn = 1;
-        let mut merge_files = vec![];
-        while !files.is_empty() {
-            let batching: Vec<_> = files.drain(0..100.min(files.len())).collect();
-
-            intspan::write_lines(
-                "chainList.tmp",
-                &batching.iter().map(AsRef::as_ref).collect(),
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(opt_parallel)
+        .build()?;
+    let log_lock = std::sync::Mutex::new(());
+
+    if should_skip(PipelineStage::AxtChain, opt_resume, tempdir_str, &[]) {
+        run_cmd!(info "==> axtChain (skipped, --resume)")?;
+    } else {
+        run_stage("axtChain", || {
+            std::fs::create_dir_all("pslChain")?;
+
+            if opt_native {
+                // Pure-Rust replacement: parse each PSL file's records and chain
+                // their blocks directly, skipping axtChain/chainAntiRepeat.
+                let gap_cost = hnsm::GapCost::from_name(opt_lineargap)?;
+                for infile in &infiles {
+                    let stem = get_basename(infile).unwrap();
+                    let records = hnsm::read_psl(infile)?;
+                    let chains = hnsm::chain_psl(&records, gap_cost);
+
+                    let file = std::fs::File::create(format!("pslChain/{}.chain", stem))?;
+                    let mut w = std::io::BufWriter::new(file);
+                    for chain in &chains {
+                        chain.write(&mut w)?;
+                    }
+                }
+            } else {
+                pool.install(|| {
+                    infiles.par_iter().try_for_each(|infile| -> anyhow::Result<()> {
+                        let stem = get_basename(infile).unwrap();
+                        let chain_err = format!("pslChain/{}.axtChain.stderr", stem);
+                        let anti_err = format!("pslChain/{}.chainAntiRepeat.stderr", stem);
+                        run_cmd!(
+                            axtChain -minScore=${opt_minscore} -linearGap=${opt_lineargap} -psl ${infile} target.chr.2bit query.chr.2bit stdout 2> ${chain_err} |
+                                chainAntiRepeat target.chr.2bit query.chr.2bit stdin pslChain/${stem}.chain 2> ${anti_err}
+                        )?;
+                        log_stderr(&log_lock, &format!("axtChain {}", stem), &chain_err);
+                        log_stderr(&log_lock, &format!("chainAntiRepeat {}", stem), &anti_err);
+                        Ok(())
+                    })
+                })?;
+            }
+            Ok(())
+        })?;
+    }
+
+    if should_skip(PipelineStage::ChainMergeSort, opt_resume, tempdir_str, &[]) {
+        run_cmd!(info "==> chainMergeSort and chainPreNet (skipped, --resume)")?;
+    } else {
+        run_cmd!(info "==> chainMergeSort and chainPreNet")?;
+        run_stage("chainMergeSort and chainPreNet", || {
+            // This step would open all .chain files and reach system's maxfile limit.
+            // So merge --merge-batch (default 100) files a time.
+            //
+            // chainMergeSort - Combine sorted files into larger sorted file
+            // usage:
+            //    chainMergeSort file(s)
+            // Output goes to standard output
+            // options:
+            //    -saveId - keep the existing chain ids.
+            //    -inputList=somefile - somefile contains list of input chain files.
+            //    -tempDir=somedir/ - somedir has space for temporary sorting data, default ./
+            let mut files = list_files_ext("pslChain", "chain")?;
+            let mut sn = 1;
+            let mut merge_files = vec![];
+            while !files.is_empty() {
+                let batching: Vec<_> = files.drain(0..opt_merge_batch.min(files.len())).collect();
+
+                intspan::write_lines(
+                    "chainList.tmp",
+                    &batching.iter().map(AsRef::as_ref).collect(),
+                )?;
+                run_cmd!(
+                    chainMergeSort -inputList=chainList.tmp > all.${sn}.chain.tmp
+                )?;
+                merge_files.push(format!("all.{}.chain.tmp", sn));
+
+                sn += 1;
+            }
+
+            run_cmd!(
+                chainMergeSort $[merge_files] > all.chain
             )?;
+
+            // chainPreNet - Remove chains that don't have a chance of being netted
+            // usage:
+            //   chainPreNet in.chain target.sizes query.sizes out.chain
             run_cmd!(
-                chainMergeSort -inputList=chainList.tmp > all.${sn}.chain.tmp
+                chainPreNet all.chain target.chr.sizes query.chr.sizes all.pre.chain
             )?;
-            merge_files.push(format!("all.{}.chain.tmp", sn));
+            Ok(())
+        })?;
+    }
 
-            sn += 1;
+    if opt_stop_at == "chain" {
+        run_cmd!(info "==> Stopping after the chain stage (--stop-at chain)")?;
+        if abs_outdir == "stdout" {
+            let content = std::fs::read_to_string("all.pre.chain")?;
+            print!("{}", content);
+        } else {
+            std::fs::copy("all.pre.chain", format!("{}/all.pre.chain", abs_outdir))?;
         }
 
-        run_cmd!(
-            chainMergeSort $[merge_files] > all.chain
-        )?;
-
-        // chainPreNet - Remove chains that don't have a chance of being netted
-        // usage:
-        //   chainPreNet in.chain target.sizes query.sizes out.chain
-        run_cmd!(
-            chainPreNet all.chain target.chr.sizes query.chr.sizes all.pre.chain
-        )?;
-    }
-
-    run_cmd!(info "==> chain-net")?;
-    {
-        // chainNet - Make alignment nets out of chains
-        // usage:
-        //   chainNet in.chain target.sizes query.sizes target.net query.net
-        //
-        // netSyntenic - Add synteny info to net.
-        // usage:
-        //   netSyntenic in.net out.net
-        run_cmd!(
-            chainNet -minSpace=1 all.pre.chain target.chr.sizes query.chr.sizes stdout query.chainnet |
-                netSyntenic stdin noClass.net
-        )?;
-
-        // netChainSubset - Create chain file with subset of chains that appear in
-        // the net
-        // usage:
-        //    netChainSubset in.net in.chain out.chain
-        // options:
-        //    -gapOut=gap.tab - Output gap sizes to file
-        //    -type=XXX - Restrict output to particular type in net file
-        //    -splitOnInsert - Split chain when get an insertion of another chain
-        //    -wholeChains - Write entire chain references by net, don't split
-        //     when a high-level net is encoundered.  This is useful when nets
-        //     have been filtered.
-        //    -skipMissing - skip chains that are not found instead of generating
-        //     an error.  Useful if chains have been filtered.
-        //
-        // chainStitchId - Join chain fragments with the same chain ID into a single
-        //    chain per ID.  Chain fragments must be from same original chain but
-        //    must not overlap.  Chain fragment scores are summed.
-        // usage:
-        //    chainStitchId in.chain out.chain
-        run_cmd!(
-            netChainSubset -verbose=0 noClass.net all.chain stdout |
-                chainStitchId stdin over.chain
-        )?;
-
-        // netSplit - Split a genome net file into chromosome net files
-        // usage:
-        //   netSplit in.net outDir
-        std::fs::create_dir_all("net")?;
-        run_cmd!(
-            netSplit noClass.net net > /dev/null
-        )?;
-    }
-
-    run_cmd!(info "==> netToAxt")?;
-    {
-        std::fs::create_dir_all("axtNet")?;
-
-        let files = list_files_ext("net", "net")?;
-
-        // netToAxt - Convert net (and chain) to axt.
-        // usage:
-        //   netToAxt in.net in.chain target.2bit query.2bit out.axt
-        // note:
-        // directories full of .nib files (an older format)
-        // may also be used in place of target.2bit and query.2bit.
-        //
-        // axtSort - Sort axt files
-        // usage:
-        //   axtSort in.axt out.axt
-        for file in files {
-            let stem = get_basename(&file).unwrap();
+        std::env::set_current_dir(&curdir)?;
+        return Ok(());
+    }
+
+    if should_skip(PipelineStage::ChainNet, opt_resume, tempdir_str, &[]) {
+        run_cmd!(info "==> chain-net (skipped, --resume)")?;
+    } else {
+        run_cmd!(info "==> chain-net")?;
+        run_stage("chain-net", || {
+            // chainNet - Make alignment nets out of chains
+            // usage:
+            //   chainNet in.chain target.sizes query.sizes target.net query.net
+            //
+            // netSyntenic - Add synteny info to net.
+            // usage:
+            //   netSyntenic in.net out.net
+            run_cmd!(
+                chainNet -minSpace=1 all.pre.chain target.chr.sizes query.chr.sizes stdout query.chainnet |
+                    netSyntenic stdin noClass.net
+            )?;
+
+            // netChainSubset - Create chain file with subset of chains that appear in
+            // the net
+            // usage:
+            //    netChainSubset in.net in.chain out.chain
+            // options:
+            //    -gapOut=gap.tab - Output gap sizes to file
+            //    -type=XXX - Restrict output to particular type in net file
+            //    -splitOnInsert - Split chain when get an insertion of another chain
+            //    -wholeChains - Write entire chain references by net, don't split
+            //     when a high-level net is encoundered.  This is useful when nets
+            //     have been filtered.
+            //    -skipMissing - skip chains that are not found instead of generating
+            //     an error.  Useful if chains have been filtered.
+            //
+            // chainStitchId - Join chain fragments with the same chain ID into a single
+            //    chain per ID.  Chain fragments must be from same original chain but
+            //    must not overlap.  Chain fragment scores are summed.
+            // usage:
+            //    chainStitchId in.chain out.chain
             run_cmd!(
-                netToAxt ${file} all.pre.chain target.chr.2bit query.chr.2bit stdout |
-                    axtSort stdin axtNet/${stem}.axt
+                netChainSubset -verbose=0 noClass.net all.chain stdout |
+                    chainStitchId stdin over.chain
             )?;
-        }
+
+            // netSplit - Split a genome net file into chromosome net files
+            // usage:
+            //   netSplit in.net outDir
+            std::fs::create_dir_all("net")?;
+            run_cmd!(
+                netSplit noClass.net net > /dev/null
+            )?;
+            Ok(())
+        })?;
     }
 
-    run_cmd!(info "==> axt-maf")?;
-    if !is_syn {
-        run_cmd!(info "==> axtToMaf")?;
+    if should_skip(PipelineStage::NetToAxt, opt_resume, tempdir_str, &[]) {
+        run_cmd!(info "==> netToAxt (skipped, --resume)")?;
+    } else {
+        run_cmd!(info "==> netToAxt")?;
+        run_stage("netToAxt", || {
+            std::fs::create_dir_all("axtNet")?;
 
-        let files = list_files_ext("axtNet", "axt")?;
-        for file in files {
-            let stem = get_basename(&file).unwrap();
-            if abs_outdir == "stdout" {
-                if opt_tname.is_empty() {
-                    run_cmd!(
-                        axtToMaf ${file} target.chr.sizes query.chr.sizes stdout
-                    )?;
-                } else {
-                    run_cmd!(
-                        axtToMaf -tPrefix=${opt_tname} -qPrefix=${opt_qname} ${file} target.chr.sizes query.chr.sizes stdout
-                    )?;
-                }
-            } else {
-                if opt_tname.is_empty() {
+            let files = list_files_ext("net", "net")?;
+
+            // netToAxt - Convert net (and chain) to axt.
+            // usage:
+            //   netToAxt in.net in.chain target.2bit query.2bit out.axt
+            // note:
+            // directories full of .nib files (an older format)
+            // may also be used in place of target.2bit and query.2bit.
+            //
+            // axtSort - Sort axt files
+            // usage:
+            //   axtSort in.axt out.axt
+            pool.install(|| {
+                files.par_iter().try_for_each(|file| -> anyhow::Result<()> {
+                    let stem = get_basename(file).unwrap();
+                    let net_err = format!("axtNet/{}.netToAxt.stderr", stem);
+                    let sort_err = format!("axtNet/{}.axtSort.stderr", stem);
                     run_cmd!(
-                        axtToMaf ${file} target.chr.sizes query.chr.sizes ${abs_outdir}/${stem}.maf
+                        netToAxt ${file} all.pre.chain target.chr.2bit query.chr.2bit stdout 2> ${net_err} |
+                            axtSort stdin axtNet/${stem}.axt 2> ${sort_err}
                     )?;
+                    log_stderr(&log_lock, &format!("netToAxt {}", stem), &net_err);
+                    log_stderr(&log_lock, &format!("axtSort {}", stem), &sort_err);
+                    Ok(())
+                })
+            })?;
+            Ok(())
+        })?;
+    }
+
+    run_cmd!(info "==> axt-maf")?;
+    if !is_syn {
+        run_stage("axtToMaf", || {
+            run_cmd!(info "==> axtToMaf")?;
+
+            let files = list_files_ext("axtNet", "axt")?;
+            for file in files {
+                let stem = get_basename(&file).unwrap();
+                if abs_outdir == "stdout" {
+                    if opt_tname.is_empty() {
+                        run_cmd!(
+                            axtToMaf ${file} target.chr.sizes query.chr.sizes stdout
+                        )?;
+                    } else {
+                        run_cmd!(
+                            axtToMaf -tPrefix=${opt_tname} -qPrefix=${opt_qname} ${file} target.chr.sizes query.chr.sizes stdout
+                        )?;
+                    }
                 } else {
-                    run_cmd!(
-                        axtToMaf -tPrefix=${opt_tname} -qPrefix=${opt_qname} ${file} target.chr.sizes query.chr.sizes ${abs_outdir}/${stem}.maf
-                    )?;
+                    if opt_tname.is_empty() {
+                        run_cmd!(
+                            axtToMaf ${file} target.chr.sizes query.chr.sizes ${abs_outdir}/${stem}.maf
+                        )?;
+                    } else {
+                        run_cmd!(
+                            axtToMaf -tPrefix=${opt_tname} -qPrefix=${opt_qname} ${file} target.chr.sizes query.chr.sizes ${abs_outdir}/${stem}.maf
+                        )?;
+                    }
                 }
             }
-        }
+            Ok(())
+        })?;
     } else {
-        std::fs::create_dir_all("synNet")?;
-        std::fs::create_dir_all("chain")?;
-
-        run_cmd!(info "==> synNet.maf")?;
-
-        // netFilter - Filter out parts of net.  What passes
-        // filter goes to standard output.  Note a net is a
-        // recursive data structure.  If a parent fails to pass
-        // the filter, the children are not even considered.
-        // usage:
-        //    netFilter in.net(s)
-        run_cmd!(
-            netFilter -syn noClass.net |
-                netSplit stdin synNet > /dev/null
-        )?;
-
-        // chainSplit - Split chains up by target or query sequence
-        // usage:
-        //    chainSplit outDir inChain(s)
-        // options:
-        //    -q  - Split on query (default is on target)
-        //    -lump=N  Lump together so have only N split files.
-        run_cmd!(
-            chainSplit synNet all.chain
-        )?;
-
-        let files = list_files_ext("synNet", "net")?;
-        for file in files {
-            let stem = get_basename(&file).unwrap();
-            let chain_file = format!("{}.chain", file.strip_suffix(".net").unwrap());
-            if abs_outdir == "stdout" {
-                if opt_tname.is_empty() {
-                    run_cmd!(
-                        netToAxt ${file} ${chain_file} target.chr.2bit query.chr.2bit stdout |
-                            axtSort stdin stdout |
-                            axtToMaf stdin target.chr.sizes query.chr.sizes stdout
-                    )?;
-                } else {
-                    run_cmd!(
-                        netToAxt ${file} ${chain_file} target.chr.2bit query.chr.2bit stdout |
-                            axtSort stdin stdout |
-                            axtToMaf -tPrefix=${opt_tname} -qPrefix=${opt_qname} stdin target.chr.sizes query.chr.sizes stdout
-                    )?;
-                }
-            } else {
-                if opt_tname.is_empty() {
-                    run_cmd!(
-                        netToAxt ${file} ${chain_file} target.chr.2bit query.chr.2bit stdout |
-                            axtSort stdin stdout |
-                            axtToMaf stdin target.chr.sizes query.chr.sizes ${abs_outdir}/${stem}.maf
-                    )?;
+        run_stage("synNet.maf", || {
+            std::fs::create_dir_all("synNet")?;
+            std::fs::create_dir_all("chain")?;
+
+            run_cmd!(info "==> synNet.maf")?;
+
+            // netFilter - Filter out parts of net.  What passes
+            // filter goes to standard output.  Note a net is a
+            // recursive data structure.  If a parent fails to pass
+            // the filter, the children are not even considered.
+            // usage:
+            //    netFilter in.net(s)
+            run_cmd!(
+                netFilter -syn noClass.net |
+                    netSplit stdin synNet > /dev/null
+            )?;
+
+            // chainSplit - Split chains up by target or query sequence
+            // usage:
+            //    chainSplit outDir inChain(s)
+            // options:
+            //    -q  - Split on query (default is on target)
+            //    -lump=N  Lump together so have only N split files.
+            run_cmd!(
+                chainSplit synNet all.chain
+            )?;
+
+            let files = list_files_ext("synNet", "net")?;
+            for file in files {
+                let stem = get_basename(&file).unwrap();
+                let chain_file = format!("{}.chain", file.strip_suffix(".net").unwrap());
+                if abs_outdir == "stdout" {
+                    if opt_tname.is_empty() {
+                        run_cmd!(
+                            netToAxt ${file} ${chain_file} target.chr.2bit query.chr.2bit stdout |
+                                axtSort stdin stdout |
+                                axtToMaf stdin target.chr.sizes query.chr.sizes stdout
+                        )?;
+                    } else {
+                        run_cmd!(
+                            netToAxt ${file} ${chain_file} target.chr.2bit query.chr.2bit stdout |
+                                axtSort stdin stdout |
+                                axtToMaf -tPrefix=${opt_tname} -qPrefix=${opt_qname} stdin target.chr.sizes query.chr.sizes stdout
+                        )?;
+                    }
                 } else {
-                    run_cmd!(
-                        netToAxt ${file} ${chain_file} target.chr.2bit query.chr.2bit stdout |
-                            axtSort stdin stdout |
-                            axtToMaf -tPrefix=${opt_tname} -qPrefix=${opt_qname} stdin target.chr.sizes query.chr.sizes ${abs_outdir}/${stem}.maf
-                    )?;
+                    if opt_tname.is_empty() {
+                        run_cmd!(
+                            netToAxt ${file} ${chain_file} target.chr.2bit query.chr.2bit stdout |
+                                axtSort stdin stdout |
+                                axtToMaf stdin target.chr.sizes query.chr.sizes ${abs_outdir}/${stem}.maf
+                        )?;
+                    } else {
+                        run_cmd!(
+                            netToAxt ${file} ${chain_file} target.chr.2bit query.chr.2bit stdout |
+                                axtSort stdin stdout |
+                                axtToMaf -tPrefix=${opt_tname} -qPrefix=${opt_qname} stdin target.chr.sizes query.chr.sizes ${abs_outdir}/${stem}.maf
+                        )?;
+                    }
                 }
             }
-        }
+            Ok(())
+        })?;
     }
 
     //----------------------------
@@ -477,3 +1081,132 @@ fn get_basename(file_path: &str) -> Option<String> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preflight_reports_all_missing_bins() {
+        let saved_path = std::env::var("PATH").ok();
+        std::env::set_var("PATH", "/nonexistent-bin-dir");
+
+        let result = preflight_kent_tools(REQUIRED_BINS);
+
+        if let Some(path) = saved_path {
+            std::env::set_var("PATH", path);
+        }
+
+        let err = result.expect_err("all kent-tools binaries should be reported missing");
+        let message = err.to_string();
+        for bin in REQUIRED_BINS {
+            assert!(message.contains(bin), "{} missing from: {}", bin, message);
+        }
+    }
+
+    #[test]
+    fn required_bins_for_native_drops_axtchain_and_chainantirepeat() {
+        let bins = required_bins_for(true, false, "maf");
+        assert!(!bins.contains(&"axtChain"));
+        assert!(!bins.contains(&"chainAntiRepeat"));
+        assert!(bins.contains(&"chainMergeSort"));
+        assert!(bins.contains(&"netToAxt"));
+    }
+
+    #[test]
+    fn required_bins_for_native_2bit_drops_fatotwobit() {
+        let bins = required_bins_for(false, true, "maf");
+        assert!(!bins.contains(&"faToTwoBit"));
+        assert!(bins.contains(&"axtChain"));
+    }
+
+    #[test]
+    fn merge_batch_defaults_to_100() {
+        let cmd = make_subcommand();
+        let matches = cmd
+            .try_get_matches_from(vec!["chain", "--check"])
+            .unwrap();
+        assert_eq!(*matches.get_one::<usize>("merge_batch").unwrap(), 100);
+    }
+
+    #[test]
+    fn validate_merge_batch_rejects_a_batch_too_close_to_the_soft_limit() {
+        // Without a readable /proc/self/limits (non-Linux), validation is a no-op;
+        // this only asserts the behavior where the limit IS known.
+        if let Some(limit) = soft_open_file_limit() {
+            assert!(validate_merge_batch(limit as usize).is_err());
+            assert!(validate_merge_batch(1).is_ok());
+        }
+    }
+
+    #[test]
+    fn is_fresh_true_when_sentinel_newer_than_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("target.fa");
+        std::fs::write(&input, b">a\nACGT\n").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        std::fs::write(dir.path().join("target.chr.sizes"), b"a\t4\n").unwrap();
+
+        let dir_str = dir.path().to_str().unwrap();
+        let input_str = input.to_str().unwrap();
+        assert!(PipelineStage::Sizes.is_fresh(dir_str, &[input_str]));
+    }
+
+    #[test]
+    fn is_fresh_false_when_an_input_was_modified_after_the_sentinel() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("target.chr.sizes"), b"a\t4\n").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let input = dir.path().join("target.fa");
+        std::fs::write(&input, b">a\nACGT\n").unwrap();
+
+        let dir_str = dir.path().to_str().unwrap();
+        let input_str = input.to_str().unwrap();
+        assert!(!PipelineStage::Sizes.is_fresh(dir_str, &[input_str]));
+    }
+
+    #[test]
+    fn is_fresh_ignores_inputs_for_stages_without_independent_upstream_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("pslChain"), b"").unwrap();
+        let dir_str = dir.path().to_str().unwrap();
+        assert!(PipelineStage::AxtChain.is_fresh(dir_str, &[]));
+    }
+
+    #[test]
+    fn check_flag_does_not_require_positional_args() {
+        let cmd = make_subcommand();
+        let matches = cmd
+            .try_get_matches_from(vec!["chain", "--check"])
+            .expect("--check should not require target/query/psl");
+        assert!(matches.get_flag("check"));
+    }
+
+    #[test]
+    fn check_flag_reports_missing_bins_without_touching_target_query_psl() {
+        let saved_path = std::env::var("PATH").ok();
+        std::env::set_var("PATH", "/nonexistent-bin-dir");
+
+        let cmd = make_subcommand();
+        let matches = cmd.try_get_matches_from(vec!["chain", "--check"]).unwrap();
+        let result = execute(&matches);
+
+        if let Some(path) = saved_path {
+            std::env::set_var("PATH", path);
+        }
+
+        let err = result.expect_err("missing kent-tools should be reported");
+        assert!(err.to_string().contains("axtChain"));
+    }
+
+    #[test]
+    fn required_bins_for_stop_at_chain_drops_downstream_bins() {
+        let bins = required_bins_for(false, false, "chain");
+        assert!(bins.contains(&"axtChain"));
+        assert!(bins.contains(&"chainMergeSort"));
+        assert!(bins.contains(&"chainPreNet"));
+        assert!(!bins.contains(&"chainNet"));
+        assert!(!bins.contains(&"netToAxt"));
+        assert!(!bins.contains(&"faToTwoBit"));
+    }
+}
@@ -2,5 +2,7 @@
 
 pub mod chain;
 pub mod ir;
+pub mod psl;
 pub mod rept;
+pub mod stat;
 pub mod trf;
@@ -0,0 +1,173 @@
+//! Optional pre-filtering of PSL records before they reach `axtChain` (or the
+//! `--native` chainer), used by `pgr chain`'s `--psl-min-match`/
+//! `--psl-min-identity` flags.
+//!
+//! Parsing reuses the existing `hnsm::{read_psl, PslRecord}` (see
+//! `libs/psl.rs`) rather than a second parser; this module only adds the
+//! filtering predicate and the serializer needed to write a filtered PSL
+//! file back out for `axtChain` to read.
+
+use hnsm::PslRecord;
+use std::io::Write;
+
+/// Fraction of aligned bases that match, in `[0.0, 1.0]`; `0.0` for a record
+/// with no aligned bases at all.
+pub fn identity(record: &PslRecord) -> f64 {
+    let aligned = record.matches + record.mismatches;
+    if aligned == 0 {
+        0.0
+    } else {
+        record.matches as f64 / aligned as f64
+    }
+}
+
+/// Drops records with fewer than `min_match` matching bases or an identity
+/// below `min_identity`; `None` disables the corresponding check.
+pub fn filter_records(
+    records: Vec<PslRecord>,
+    min_match: Option<u64>,
+    min_identity: Option<f64>,
+) -> Vec<PslRecord> {
+    records
+        .into_iter()
+        .filter(|r| match min_match {
+            Some(m) => r.matches >= m,
+            None => true,
+        })
+        .filter(|r| match min_identity {
+            Some(i) => identity(r) >= i,
+            None => true,
+        })
+        .collect()
+}
+
+/// Serializes one record back to a tab-separated PSL data line, the inverse
+/// of `PslRecord::parse`.
+pub fn write_record(w: &mut impl Write, r: &PslRecord) -> std::io::Result<()> {
+    let join = |v: &[u64]| -> String {
+        v.iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+            + ","
+    };
+    writeln!(
+        w,
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        r.matches,
+        r.mismatches,
+        r.rep_matches,
+        r.n_count,
+        r.q_num_insert,
+        r.q_base_insert,
+        r.t_num_insert,
+        r.t_base_insert,
+        r.strand,
+        r.q_name,
+        r.q_size,
+        r.q_start,
+        r.q_end,
+        r.t_name,
+        r.t_size,
+        r.t_start,
+        r.t_end,
+        r.block_count,
+        join(&r.block_sizes),
+        join(&r.q_starts),
+        join(&r.t_starts),
+    )
+}
+
+/// Reads `infile`, drops records failing `min_match`/`min_identity`, and
+/// writes the survivors to `outfile` as PSL. Returns the number of records
+/// dropped, for logging.
+pub fn filter_psl_file(
+    infile: &str,
+    outfile: &str,
+    min_match: Option<u64>,
+    min_identity: Option<f64>,
+) -> anyhow::Result<usize> {
+    let records = hnsm::read_psl(infile)?;
+    let before = records.len();
+    let kept = filter_records(records, min_match, min_identity);
+    let dropped = before - kept.len();
+
+    let file = std::fs::File::create(outfile)?;
+    let mut w = std::io::BufWriter::new(file);
+    for r in &kept {
+        write_record(&mut w, r)?;
+    }
+
+    Ok(dropped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(matches: u64, mismatches: u64) -> PslRecord {
+        PslRecord {
+            matches,
+            mismatches,
+            rep_matches: 0,
+            n_count: 0,
+            q_num_insert: 0,
+            q_base_insert: 0,
+            t_num_insert: 0,
+            t_base_insert: 0,
+            strand: "+".to_string(),
+            q_name: "q".to_string(),
+            q_size: matches + mismatches,
+            q_start: 0,
+            q_end: matches + mismatches,
+            t_name: "t".to_string(),
+            t_size: matches + mismatches,
+            t_start: 0,
+            t_end: matches + mismatches,
+            block_count: 1,
+            block_sizes: vec![matches + mismatches],
+            q_starts: vec![0],
+            t_starts: vec![0],
+        }
+    }
+
+    #[test]
+    fn identity_is_zero_for_a_record_with_no_aligned_bases() {
+        assert_eq!(identity(&record(0, 0)), 0.0);
+    }
+
+    #[test]
+    fn filter_records_drops_below_thresholds_and_keeps_the_rest() {
+        let records = vec![
+            record(100, 0),  // identity 1.0, matches 100
+            record(50, 50),  // identity 0.5, matches 50
+            record(10, 90),  // identity 0.1, matches 10
+        ];
+
+        let kept = filter_records(records, Some(20), Some(0.6));
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].matches, 100);
+    }
+
+    #[test]
+    fn filter_records_is_a_no_op_when_both_thresholds_are_none() {
+        let records = vec![record(100, 0), record(0, 100)];
+        let kept = filter_records(records, None, None);
+        assert_eq!(kept.len(), 2);
+    }
+
+    #[test]
+    fn write_record_round_trips_through_parse() {
+        let mut buf = Vec::new();
+        let r = record(90, 10);
+        write_record(&mut buf, &r).unwrap();
+
+        let line = String::from_utf8(buf).unwrap();
+        let parsed = PslRecord::parse(line.trim_end()).unwrap();
+        assert_eq!(parsed.matches, r.matches);
+        assert_eq!(parsed.mismatches, r.mismatches);
+        assert_eq!(parsed.block_sizes, r.block_sizes);
+        assert_eq!(parsed.q_starts, r.q_starts);
+        assert_eq!(parsed.t_starts, r.t_starts);
+    }
+}
@@ -0,0 +1,190 @@
+use clap::*;
+use std::collections::BTreeMap;
+
+// Create clap subcommand arguments
+pub fn make_subcommand() -> Command {
+    Command::new("stat")
+        .about("Per-chromosome coverage and identity from pgr chain's axt/maf output")
+        .after_help(
+            r###"
+* <sizes> is the target .sizes file, e.g. the target.chr.sizes written by
+  `pgr chain`
+
+* <infiles> are the per-chromosome axt/maf files under `pgr chain`'s
+  axtNet/ or maf output directory; a file's basename up to the first `.`
+  is taken as its target chromosome, matching the <chrom>.axt / <chrom>.maf
+  naming that `pgr chain` itself writes
+    * .gz is supported
+    * axt files with no alignments (an empty chromosome) are fine; the
+      chromosome still gets a zeroed row from <sizes>
+
+* --format is guessed from each file's extension; pass it explicitly when
+  a file's extension doesn't match its content
+
+* identity is gap-compressed - see `hnsm::gap_compressed_stat`
+
+"###,
+        )
+        .arg(
+            Arg::new("sizes")
+                .required(true)
+                .index(1)
+                .help("The target .sizes file"),
+        )
+        .arg(
+            Arg::new("infiles")
+                .required(true)
+                .num_args(1..)
+                .index(2)
+                .help("Set the input axt/maf files to use"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .num_args(1)
+                .value_parser([
+                    builder::PossibleValue::new("auto"),
+                    builder::PossibleValue::new("axt"),
+                    builder::PossibleValue::new("maf"),
+                ])
+                .default_value("auto")
+                .help("Alignment format of <infiles>"),
+        )
+        .arg(
+            Arg::new("outfile")
+                .long("outfile")
+                .short('o')
+                .num_args(1)
+                .default_value("stdout")
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+#[derive(Default, Clone)]
+struct ChrStat {
+    stat: hnsm::GapCompressedStat,
+    nets: u64,
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> anyhow::Result<()> {
+    let sizes = intspan::read_sizes(args.get_one::<String>("sizes").unwrap());
+    let opt_format = args.get_one::<String>("format").unwrap();
+    let mut writer = intspan::writer(args.get_one::<String>("outfile").unwrap());
+
+    // Every chromosome in .sizes gets a row, even one with no matching infile.
+    let mut by_chr: BTreeMap<String, ChrStat> = sizes
+        .keys()
+        .map(|name| (name.clone(), ChrStat::default()))
+        .collect();
+
+    for infile in args.get_many::<String>("infiles").unwrap() {
+        let chr = stem_of(infile);
+        let format = if opt_format == "auto" {
+            detect_format(infile)?
+        } else {
+            opt_format.to_string()
+        };
+
+        let entry = by_chr.entry(chr).or_default();
+        let mut reader = intspan::reader(infile);
+
+        match format.as_str() {
+            "axt" => {
+                let tname = "target".to_string();
+                let qname = "query".to_string();
+                while let Ok(block) = hnsm::next_axt_block(&mut reader, &sizes, &tname, &qname) {
+                    if block.entries.len() != 2 {
+                        continue;
+                    }
+                    entry
+                        .stat
+                        .merge(&hnsm::gap_compressed_stat(
+                            block.entries[0].seq(),
+                            block.entries[1].seq(),
+                        ));
+                    entry.nets += 1;
+                }
+            }
+            "maf" => {
+                while let Ok(block) = hnsm::next_maf_block(&mut reader) {
+                    if block.entries.len() != 2 {
+                        continue;
+                    }
+                    entry.stat.merge(&hnsm::gap_compressed_stat(
+                        &block.entries[0].alignment,
+                        &block.entries[1].alignment,
+                    ));
+                    entry.nets += 1;
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    writer.write_all("chr\taligned_bp\tcoverage\tmismatches\tidentity\tnets\n".as_ref())?;
+
+    let mut total = ChrStat::default();
+    for (chr, chr_stat) in &by_chr {
+        let size = *sizes.get(chr).unwrap_or(&0);
+        let coverage = if size > 0 {
+            chr_stat.stat.aligned_bp as f64 / size as f64
+        } else {
+            0.0
+        };
+        writer.write_fmt(format_args!(
+            "{}\t{}\t{:.4}\t{}\t{:.4}\t{}\n",
+            chr,
+            chr_stat.stat.aligned_bp,
+            coverage,
+            chr_stat.stat.mismatches,
+            chr_stat.stat.identity(),
+            chr_stat.nets,
+        ))?;
+        total.stat.merge(&chr_stat.stat);
+        total.nets += chr_stat.nets;
+    }
+
+    let total_size: i32 = sizes.values().sum();
+    let total_coverage = if total_size > 0 {
+        total.stat.aligned_bp as f64 / total_size as f64
+    } else {
+        0.0
+    };
+    writer.write_fmt(format_args!(
+        "total\t{}\t{:.4}\t{}\t{:.4}\t{}\n",
+        total.stat.aligned_bp,
+        total_coverage,
+        total.stat.mismatches,
+        total.stat.identity(),
+        total.nets,
+    ))?;
+
+    Ok(())
+}
+
+/// Basename of `path` up to its first `.`, e.g. `axtNet/I.axt` -> `I`,
+/// mirroring the `<chrom>.axt` / `<chrom>.maf` naming `pgr chain` writes.
+fn stem_of(path: &str) -> String {
+    match std::path::Path::new(path)
+        .file_name()
+        .and_then(std::ffi::OsStr::to_str)
+    {
+        Some(name) => name.split('.').next().unwrap().to_string(),
+        None => path.to_string(),
+    }
+}
+
+fn detect_format(path: &str) -> anyhow::Result<String> {
+    let lower = path.to_ascii_lowercase();
+    if lower.contains(".axt") {
+        Ok("axt".to_string())
+    } else if lower.contains(".maf") {
+        Ok("maf".to_string())
+    } else {
+        Err(anyhow::anyhow!(
+            "Cannot guess the format of `{}` from its extension; pass --format",
+            path
+        ))
+    }
+}
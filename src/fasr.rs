@@ -14,9 +14,11 @@ fn main() -> anyhow::Result<()> {
         .subcommand(cmd_fasr::axt2fas::make_subcommand())
         .subcommand(cmd_fasr::check::make_subcommand())
         .subcommand(cmd_fasr::concat::make_subcommand())
+        .subcommand(cmd_fasr::conserve::make_subcommand())
         .subcommand(cmd_fasr::consensus::make_subcommand())
         .subcommand(cmd_fasr::cover::make_subcommand())
         .subcommand(cmd_fasr::create::make_subcommand())
+        .subcommand(cmd_fasr::fas2maf::make_subcommand())
         .subcommand(cmd_fasr::filter::make_subcommand())
         .subcommand(cmd_fasr::join::make_subcommand())
         .subcommand(cmd_fasr::link::make_subcommand())
@@ -27,10 +29,13 @@ fn main() -> anyhow::Result<()> {
         .subcommand(cmd_fasr::replace::make_subcommand())
         .subcommand(cmd_fasr::separate::make_subcommand())
         .subcommand(cmd_fasr::slice::make_subcommand())
+        .subcommand(cmd_fasr::snp::make_subcommand())
         .subcommand(cmd_fasr::split::make_subcommand())
         .subcommand(cmd_fasr::stat::make_subcommand())
         .subcommand(cmd_fasr::subset::make_subcommand())
+        .subcommand(cmd_fasr::trim::make_subcommand())
         .subcommand(cmd_fasr::variation::make_subcommand())
+        .subcommand(cmd_fasr::vcf::make_subcommand())
         .subcommand(cmd_fasr::xlsx::make_subcommand());
 
     // Check which subcomamnd the user ran...
@@ -38,9 +43,11 @@ fn main() -> anyhow::Result<()> {
         Some(("axt2fas", sub_matches)) => cmd_fasr::axt2fas::execute(sub_matches),
         Some(("check", sub_matches)) => cmd_fasr::check::execute(sub_matches),
         Some(("concat", sub_matches)) => cmd_fasr::concat::execute(sub_matches),
+        Some(("conserve", sub_matches)) => cmd_fasr::conserve::execute(sub_matches),
         Some(("consensus", sub_matches)) => cmd_fasr::consensus::execute(sub_matches),
         Some(("cover", sub_matches)) => cmd_fasr::cover::execute(sub_matches),
         Some(("create", sub_matches)) => cmd_fasr::create::execute(sub_matches),
+        Some(("fas2maf", sub_matches)) => cmd_fasr::fas2maf::execute(sub_matches),
         Some(("filter", sub_matches)) => cmd_fasr::filter::execute(sub_matches),
         Some(("join", sub_matches)) => cmd_fasr::join::execute(sub_matches),
         Some(("link", sub_matches)) => cmd_fasr::link::execute(sub_matches),
@@ -51,10 +58,13 @@ fn main() -> anyhow::Result<()> {
         Some(("replace", sub_matches)) => cmd_fasr::replace::execute(sub_matches),
         Some(("separate", sub_matches)) => cmd_fasr::separate::execute(sub_matches),
         Some(("slice", sub_matches)) => cmd_fasr::slice::execute(sub_matches),
+        Some(("snp", sub_matches)) => cmd_fasr::snp::execute(sub_matches),
         Some(("split", sub_matches)) => cmd_fasr::split::execute(sub_matches),
         Some(("stat", sub_matches)) => cmd_fasr::stat::execute(sub_matches),
         Some(("subset", sub_matches)) => cmd_fasr::subset::execute(sub_matches),
+        Some(("trim", sub_matches)) => cmd_fasr::trim::execute(sub_matches),
         Some(("variation", sub_matches)) => cmd_fasr::variation::execute(sub_matches),
+        Some(("vcf", sub_matches)) => cmd_fasr::vcf::execute(sub_matches),
         Some(("xlsx", sub_matches)) => cmd_fasr::xlsx::execute(sub_matches),
         _ => unreachable!(),
     }
@@ -64,7 +74,7 @@ fn main() -> anyhow::Result<()> {
 }
 
 // TODO: replace samtools
-// TODO: add more tools - vcf, match
+// TODO: add more tools - match
 // TODO: fasr variation --indel
 // TODO: fasr xlsx --indel
 // TODO: fasr match
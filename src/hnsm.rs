@@ -22,10 +22,20 @@ fn main() -> anyhow::Result<()> {
         .subcommand(cmd::count::make_subcommand())
         .subcommand(cmd::das::make_subcommand())
         .subcommand(cmd::dedup::make_subcommand())
+        .subcommand(cmd::degap::make_subcommand())
         .subcommand(cmd::distance::make_subcommand())
+        .subcommand(cmd::dust::make_subcommand())
+        .subcommand(cmd::expand::make_subcommand())
+        .subcommand(cmd::fa2tab::make_subcommand())
         .subcommand(cmd::filter::make_subcommand())
+        .subcommand(cmd::gc::make_subcommand())
+        .subcommand(cmd::gff::make_subcommand())
         .subcommand(cmd::gz::make_subcommand())
+        .subcommand(cmd::hash::make_subcommand())
+        .subcommand(cmd::hv::make_subcommand())
         .subcommand(cmd::interleave::make_subcommand())
+        .subcommand(cmd::kcount::make_subcommand())
+        .subcommand(cmd::maf2fa::make_subcommand())
         .subcommand(cmd::mask::make_subcommand())
         .subcommand(cmd::masked::make_subcommand())
         .subcommand(cmd::n50::make_subcommand())
@@ -33,22 +43,27 @@ fn main() -> anyhow::Result<()> {
         .subcommand(cmd::order::make_subcommand())
         .subcommand(cmd::range::make_subcommand())
         .subcommand(cmd::rc::make_subcommand())
+        .subcommand(cmd::rename::make_subcommand())
+        .subcommand(cmd::repeats::make_subcommand())
         .subcommand(cmd::replace::make_subcommand())
         .subcommand(cmd::manifold::make_subcommand())
+        .subcommand(cmd::screen::make_subcommand())
         .subcommand(cmd::similarity::make_subcommand())
         .subcommand(cmd::sixframe::make_subcommand())
         .subcommand(cmd::size::make_subcommand())
         .subcommand(cmd::some::make_subcommand())
         .subcommand(cmd::split::make_subcommand())
+        .subcommand(cmd::validate::make_subcommand())
         .after_help(
             r###"
 Subcommand groups:
 
 * Fasta files
-    * info: size / count / masked / n50
+    * info: size / count / masked / n50 / repeats / gc / fa2tab
     * records: one / some / order / split
-    * transform: replace / rc / filter / dedup / mask
+    * transform: replace / rename / rc / filter / dedup / mask / dust / expand / degap
     * indexing: gz / range
+    * validate
 
 * Fastq files
     * interleave
@@ -56,12 +71,18 @@ Subcommand groups:
 * Clustering
     * vectors: similarity
     * DNA/protein: distance / identity
+    * sketches: hv / kcount
+    * hash: dump the minimizers/syncmers behind distance
     * cluster
     * reduction
 
 * Synteny
     * das
     * chain
+    * maf2fa: block fasta from a `pgr chain`-produced maf
+
+* Annotation
+    * gff rg / gff extract / gff to-annot
 
 * <infiles> are paths to fasta files, .fa.gz is supported
     * infile == stdin means reading from STDIN
@@ -77,6 +98,9 @@ Subcommand groups:
         Some(("count", sub_matches)) => cmd::count::execute(sub_matches),
         Some(("masked", sub_matches)) => cmd::masked::execute(sub_matches),
         Some(("n50", sub_matches)) => cmd::n50::execute(sub_matches),
+        Some(("repeats", sub_matches)) => cmd::repeats::execute(sub_matches),
+        Some(("gc", sub_matches)) => cmd::gc::execute(sub_matches),
+        Some(("fa2tab", sub_matches)) => cmd::fa2tab::execute(sub_matches),
         // records
         Some(("one", sub_matches)) => cmd::one::execute(sub_matches),
         Some(("some", sub_matches)) => cmd::some::execute(sub_matches),
@@ -84,26 +108,38 @@ Subcommand groups:
         Some(("split", sub_matches)) => cmd::split::execute(sub_matches),
         // transform
         Some(("replace", sub_matches)) => cmd::replace::execute(sub_matches),
+        Some(("rename", sub_matches)) => cmd::rename::execute(sub_matches),
         Some(("rc", sub_matches)) => cmd::rc::execute(sub_matches),
         Some(("filter", sub_matches)) => cmd::filter::execute(sub_matches),
         Some(("dedup", sub_matches)) => cmd::dedup::execute(sub_matches),
+        Some(("degap", sub_matches)) => cmd::degap::execute(sub_matches),
         Some(("mask", sub_matches)) => cmd::mask::execute(sub_matches),
+        Some(("dust", sub_matches)) => cmd::dust::execute(sub_matches),
+        Some(("expand", sub_matches)) => cmd::expand::execute(sub_matches),
         //
         Some(("sixframe", sub_matches)) => cmd::sixframe::execute(sub_matches),
+        // annotation
+        Some(("gff", sub_matches)) => cmd::gff::execute(sub_matches),
         // index
         Some(("gz", sub_matches)) => cmd::gz::execute(sub_matches),
         Some(("range", sub_matches)) => cmd::range::execute(sub_matches),
+        Some(("validate", sub_matches)) => cmd::validate::execute(sub_matches),
         // fastq
         Some(("interleave", sub_matches)) => cmd::interleave::execute(sub_matches),
         // clustering
         Some(("distance", sub_matches)) => cmd::distance::execute(sub_matches),
+        Some(("hash", sub_matches)) => cmd::hash::execute(sub_matches),
+        Some(("screen", sub_matches)) => cmd::screen::execute(sub_matches),
         Some(("similarity", sub_matches)) => cmd::similarity::execute(sub_matches),
+        Some(("hv", sub_matches)) => cmd::hv::execute(sub_matches),
+        Some(("kcount", sub_matches)) => cmd::kcount::execute(sub_matches),
         Some(("convert", sub_matches)) => cmd::convert::execute(sub_matches),
         Some(("cluster", sub_matches)) => cmd::cluster::execute(sub_matches),
         Some(("manifold", sub_matches)) => cmd::manifold::execute(sub_matches),
         // Synteny
         Some(("das", sub_matches)) => cmd::das::execute(sub_matches),
         Some(("chain", sub_matches)) => cmd::chain::execute(sub_matches),
+        Some(("maf2fa", sub_matches)) => cmd::maf2fa::execute(sub_matches),
         _ => unreachable!(),
     }
     .unwrap();
@@ -115,3 +151,12 @@ Subcommand groups:
 //  sort
 //  identity: accurate pairwise sequence identity
 //    https://lh3.github.io/2018/11/25/on-the-definition-of-sequence-identity
+//  ribbon: SVG synteny ribbon plots with a scale bar and a gene density track
+//    needs an SVG-rendering dependency and a chromosome-track drawing layer;
+//    neither exists yet, so this is parked until that groundwork is laid
+//  synt dna --parallel: multi-threaded sequence loading for a synteny-graph
+//    builder (a SyntenyFinder fed by a channel-based producer) requires the
+//    whole synt subsystem, which doesn't exist yet; the closest real command,
+//    `chain`, reads a single precomputed hit table rather than raw sequence
+//    files, so there's nothing to parallelize there either. Parked until a
+//    real multi-genome sequence-loading path exists
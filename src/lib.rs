@@ -4,10 +4,18 @@ extern crate lazy_static;
 pub mod libs;
 
 pub use crate::libs::alignment::*;
-pub use crate::libs::dbscan::Dbscan;
+pub use crate::libs::banded::{banded_identity, AlignMode, SubMatrix};
+pub use crate::libs::chain::{chain_psl, Chain, ChainBlock, GapCost};
+pub use crate::libs::dbscan::{find_elbow, k_distances, Dbscan};
 pub use crate::libs::fas::*;
 pub use crate::libs::hash::*;
 pub use crate::libs::io::*;
 pub use crate::libs::loc::*;
 pub use crate::libs::matrix::ScoringMatrix;
+pub use crate::libs::mcl::{modularity, Mcl};
 pub use crate::libs::nt::*;
+pub use crate::libs::progress::{render_line as render_progress_line, ProgressReporter};
+pub use crate::libs::psl::{read_psl, PslRecord};
+pub use crate::libs::stats::SeqStats;
+pub use crate::libs::tsne::Tsne;
+pub use crate::libs::twobit::{write_two_bit, TwoBitReader};
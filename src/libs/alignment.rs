@@ -132,6 +132,199 @@ pub fn alignment_stat(seqs: &[&[u8]]) -> (i32, i32, i32, i32, i32, f32) {
     )
 }
 
+/// Pairwise alignment statistics with gaps compressed to a single event,
+/// regardless of their length, as used by `pgr stat` to summarize
+/// `pgr chain`'s axt/maf output.
+///
+/// Unlike [`alignment_stat`], which counts every gapped column, a run of
+/// consecutive gap columns (an indel) here counts as one `gap_opens`, not
+/// one per base — the usual meaning of "gap-compressed identity".
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GapCompressedStat {
+    /// Target bases covered by the alignment, i.e. columns where `target`
+    /// is not a gap.
+    pub aligned_bp: i32,
+    pub matches: i32,
+    pub mismatches: i32,
+    pub gap_opens: i32,
+}
+
+impl GapCompressedStat {
+    /// `matches / (matches + mismatches + gap_opens)`, `0.0` if nothing was
+    /// aligned.
+    pub fn identity(&self) -> f32 {
+        let denom = self.matches + self.mismatches + self.gap_opens;
+        if denom == 0 {
+            0.0
+        } else {
+            self.matches as f32 / denom as f32
+        }
+    }
+
+    /// Folds another block's stats into this one, for genome-wide totals.
+    pub fn merge(&mut self, other: &GapCompressedStat) {
+        self.aligned_bp += other.aligned_bp;
+        self.matches += other.matches;
+        self.mismatches += other.mismatches;
+        self.gap_opens += other.gap_opens;
+    }
+}
+
+/// Computes [`GapCompressedStat`] for one pair of aligned rows, e.g. the
+/// target/query rows of an axt or maf block.
+///
+/// ```
+/// let stat = hnsm::gap_compressed_stat(b"AAAATTTTGG", b"aaaatttttg");
+/// assert_eq!(stat.aligned_bp, 10);
+/// assert_eq!(stat.matches, 9);
+/// assert_eq!(stat.mismatches, 1);
+/// assert_eq!(stat.gap_opens, 0);
+///
+/// //                                    a run of one gap, not one per base
+/// let stat = hnsm::gap_compressed_stat(b"TTAGCCGCTGAGAAGCC", b"GTAGCCGCTGA-AGGCC");
+/// assert_eq!(stat.aligned_bp, 17);
+/// assert_eq!(stat.matches, 14);
+/// assert_eq!(stat.mismatches, 2);
+/// assert_eq!(stat.gap_opens, 1);
+/// ```
+pub fn gap_compressed_stat(target: &[u8], query: &[u8]) -> GapCompressedStat {
+    assert_eq!(
+        target.len(),
+        query.len(),
+        "Two sequences of different length ({}!={})",
+        target.len(),
+        query.len()
+    );
+
+    let mut stat = GapCompressedStat::default();
+    let mut in_gap = false;
+
+    for (&t, &q) in target.iter().zip(query) {
+        if t == b'-' || q == b'-' {
+            if !in_gap {
+                stat.gap_opens += 1;
+                in_gap = true;
+            }
+            if t != b'-' {
+                stat.aligned_bp += 1;
+            }
+            continue;
+        }
+
+        in_gap = false;
+        stat.aligned_bp += 1;
+        if t.to_ascii_uppercase() == q.to_ascii_uppercase() {
+            stat.matches += 1;
+        } else {
+            stat.mismatches += 1;
+        }
+    }
+
+    stat
+}
+
+/// Population-genetics summary of one aligned block, built on top of
+/// [`alignment_stat`]: sequence count, comparable (gap/ambiguity-free)
+/// alignment length, segregating sites, nucleotide diversity `pi`
+/// (the same mean pairwise distance `alignment_stat` reports as `D`),
+/// Watterson's `theta`, and Tajima's D.
+///
+/// `tajima_d` is `None` when there are fewer than 4 sequences or no
+/// segregating sites, where the variance estimator is undefined (Tajima 1989).
+///
+/// ```
+/// let seqs = vec![
+///     b"AAAAAAAAAA".as_ref(),
+///     b"AAAAAAAAAT".as_ref(),
+///     b"AAAAAAAATT".as_ref(),
+///     b"AAAAAAATTT".as_ref(),
+/// ];
+/// let stat = hnsm::diversity_stat(&seqs);
+/// assert_eq!(stat.seq_count, 4);
+/// assert_eq!(stat.comparable, 10);
+/// assert_eq!(stat.segregating, 3);
+/// assert!((stat.theta - 0.1636).abs() < 1e-3);
+/// assert!(stat.tajima_d.unwrap() > 0.0);
+/// ```
+#[derive(Default, Clone)]
+pub struct DiversityStat {
+    pub seq_count: usize,
+    pub length: i32,
+    pub comparable: i32,
+    pub segregating: i32,
+    pub pi: f64,
+    pub theta: f64,
+    pub tajima_d: Option<f64>,
+}
+
+pub fn diversity_stat(seqs: &[&[u8]]) -> DiversityStat {
+    let n = seqs.len();
+    let (length, comparable, segregating, _gap, _ambiguous, mean_d) = alignment_stat(seqs);
+
+    let pi = mean_d as f64;
+    let raw_k = pi * comparable as f64;
+
+    let a1: f64 = (1..n).map(|i| 1.0 / i as f64).sum();
+    let theta = if comparable > 0 && a1 > 0.0 {
+        segregating as f64 / a1 / comparable as f64
+    } else {
+        0.0
+    };
+
+    DiversityStat {
+        seq_count: n,
+        length,
+        comparable,
+        segregating,
+        pi,
+        theta,
+        tajima_d: tajima_d(n, segregating, raw_k),
+    }
+}
+
+/// Tajima's D (Tajima 1989) from a sample size, segregating site count, and
+/// the raw (not per-site) average number of pairwise differences. `None`
+/// when fewer than 4 sequences or no segregating sites, where the variance
+/// estimator is undefined. Shared by [`diversity_stat`] and `fasr stat`'s
+/// weighted total row.
+pub fn tajima_d(seq_count: usize, segregating: i32, raw_k: f64) -> Option<f64> {
+    if seq_count < 4 || segregating == 0 {
+        return None;
+    }
+
+    let n_f = seq_count as f64;
+    let s_f = segregating as f64;
+    let a1: f64 = (1..seq_count).map(|i| 1.0 / i as f64).sum();
+    let a2: f64 = (1..seq_count).map(|i| 1.0 / (i as f64 * i as f64)).sum();
+    let b1 = (n_f + 1.0) / (3.0 * (n_f - 1.0));
+    let b2 = 2.0 * (n_f * n_f + n_f + 3.0) / (9.0 * n_f * (n_f - 1.0));
+    let c1 = b1 - 1.0 / a1;
+    let c2 = b2 - (n_f + 2.0) / (a1 * n_f) + a2 / (a1 * a1);
+    let e1 = c1 / a1;
+    let e2 = c2 / (a1 * a1 + a2);
+    let variance = e1 * s_f + e2 * s_f * (s_f - 1.0);
+
+    if variance <= 0.0 {
+        None
+    } else {
+        Some((raw_k - s_f / a1) / variance.sqrt())
+    }
+}
+
+/// Ancestral/derived resolution of a [`Substitution`] or `Indel` against an outgroup.
+///
+/// Replaces the previous convention of overloading `pattern == "unknown"`: `Unpolarized`
+/// is the state before [`polarize_subs`]/`polarize_indels` runs, `Resolved` means the
+/// outgroup base unambiguously matched one of the ingroup alleles, and `Unknown` means
+/// it didn't (outgroup gap/N, or a third allele).
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Polarity {
+    #[default]
+    Unpolarized,
+    Resolved,
+    Unknown,
+}
+
 #[derive(Default, Clone)]
 pub struct Substitution {
     pub pos: i32,
@@ -142,6 +335,7 @@ pub struct Substitution {
     pub freq: i32,
     pub pattern: String,
     pub obase: String,
+    pub polarity: Polarity,
 }
 
 /// To string
@@ -280,6 +474,7 @@ pub fn get_subs(seqs: &[&[u8]]) -> anyhow::Result<Vec<Substitution>> {
             freq: min(freq, seq_count as i32 - freq),
             pattern,
             obase,
+            polarity: Polarity::Unpolarized,
         };
         sites.push(sub);
     }
@@ -346,6 +541,7 @@ pub fn polarize_subs(subs: &mut Vec<Substitution>, og: &[u8]) {
         if sub.qbase == "".to_string() {
             // complex ingroup bases
             sub.obase = obase.clone();
+            sub.polarity = Polarity::Unknown;
         } else if BASES.contains(&obase_u8) {
             if sub.bases.contains(&obase) {
                 // can polarize subs
@@ -368,23 +564,223 @@ pub fn polarize_subs(subs: &mut Vec<Substitution>, og: &[u8]) {
                 sub.freq = freq;
                 sub.pattern = pattern;
                 sub.obase = obase.clone();
+                sub.polarity = Polarity::Resolved;
             } else {
-                // outgroup base is not equal to any nts
+                // outgroup base is a third allele, not equal to any ingroup nts
                 sub.mutant_to = "Complex".to_string();
                 sub.freq = -1;
                 sub.pattern = "unknown".to_string();
                 sub.obase = obase.clone();
+                sub.polarity = Polarity::Unknown;
             }
         } else {
-            // outgroup base is N
+            // outgroup base is a gap or an ambiguity code (N, etc.)
             sub.mutant_to = "Complex".to_string();
             sub.freq = -1;
             sub.pattern = "unknown".to_string();
             sub.obase = obase.clone();
+            sub.polarity = Polarity::Unknown;
         }
     }
 }
 
+/// Polarize substitutions against multiple outgroups by majority rule
+///
+/// The ancestral state at each site is the base agreed on by the largest
+/// number of outgroups; a tie (including all outgroups disagreeing, or none
+/// carrying a valid ACGT base) leaves the site [`Polarity::Unknown`], same as
+/// a single ambiguous outgroup does in [`polarize_subs`]. Passing a single
+/// outgroup reproduces `polarize_subs`'s behavior exactly.
+///
+/// ```
+/// let seqs = vec![
+///     //        *
+///     b"AAAATTTTGG".as_ref(),
+///     b"AAAATTTTAG".as_ref(),
+///     b"AAAATTTTAG".as_ref(),
+///     b"AAAATTTTAG".as_ref(),
+/// ];
+/// let mut subs = hnsm::get_subs(&seqs[0..2]).unwrap();
+/// hnsm::polarize_subs_multi(&mut subs, &seqs[2..4]);
+/// let sub = subs.first().unwrap();
+/// assert_eq!(sub.obase, "A".to_string());
+/// assert_eq!(sub.polarity, hnsm::Polarity::Resolved);
+///
+/// // A tied vote between two outgroups can't be polarized
+/// let seqs = vec![
+///     //        *
+///     b"AAAATTTTGG".as_ref(),
+///     b"AAAATTTTAG".as_ref(),
+///     b"AAAATTTTAG".as_ref(),
+///     b"AAAATTTTGG".as_ref(),
+/// ];
+/// let mut subs = hnsm::get_subs(&seqs[0..2]).unwrap();
+/// hnsm::polarize_subs_multi(&mut subs, &seqs[2..4]);
+/// let sub = subs.first().unwrap();
+/// assert_eq!(sub.polarity, hnsm::Polarity::Unknown);
+/// ```
+pub fn polarize_subs_multi(subs: &mut Vec<Substitution>, outgroups: &[&[u8]]) {
+    for sub in subs {
+        let pos = sub.pos;
+
+        if sub.qbase.is_empty() {
+            // complex ingroup bases
+            sub.polarity = Polarity::Unknown;
+            continue;
+        }
+
+        let mut votes: BTreeMap<u8, i32> = BTreeMap::new();
+        for og in outgroups {
+            let obase_u8 = og[(pos - 1) as usize].to_ascii_uppercase();
+            if BASES.contains(&obase_u8) {
+                *votes.entry(obase_u8).or_insert(0) += 1;
+            }
+        }
+
+        let top = votes.values().copied().max();
+        let winners: Vec<u8> = match top {
+            Some(top) => votes
+                .iter()
+                .filter(|(_, &c)| c == top)
+                .map(|(&b, _)| b)
+                .collect(),
+            None => vec![],
+        };
+
+        if winners.len() != 1 {
+            // no outgroup carried a valid base, or the vote was tied
+            sub.mutant_to = "Complex".to_string();
+            sub.freq = -1;
+            sub.pattern = "unknown".to_string();
+            sub.obase = "".to_string();
+            sub.polarity = Polarity::Unknown;
+            continue;
+        }
+
+        let obase_u8 = winners[0];
+        let obase = String::from_utf8(vec![obase_u8]).unwrap();
+
+        if sub.bases.contains(&obase) {
+            // can polarize subs
+            let mut mutant_to = "".to_string();
+            let mut freq = 0;
+            let mut pattern = "".to_string();
+            for base in sub.bases.as_bytes() {
+                if *base == obase_u8 {
+                    pattern += "0";
+                } else {
+                    pattern += "1";
+                    freq += 1;
+                    mutant_to =
+                        format!("{}->{}", obase, String::from_utf8(vec![*base]).unwrap())
+                            .to_string();
+                }
+            }
+            sub.mutant_to = mutant_to;
+            sub.freq = freq;
+            sub.pattern = pattern;
+            sub.obase = obase;
+            sub.polarity = Polarity::Resolved;
+        } else {
+            // majority outgroup base is a third allele, not equal to any ingroup nts
+            sub.mutant_to = "Complex".to_string();
+            sub.freq = -1;
+            sub.pattern = "unknown".to_string();
+            sub.obase = obase;
+            sub.polarity = Polarity::Unknown;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polarize_subs_multi_single_outgroup_matches_polarize_subs() {
+        let seqs: Vec<&[u8]> = vec![b"AAAATTTTGG", b"AAAATTTTAG", b"AAAATTTTAG"];
+        let mut subs_single = get_subs(&seqs[0..2]).unwrap();
+        polarize_subs(&mut subs_single, seqs[2]);
+
+        let mut subs_multi = get_subs(&seqs[0..2]).unwrap();
+        polarize_subs_multi(&mut subs_multi, &seqs[2..3]);
+
+        assert_eq!(subs_single.first().unwrap().obase, subs_multi.first().unwrap().obase);
+        assert_eq!(subs_single.first().unwrap().polarity, subs_multi.first().unwrap().polarity);
+    }
+
+    #[test]
+    fn polarize_subs_multi_majority_wins() {
+        let seqs: Vec<&[u8]> = vec![
+            b"AAAATTTTGG",
+            b"AAAATTTTAG",
+            b"AAAATTTTAG",
+            b"AAAATTTTAG",
+            b"AAAATTTTGG",
+        ];
+        let mut subs = get_subs(&seqs[0..2]).unwrap();
+        polarize_subs_multi(&mut subs, &seqs[2..5]);
+        let sub = subs.first().unwrap();
+        assert_eq!(sub.obase, "A");
+        assert_eq!(sub.polarity, Polarity::Resolved);
+    }
+
+    #[test]
+    fn polarize_subs_multi_tie_is_unknown() {
+        let seqs: Vec<&[u8]> = vec![
+            b"AAAATTTTGG",
+            b"AAAATTTTAG",
+            b"AAAATTTTAG",
+            b"AAAATTTTGG",
+        ];
+        let mut subs = get_subs(&seqs[0..2]).unwrap();
+        polarize_subs_multi(&mut subs, &seqs[2..4]);
+        let sub = subs.first().unwrap();
+        assert_eq!(sub.polarity, Polarity::Unknown);
+        assert_eq!(sub.pattern, "unknown");
+    }
+
+    #[test]
+    fn polarize_subs_outgroup_gap() {
+        let seqs: Vec<&[u8]> = vec![b"AAAATTTTGG", b"AAAATTTTAG", b"AAAATTTT-G"];
+        let mut subs = get_subs(&seqs[0..2]).unwrap();
+        polarize_subs(&mut subs, seqs[2]);
+        let sub = subs.first().unwrap();
+        assert_eq!(sub.polarity, Polarity::Unknown);
+        assert_eq!(sub.pattern, "unknown");
+    }
+
+    #[test]
+    fn polarize_subs_outgroup_n() {
+        let seqs: Vec<&[u8]> = vec![b"AAAATTTTGG", b"AAAATTTTAG", b"AAAATTTTNG"];
+        let mut subs = get_subs(&seqs[0..2]).unwrap();
+        polarize_subs(&mut subs, seqs[2]);
+        let sub = subs.first().unwrap();
+        assert_eq!(sub.polarity, Polarity::Unknown);
+        assert_eq!(sub.pattern, "unknown");
+    }
+
+    #[test]
+    fn polarize_subs_outgroup_third_allele() {
+        let seqs: Vec<&[u8]> = vec![b"AAAATTTTGG", b"AAAATTTTAG", b"AAAATTTTCG"];
+        let mut subs = get_subs(&seqs[0..2]).unwrap();
+        polarize_subs(&mut subs, seqs[2]);
+        let sub = subs.first().unwrap();
+        assert_eq!(sub.polarity, Polarity::Unknown);
+        assert_eq!(sub.pattern, "unknown");
+    }
+
+    #[test]
+    fn polarize_subs_outgroup_resolved() {
+        let seqs: Vec<&[u8]> = vec![b"AAAATTTTGG", b"AAAATTTTAG", b"AAAATTTTAG"];
+        let mut subs = get_subs(&seqs[0..2]).unwrap();
+        polarize_subs(&mut subs, seqs[2]);
+        let sub = subs.first().unwrap();
+        assert_eq!(sub.polarity, Polarity::Resolved);
+        assert_eq!(sub.pattern, "10");
+    }
+}
+
 /// ```
 /// use hnsm::{indel_intspan, seq_intspan};
 /// let tests : Vec<(&str, &str)> = vec![
@@ -422,6 +818,133 @@ pub fn seq_intspan(seq: &[u8]) -> IntSpan {
     IntSpan::from_pair(1, seq.len() as i32).diff(&indel_intspan(seq))
 }
 
+/// A polymorphic indel event, mirroring [`Substitution`] but tracking presence ('1')
+/// vs. gap ('0') per ingroup sequence instead of a nucleotide.
+#[derive(Default, Clone)]
+pub struct Indel {
+    pub start: i32,
+    pub end: i32,
+    pub length: i32,
+    pub bases: String,
+    pub freq: i32,
+    pub pattern: String,
+    pub obase: String,
+    pub polarity: Polarity,
+}
+
+/// Returns unpolarized indels: maximal runs of columns where the gap/non-gap pattern
+/// across `seqs` is constant and not all sequences agree (a polymorphic indel).
+///
+/// ```
+/// let seqs = vec![
+///     //   **
+///     b"AA--TT".as_ref(),
+///     b"AACGTT".as_ref(),
+/// ];
+/// let indels = hnsm::get_indels(&seqs).unwrap();
+/// let indel = indels.first().unwrap();
+/// assert_eq!(indel.start, 3);
+/// assert_eq!(indel.end, 4);
+/// assert_eq!(indel.length, 2);
+/// assert_eq!(indel.bases, "01".to_string());
+/// ```
+pub fn get_indels(seqs: &[&[u8]]) -> anyhow::Result<Vec<Indel>> {
+    let seq_count = seqs.len();
+    let length = seqs[0].len();
+
+    let mut indels = vec![];
+    let mut pos = 0;
+    while pos < length {
+        let pattern: Vec<bool> = (0..seq_count).map(|i| seqs[i][pos] == b'-').collect();
+        let is_variable = pattern.iter().any(|&g| g) && pattern.iter().any(|&g| !g);
+        if !is_variable {
+            pos += 1;
+            continue;
+        }
+
+        let start = pos;
+        let mut end = pos;
+        while end + 1 < length {
+            let next: Vec<bool> = (0..seq_count).map(|i| seqs[i][end + 1] == b'-').collect();
+            if next != pattern {
+                break;
+            }
+            end += 1;
+        }
+
+        let bases: String = pattern.iter().map(|&g| if g { '0' } else { '1' }).collect();
+        let freq = pattern.iter().filter(|&&g| g).count() as i32;
+
+        indels.push(Indel {
+            start: (start + 1) as i32,
+            end: (end + 1) as i32,
+            length: (end - start + 1) as i32,
+            freq: min(freq, seq_count as i32 - freq),
+            pattern: bases.clone(),
+            bases,
+            obase: "".to_string(),
+            polarity: Polarity::Unpolarized,
+        });
+
+        pos = end + 1;
+    }
+
+    Ok(indels)
+}
+
+/// Polarizes indels against an outgroup sequence `og`, the indel counterpart of
+/// [`polarize_subs`]. An indel is `Resolved` when the outgroup is uniformly gapped or
+/// uniformly present over the whole span (so its ancestral state is unambiguous), and
+/// `Unknown` when the outgroup is itself polymorphic over that span.
+///
+/// ```
+/// let seqs = vec![
+///     //   **
+///     b"AA--TT".as_ref(),
+///     b"AACGTT".as_ref(),
+///     b"AACGTT".as_ref(),
+/// ];
+/// let mut indels = hnsm::get_indels(&seqs[0..2]).unwrap();
+/// hnsm::polarize_indels(&mut indels, seqs[2]);
+/// let indel = indels.first().unwrap();
+/// assert_eq!(indel.pattern, "10".to_string());
+/// ```
+pub fn polarize_indels(indels: &mut Vec<Indel>, og: &[u8]) {
+    for indel in indels {
+        let start = (indel.start - 1) as usize;
+        let end = (indel.end - 1) as usize;
+        let og_gapped: Vec<bool> = (start..=end).map(|p| og[p] == b'-').collect();
+
+        let all_gap = og_gapped.iter().all(|&g| g);
+        let all_present = og_gapped.iter().all(|&g| !g);
+
+        if all_gap || all_present {
+            let og_is_gap = all_gap;
+            let mut pattern = "".to_string();
+            let mut freq = 0;
+            for base in indel.bases.as_bytes() {
+                let ingroup_is_gap = *base == b'0';
+                if ingroup_is_gap == og_is_gap {
+                    pattern += "0";
+                } else {
+                    pattern += "1";
+                    freq += 1;
+                }
+            }
+            indel.pattern = pattern;
+            indel.freq = freq;
+            indel.obase = if og_is_gap { "-".to_string() } else { "seq".to_string() };
+            indel.polarity = Polarity::Resolved;
+        } else {
+            // outgroup is itself polymorphic (part gapped, part not) over this span
+            indel.freq = -1;
+            indel.pattern = "unknown".to_string();
+            indel.obase = "".to_string();
+            indel.polarity = Polarity::Unknown;
+        }
+    }
+}
+
 /// ```
 /// match which::which("spoa") {
 ///     Ok(_) => {
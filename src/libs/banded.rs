@@ -0,0 +1,275 @@
+//! Banded pairwise alignment used to verify sketch-estimated identities.
+//!
+//! This is intentionally small: it is meant to check candidate pairs that a
+//! minimizer/sketch-based method (see [`crate::distance`](../cmd/distance))
+//! has already flagged as similar, not to align arbitrary sequences from
+//! scratch. The band width should be derived from the estimated distance so
+//! that only pairs which are actually close incur the `O(n * band)` cost.
+
+const NEG_INF: i32 = i32::MIN / 2;
+
+/// Alignment mode for [`banded_identity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignMode {
+    /// End-to-end alignment (banded Needleman-Wunsch).
+    Global,
+    /// Best local alignment (banded Smith-Waterman).
+    Local,
+}
+
+/// Substitution scheme for [`banded_identity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubMatrix {
+    /// Simple match/mismatch scoring for nucleotides.
+    Dna,
+    /// BLOSUM62 scoring for amino acids.
+    Blosum62,
+}
+
+const GAP_PENALTY: i32 = -4;
+const DNA_MATCH: i32 = 5;
+const DNA_MISMATCH: i32 = -4;
+
+const AA_ORDER: &[u8] = b"ARNDCQEGHILKMFPSTWYVBZX*";
+
+#[rustfmt::skip]
+const BLOSUM62: [[i32; 24]; 24] = [
+    [ 4,-1,-2,-2, 0,-1,-1, 0,-2,-1,-1,-1,-1,-2,-1, 1, 0,-3,-2, 0,-2,-1, 0,-4],
+    [-1, 5, 0,-2,-3, 1, 0,-2, 0,-3,-2, 2,-1,-3,-2,-1,-1,-3,-2,-3,-1, 0,-1,-4],
+    [-2, 0, 6, 1,-3, 0, 0, 0, 1,-3,-3, 0,-2,-3,-2, 1, 0,-4,-2,-3, 3, 0,-1,-4],
+    [-2,-2, 1, 6,-3, 0, 2,-1,-1,-3,-4,-1,-3,-3,-1, 0,-1,-4,-3,-3, 4, 1,-1,-4],
+    [ 0,-3,-3,-3, 9,-3,-4,-3,-3,-1,-1,-3,-1,-2,-3,-1,-1,-2,-2,-1,-3,-3,-2,-4],
+    [-1, 1, 0, 0,-3, 5, 2,-2, 0,-3,-2, 1, 0,-3,-1, 0,-1,-2,-1,-2, 0, 3,-1,-4],
+    [-1, 0, 0, 2,-4, 2, 5,-2, 0,-3,-3, 1,-2,-3,-1, 0,-1,-3,-2,-2, 1, 4,-1,-4],
+    [ 0,-2, 0,-1,-3,-2,-2, 6,-2,-4,-4,-2,-3,-3,-2, 0,-2,-2,-3,-3,-1,-2,-1,-4],
+    [-2, 0, 1,-1,-3, 0, 0,-2, 8,-3,-3,-1,-2,-1,-2,-1,-2,-2, 2,-3, 0, 0,-1,-4],
+    [-1,-3,-3,-3,-1,-3,-3,-4,-3, 4, 2,-3, 1, 0,-3,-2,-1,-3,-1, 3,-3,-3,-1,-4],
+    [-1,-2,-3,-4,-1,-2,-3,-4,-3, 2, 4,-2, 2, 0,-3,-2,-1,-2,-1, 1,-4,-3,-1,-4],
+    [-1, 2, 0,-1,-3, 1, 1,-2,-1,-3,-2, 5,-1,-3,-1, 0,-1,-3,-2,-2, 0, 1,-1,-4],
+    [-1,-1,-2,-3,-1, 0,-2,-3,-2, 1, 2,-1, 5, 0,-2,-1,-1,-1,-1, 1,-3,-1,-1,-4],
+    [-2,-3,-3,-3,-2,-3,-3,-3,-1, 0, 0,-3, 0, 6,-4,-2,-2, 1, 3,-1,-3,-3,-1,-4],
+    [-1,-2,-2,-1,-3,-1,-1,-2,-2,-3,-3,-1,-2,-4, 7,-1,-1,-4,-3,-2,-2,-1,-2,-4],
+    [ 1,-1, 1, 0,-1, 0, 0, 0,-1,-2,-2, 0,-1,-2,-1, 4, 1,-3,-2,-2, 0, 0, 0,-4],
+    [ 0,-1, 0,-1,-1,-1,-1,-2,-2,-1,-1,-1,-1,-2,-1, 1, 5,-2,-2, 0,-1,-1, 0,-4],
+    [-3,-3,-4,-4,-2,-2,-3,-2,-2,-3,-2,-3,-1, 1,-4,-3,-2,11, 2,-3,-4,-3,-2,-4],
+    [-2,-2,-2,-3,-2,-1,-2,-3, 2,-1,-1,-2,-1, 3,-3,-2,-2, 2, 7,-1,-3,-2,-1,-4],
+    [ 0,-3,-3,-3,-1,-2,-2,-3,-3, 3, 1,-2, 1,-1,-2,-2, 0,-3,-1, 4,-3,-2,-1,-4],
+    [-2,-1, 3, 4,-3, 0, 1,-1, 0,-3,-4, 0,-3,-3,-2, 0,-1,-4,-3,-3, 4, 1,-1,-4],
+    [-1, 0, 0, 1,-3, 3, 4,-2, 0,-3,-3, 1,-1,-3,-1, 0,-1,-3,-2,-2, 1, 4,-1,-4],
+    [ 0,-1,-1,-1,-2,-1,-1,-1,-1,-1,-1,-1,-1,-1,-2, 0, 0,-2,-1,-1,-1,-1,-1,-4],
+    [-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4,-4, 1],
+];
+
+fn aa_index(b: u8) -> usize {
+    let upper = b.to_ascii_uppercase();
+    AA_ORDER
+        .iter()
+        .position(|&c| c == upper)
+        .unwrap_or(AA_ORDER.len() - 2) // fall back to 'X'
+}
+
+fn score(a: u8, b: u8, matrix: SubMatrix) -> i32 {
+    match matrix {
+        SubMatrix::Dna => {
+            if a.to_ascii_uppercase() == b.to_ascii_uppercase() {
+                DNA_MATCH
+            } else {
+                DNA_MISMATCH
+            }
+        }
+        SubMatrix::Blosum62 => BLOSUM62[aa_index(a)][aa_index(b)],
+    }
+}
+
+/// Runs a banded alignment between `seq1` and `seq2` and returns the
+/// alignment identity, i.e. the fraction of aligned columns that are
+/// matches, in `0.0 ..= 1.0`.
+///
+/// `band` is the maximum allowed offset between the diagonals visited on
+/// either side of the main diagonal; cells outside the band are treated as
+/// unreachable. Callers should derive `band` from the sketch-estimated
+/// distance (e.g. `estimated_distance * seq.len() + margin`) so the band is
+/// wide enough to contain the true alignment for genuinely similar pairs.
+pub fn banded_identity(seq1: &[u8], seq2: &[u8], band: usize, mode: AlignMode, matrix: SubMatrix) -> f64 {
+    let n = seq1.len();
+    let m = seq2.len();
+    if n == 0 || m == 0 {
+        return 0.0;
+    }
+
+    let band = band.max(seq1.len().abs_diff(seq2.len()));
+    let width = 2 * band + 1;
+
+    // dp[i][k] where k = j - i + band, restricted to |j - i| <= band
+    let mut dp = vec![vec![NEG_INF; width]; n + 1];
+    let mut is_match = vec![vec![false; width]; n + 1];
+
+    let idx = |i: i64, j: i64| -> Option<usize> {
+        let k = j - i + band as i64;
+        if k >= 0 && (k as usize) < width {
+            Some(k as usize)
+        } else {
+            None
+        }
+    };
+
+    if let Some(k) = idx(0, 0) {
+        dp[0][k] = 0;
+    }
+    for j in 1..=m {
+        if let Some(k) = idx(0, j as i64) {
+            dp[0][k] = if mode == AlignMode::Global {
+                GAP_PENALTY * j as i32
+            } else {
+                0
+            };
+        }
+    }
+
+    for i in 1..=n {
+        let j_lo = (i as i64 - band as i64).max(0) as usize;
+        let j_hi = (i + band).min(m);
+
+        if j_lo == 0 {
+            if let Some(k) = idx(i as i64, 0) {
+                dp[i][k] = if mode == AlignMode::Global {
+                    GAP_PENALTY * i as i32
+                } else {
+                    0
+                };
+            }
+        }
+
+        for j in j_lo.max(1)..=j_hi {
+            let k = idx(i as i64, j as i64).unwrap();
+            let s = score(seq1[i - 1], seq2[j - 1], matrix);
+
+            let diag = idx((i - 1) as i64, (j - 1) as i64)
+                .map(|k2| dp[i - 1][k2])
+                .unwrap_or(NEG_INF);
+            let up = idx((i - 1) as i64, j as i64)
+                .map(|k2| dp[i - 1][k2])
+                .unwrap_or(NEG_INF);
+            let left = idx(i as i64, (j - 1) as i64)
+                .map(|k2| dp[i][k2])
+                .unwrap_or(NEG_INF);
+
+            let mut best = diag.saturating_add(s);
+            let mut matched = s > 0 || (matrix == SubMatrix::Dna && s == DNA_MATCH);
+            let from_up = up.saturating_add(GAP_PENALTY);
+            let from_left = left.saturating_add(GAP_PENALTY);
+            if from_up > best {
+                best = from_up;
+                matched = false;
+            }
+            if from_left > best {
+                best = from_left;
+                matched = false;
+            }
+            if mode == AlignMode::Local && best < 0 {
+                best = 0;
+                matched = false;
+            }
+
+            dp[i][k] = best;
+            is_match[i][k] = matched;
+        }
+    }
+
+    // Trace back the best-scoring path to count matches vs. aligned columns.
+    let (mut i, mut j) = match mode {
+        AlignMode::Global => (n, m),
+        AlignMode::Local => {
+            let mut best_score = NEG_INF;
+            let mut best_ij = (0, 0);
+            for ii in 0..=n {
+                let j_lo = (ii as i64 - band as i64).max(0) as usize;
+                let j_hi = (ii + band).min(m);
+                for jj in j_lo..=j_hi {
+                    if let Some(k) = idx(ii as i64, jj as i64) {
+                        if dp[ii][k] > best_score {
+                            best_score = dp[ii][k];
+                            best_ij = (ii, jj);
+                        }
+                    }
+                }
+            }
+            best_ij
+        }
+    };
+
+    let mut matches = 0usize;
+    let mut columns = 0usize;
+    while i > 0 && j > 0 {
+        let k = match idx(i as i64, j as i64) {
+            Some(k) => k,
+            None => break,
+        };
+        if mode == AlignMode::Local && dp[i][k] <= 0 {
+            break;
+        }
+
+        let diag = idx((i - 1) as i64, (j - 1) as i64).map(|k2| dp[i - 1][k2]);
+        let s = score(seq1[i - 1], seq2[j - 1], matrix);
+        if diag.is_some() && dp[i][k] == diag.unwrap().saturating_add(s) {
+            columns += 1;
+            if is_match[i][k] {
+                matches += 1;
+            }
+            i -= 1;
+            j -= 1;
+        } else if idx((i - 1) as i64, j as i64).is_some()
+            && dp[i][k] == dp[i - 1][idx((i - 1) as i64, j as i64).unwrap()].saturating_add(GAP_PENALTY)
+        {
+            columns += 1;
+            i -= 1;
+        } else {
+            columns += 1;
+            j -= 1;
+        }
+    }
+    if mode == AlignMode::Global {
+        columns += i + j;
+    }
+
+    if columns == 0 {
+        0.0
+    } else {
+        matches as f64 / columns as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_dna_is_fully_identical() {
+        let s = b"ACGTACGTACGT";
+        let id = banded_identity(s, s, 2, AlignMode::Global, SubMatrix::Dna);
+        assert!((id - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_single_mismatch_reduces_identity() {
+        let a = b"ACGTACGTACGT";
+        let b = b"ACGTAAGTACGT";
+        let id = banded_identity(a, b, 2, AlignMode::Global, SubMatrix::Dna);
+        assert!(id > 0.9 && id < 1.0);
+    }
+
+    #[test]
+    fn test_local_alignment_ignores_flanking_junk() {
+        let a = b"TTTTACGTACGTACGTTTTT";
+        let b = b"ACGTACGTACGT";
+        let id = banded_identity(a, b, 8, AlignMode::Local, SubMatrix::Dna);
+        assert!((id - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_blosum62_identical_protein() {
+        let s = b"MKVLATQ";
+        let id = banded_identity(s, s, 2, AlignMode::Global, SubMatrix::Blosum62);
+        assert!((id - 1.0).abs() < 1e-9);
+    }
+}
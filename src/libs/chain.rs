@@ -0,0 +1,429 @@
+//! Native (pure-Rust) PSL-to-chain scoring: a simplified version of the
+//! `axtChain`/`chainAntiRepeat` step of the UCSC chain/net pipeline, for
+//! genome pairs small enough that depending on the kent-tools binaries for
+//! this step alone is more trouble than it's worth. See [`chain_psl`].
+//!
+//! This does not reproduce `axtChain`'s exact piecewise `-linearGap` tables
+//! or its use of the base-level alignment for scoring (PSL only records
+//! block coordinates, not bases); [`GapCost`] is a single-affine
+//! approximation of the `loose`/`medium` tables instead.
+
+use crate::libs::psl::PslRecord;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// Per-base score for an ungapped matching block.
+const MATCH_SCORE: i64 = 100;
+
+/// Affine gap-cost parameters approximating one of `axtChain`'s
+/// `-linearGap` tables.
+#[derive(Debug, Clone, Copy)]
+pub struct GapCost {
+    pub open: i64,
+    pub extend: f64,
+}
+
+impl GapCost {
+    /// Approximates `axtChain -linearGap=loose` (e.g. chicken/human).
+    pub const LOOSE: GapCost = GapCost {
+        open: 400,
+        extend: 30.0,
+    };
+    /// Approximates `axtChain -linearGap=medium` (e.g. mouse/human).
+    pub const MEDIUM: GapCost = GapCost {
+        open: 600,
+        extend: 45.0,
+    };
+
+    pub fn from_name(name: &str) -> anyhow::Result<GapCost> {
+        match name {
+            "loose" => Ok(GapCost::LOOSE),
+            "medium" => Ok(GapCost::MEDIUM),
+            _ => Err(anyhow::anyhow!("unknown --lineargap table: {}", name)),
+        }
+    }
+
+    /// Cost of a gap of `dt` bases on the target and `dq` bases on the
+    /// query between two consecutive blocks (a double-sided gap when both
+    /// are nonzero, as UCSC chains allow).
+    fn cost(&self, dt: u64, dq: u64) -> i64 {
+        let gap = dt.max(dq);
+        if gap == 0 {
+            return 0;
+        }
+        self.open + (self.extend * (gap as f64).ln().max(0.0)) as i64
+    }
+}
+
+/// One ungapped block of a chain, plus the gap to the next block (`dt` on
+/// the target, `dq` on the query); the last block of a chain carries no
+/// trailing gap.
+#[derive(Debug, Clone, Copy)]
+pub struct ChainBlock {
+    pub size: u64,
+    pub dt: u64,
+    pub dq: u64,
+}
+
+/// One UCSC chain: a header plus its ordered, non-overlapping blocks.
+#[derive(Debug, Clone)]
+pub struct Chain {
+    pub score: i64,
+    pub id: u64,
+    pub t_name: String,
+    pub t_size: u64,
+    pub t_start: u64,
+    pub t_end: u64,
+    pub q_name: String,
+    pub q_size: u64,
+    pub q_strand: char,
+    pub q_start: u64,
+    pub q_end: u64,
+    pub blocks: Vec<ChainBlock>,
+}
+
+impl Chain {
+    /// Writes this chain in UCSC chain format: a `chain ...` header line,
+    /// one line per block (`size dt dq`, the last block bare), and a
+    /// trailing blank line separating it from the next chain. The target is
+    /// always `+` strand, matching PSL/blat convention.
+    pub fn write(&self, w: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(
+            w,
+            "chain {} {} {} + {} {} {} {} {} {} {} {}",
+            self.score,
+            self.t_name,
+            self.t_size,
+            self.t_start,
+            self.t_end,
+            self.q_name,
+            self.q_size,
+            self.q_strand,
+            self.q_start,
+            self.q_end,
+            self.id
+        )?;
+
+        for (i, block) in self.blocks.iter().enumerate() {
+            if i + 1 == self.blocks.len() {
+                writeln!(w, "{}", block.size)?;
+            } else {
+                writeln!(w, "{}\t{}\t{}", block.size, block.dt, block.dq)?;
+            }
+        }
+        writeln!(w)?;
+
+        Ok(())
+    }
+}
+
+/// A single ungapped PSL block, used as the unit of chaining.
+#[derive(Debug, Clone, Copy)]
+struct Anchor {
+    t_start: u64,
+    t_end: u64,
+    q_start: u64,
+    q_end: u64,
+}
+
+impl Anchor {
+    fn size(&self) -> u64 {
+        self.t_end - self.t_start
+    }
+}
+
+/// Groups PSL records by (target, query, query strand), the granularity at
+/// which their blocks can be chained together.
+fn group_by_pair(records: &[PslRecord]) -> Vec<((String, String, char), Vec<&PslRecord>)> {
+    let mut map: BTreeMap<(String, String, char), Vec<&PslRecord>> = BTreeMap::new();
+    for rec in records {
+        let strand = rec.strand.chars().next().unwrap_or('+');
+        map.entry((rec.t_name.clone(), rec.q_name.clone(), strand))
+            .or_default()
+            .push(rec);
+    }
+    map.into_iter().collect()
+}
+
+/// Finds the highest-scoring co-linear run through `remaining` (indices into
+/// `anchors`, which must be sorted by `(t_start, q_start)`) using a longest-
+/// weighted-path DP, in the spirit of `axtChain`'s dynamic-programming
+/// chainer. Returns the chain's anchor indices in target order and its
+/// total score.
+fn best_chain(anchors: &[Anchor], remaining: &[usize], gap_cost: GapCost) -> (Vec<usize>, i64) {
+    let n = remaining.len();
+    let mut dp = vec![0i64; n];
+    let mut prev: Vec<Option<usize>> = vec![None; n];
+
+    for i in 0..n {
+        let a = anchors[remaining[i]];
+        dp[i] = a.size() as i64 * MATCH_SCORE;
+        for j in 0..i {
+            let b = anchors[remaining[j]];
+            if b.t_end <= a.t_start && b.q_end <= a.q_start {
+                let dt = a.t_start - b.t_end;
+                let dq = a.q_start - b.q_end;
+                let score = dp[j] + a.size() as i64 * MATCH_SCORE - gap_cost.cost(dt, dq);
+                if score > dp[i] {
+                    dp[i] = score;
+                    prev[i] = Some(j);
+                }
+            }
+        }
+    }
+
+    let best_i = (0..n).max_by_key(|&i| dp[i]).unwrap();
+    let best_score = dp[best_i];
+
+    let mut path = vec![];
+    let mut cur = Some(best_i);
+    while let Some(i) = cur {
+        path.push(remaining[i]);
+        cur = prev[i];
+    }
+    path.reverse();
+
+    (path, best_score)
+}
+
+/// Builds a chain's blocks (with inter-block `dt`/`dq` gaps) from its
+/// target-ordered anchors.
+fn build_blocks(anchors: &[Anchor]) -> Vec<ChainBlock> {
+    let mut blocks = Vec::with_capacity(anchors.len());
+    for (i, a) in anchors.iter().enumerate() {
+        let (dt, dq) = match anchors.get(i + 1) {
+            Some(next) => (next.t_start - a.t_end, next.q_start - a.q_end),
+            None => (0, 0),
+        };
+        blocks.push(ChainBlock {
+            size: a.size(),
+            dt,
+            dq,
+        });
+    }
+    blocks
+}
+
+/// Chains the blocks of same-(target, query, strand) PSL records into one or
+/// more longer chains: within each group, repeatedly extracts the highest-
+/// scoring co-linear run of remaining blocks until none are left. This
+/// mirrors what `axtChain`/`chainAntiRepeat` do for a small, close-to-
+/// collinear genome pair, without needing those binaries on `$PATH`.
+pub fn chain_psl(records: &[PslRecord], gap_cost: GapCost) -> Vec<Chain> {
+    let mut chains = vec![];
+    let mut next_id = 1u64;
+
+    for ((t_name, q_name, strand), group) in group_by_pair(records) {
+        let mut anchors: Vec<Anchor> = vec![];
+        for rec in &group {
+            for i in 0..rec.block_count {
+                let size = rec.block_sizes[i];
+                anchors.push(Anchor {
+                    t_start: rec.t_starts[i],
+                    t_end: rec.t_starts[i] + size,
+                    q_start: rec.q_starts[i],
+                    q_end: rec.q_starts[i] + size,
+                });
+            }
+        }
+        if anchors.is_empty() {
+            continue;
+        }
+        anchors.sort_by_key(|a| (a.t_start, a.q_start));
+        anchors.dedup_by_key(|a| (a.t_start, a.t_end, a.q_start, a.q_end));
+
+        let t_size = group[0].t_size;
+        let q_size = group[0].q_size;
+
+        let mut remaining: Vec<usize> = (0..anchors.len()).collect();
+        while !remaining.is_empty() {
+            let (path, score) = best_chain(&anchors, &remaining, gap_cost);
+            if path.is_empty() {
+                break;
+            }
+
+            let chain_anchors: Vec<Anchor> = path.iter().map(|&i| anchors[i]).collect();
+            let blocks = build_blocks(&chain_anchors);
+
+            chains.push(Chain {
+                score,
+                id: next_id,
+                t_name: t_name.clone(),
+                t_size,
+                t_start: chain_anchors.first().unwrap().t_start,
+                t_end: chain_anchors.last().unwrap().t_end,
+                q_name: q_name.clone(),
+                q_size,
+                q_strand: strand,
+                q_start: chain_anchors.first().unwrap().q_start,
+                q_end: chain_anchors.last().unwrap().q_end,
+                blocks,
+            });
+            next_id += 1;
+
+            remaining.retain(|i| !path.contains(i));
+        }
+    }
+
+    chains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::psl::PslRecord;
+
+    fn psl(t_starts: &[u64], q_starts: &[u64], sizes: &[u64]) -> PslRecord {
+        PslRecord {
+            matches: sizes.iter().sum(),
+            mismatches: 0,
+            rep_matches: 0,
+            n_count: 0,
+            q_num_insert: 0,
+            q_base_insert: 0,
+            t_num_insert: 0,
+            t_base_insert: 0,
+            strand: "+".to_string(),
+            q_name: "query1".to_string(),
+            q_size: 1000,
+            q_start: q_starts[0],
+            q_end: q_starts.last().unwrap() + sizes.last().unwrap(),
+            t_name: "target1".to_string(),
+            t_size: 2000,
+            t_start: t_starts[0],
+            t_end: t_starts.last().unwrap() + sizes.last().unwrap(),
+            block_count: sizes.len(),
+            block_sizes: sizes.to_vec(),
+            q_starts: q_starts.to_vec(),
+            t_starts: t_starts.to_vec(),
+        }
+    }
+
+    #[test]
+    fn chains_two_nearby_blocks_from_separate_psl_records() {
+        let records = vec![psl(&[100], &[100], &[50]), psl(&[200], &[200], &[50])];
+
+        let chains = chain_psl(&records, GapCost::LOOSE);
+
+        assert_eq!(chains.len(), 1);
+        let chain = &chains[0];
+        assert_eq!(chain.t_start, 100);
+        assert_eq!(chain.t_end, 250);
+        assert_eq!(chain.blocks.len(), 2);
+        assert_eq!(chain.blocks[0].size, 50);
+        assert_eq!(chain.blocks[0].dt, 50);
+        assert_eq!(chain.blocks[0].dq, 50);
+        assert_eq!(chain.blocks[1].size, 50);
+        assert_eq!(chain.blocks[1].dt, 0);
+        assert_eq!(chain.blocks[1].dq, 0);
+    }
+
+    #[test]
+    fn chain_write_matches_ucsc_chain_format() {
+        let chain = Chain {
+            score: 195,
+            id: 1,
+            t_name: "target1".to_string(),
+            t_size: 2000,
+            t_start: 100,
+            t_end: 250,
+            q_name: "query1".to_string(),
+            q_size: 1000,
+            q_strand: '+',
+            q_start: 100,
+            q_end: 250,
+            blocks: vec![
+                ChainBlock {
+                    size: 50,
+                    dt: 50,
+                    dq: 50,
+                },
+                ChainBlock {
+                    size: 50,
+                    dt: 0,
+                    dq: 0,
+                },
+            ],
+        };
+
+        let mut buf = vec![];
+        chain.write(&mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+
+        assert_eq!(
+            text,
+            "chain 195 target1 2000 + 100 250 query1 1000 + 100 250 1\n50\t50\t50\n50\n\n"
+        );
+    }
+
+    #[test]
+    fn chains_five_anchors_with_known_score() {
+        // 5 blocks of size 10, evenly spaced 10 apart on both target and
+        // query, so every gap costs the same. A custom zero-extend GapCost
+        // makes the total hand-verifiable: 5 * 10 * MATCH_SCORE - 4 * open.
+        let sizes = [10, 10, 10, 10, 10];
+        let starts = [0, 20, 40, 60, 80];
+        let records: Vec<_> = starts
+            .iter()
+            .zip(sizes.iter())
+            .map(|(&s, &sz)| psl(&[s], &[s], &[sz]))
+            .collect();
+        let gap_cost = GapCost {
+            open: 1,
+            extend: 0.0,
+        };
+
+        let chains = chain_psl(&records, gap_cost);
+
+        assert_eq!(chains.len(), 1);
+        let chain = &chains[0];
+        assert_eq!(chain.score, 5 * 10 * MATCH_SCORE - 4);
+        assert_eq!(chain.blocks.len(), 5);
+        assert_eq!(chain.t_start, 0);
+        assert_eq!(chain.t_end, 90);
+    }
+
+    #[test]
+    fn distant_anchors_are_not_chained_when_gap_cost_exceeds_the_gain() {
+        // This chainer has no hard `max_dist_between_matches` cutoff; instead
+        // a gap is only crossed when doing so improves the DP score. A very
+        // large gap between a big and a small block costs more than the
+        // small block is worth, so the two end up as separate chains.
+        let big = psl(&[100], &[100], &[50]);
+        let small = psl(&[1_000_000], &[1_000_000], &[5]);
+
+        let chains = chain_psl(&[big, small], GapCost::LOOSE);
+
+        assert_eq!(chains.len(), 2);
+        assert_eq!(chains[0].blocks.len(), 1);
+        assert_eq!(chains[1].blocks.len(), 1);
+    }
+
+    #[test]
+    fn lone_anchors_are_kept_without_a_minimum_pairs_filter() {
+        // Unlike a hypothetical DagChainer with a "minimum number of pairs"
+        // option, chain_psl never discards a single-block chain: every
+        // remaining anchor is extracted into its own chain until none are
+        // left. A single tiny block therefore still produces a valid chain.
+        let records = vec![psl(&[0], &[0], &[1])];
+
+        let chains = chain_psl(&records, GapCost::LOOSE);
+
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].blocks.len(), 1);
+        assert_eq!(chains[0].score, MATCH_SCORE);
+    }
+
+    #[test]
+    fn gap_cost_applies_the_documented_open_plus_log_extend_formula() {
+        let gap_cost = GapCost::LOOSE;
+
+        assert_eq!(gap_cost.cost(0, 0), 0);
+        // open=400, extend=30.0: 400 + floor(30 * ln(100)) = 400 + 138 = 538
+        assert_eq!(gap_cost.cost(100, 0), 538);
+        // the larger of dt/dq sets the gap size
+        assert_eq!(gap_cost.cost(30, 100), gap_cost.cost(0, 100));
+        // open=400, extend=30.0: 400 + floor(30 * ln(10)) = 400 + 69 = 469
+        assert_eq!(gap_cost.cost(10, 10), 469);
+    }
+}
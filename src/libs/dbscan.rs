@@ -125,6 +125,69 @@ where
         neighbors
     }
 
+    /// Performs DBSCAN clustering with a per-point weight vector, for datasets
+    /// where density should be measured with core-point thresholds scaled by
+    /// weight rather than a flat neighbor count.
+    ///
+    /// `weights[point]` is the contribution of `point` to any neighborhood it
+    /// belongs to; a point is a core point when the summed weight of its
+    /// `eps`-neighborhood (including itself) reaches `min_points`. The
+    /// unweighted [`Dbscan::perform_clustering`] is equivalent to calling this
+    /// with all weights set to `1.0`.
+    pub fn perform_clustering_weighted(
+        &mut self,
+        matrix: &ScoringMatrix<T>,
+        weights: &[f64],
+    ) -> &Vec<Option<usize>> {
+        assert_eq!(weights.len(), matrix.size(), "weights must cover all points");
+
+        self.clusters = vec![None; matrix.size()];
+        self.visited = vec![false; matrix.size()];
+        self.current_cluster = 0;
+
+        for point in 0..matrix.size() {
+            if self.visited[point] {
+                continue;
+            }
+
+            self.visited[point] = true;
+            let neighbors = self.region_query(matrix, point);
+            if self.neighborhood_weight(&neighbors, weights) >= self.min_points as f64 {
+                self.expand_cluster_weighted(matrix, point, neighbors, weights);
+                self.current_cluster += 1;
+            }
+        }
+
+        self.clusters.as_ref()
+    }
+
+    fn neighborhood_weight(&self, neighbors: &VecDeque<usize>, weights: &[f64]) -> f64 {
+        neighbors.iter().map(|&p| weights[p]).sum()
+    }
+
+    fn expand_cluster_weighted(
+        &mut self,
+        matrix: &ScoringMatrix<T>,
+        point: usize,
+        mut neighbors: VecDeque<usize>,
+        weights: &[f64],
+    ) {
+        self.clusters[point] = Some(self.current_cluster);
+
+        while let Some(other_point) = neighbors.pop_front() {
+            if !self.visited[other_point] {
+                self.visited[other_point] = true;
+                let mut other_neighbors = self.region_query(matrix, other_point);
+                if self.neighborhood_weight(&other_neighbors, weights) >= self.min_points as f64 {
+                    neighbors.append(&mut other_neighbors);
+                }
+            }
+            if self.clusters[other_point].is_none() {
+                self.clusters[other_point] = Some(self.current_cluster);
+            }
+        }
+    }
+
     fn all_clusters(&self) -> (HashMap<usize, Vec<usize>>, Vec<usize>) {
         let mut cluster_map: HashMap<usize, Vec<usize>> = HashMap::new();
         let mut noise_points: Vec<usize> = Vec::new();
@@ -195,6 +258,67 @@ where
     }
 }
 
+/// Computes each point's distance to its `k`-th nearest neighbor (itself
+/// excluded), for use in the `--auto-eps` k-distance heuristic: sorting these
+/// ascending and looking for the elbow (see [`find_elbow`]) gives a
+/// reasonable `eps` for `min_points = k`.
+pub fn k_distances<T>(matrix: &ScoringMatrix<T>, k: usize) -> Vec<f64>
+where
+    T: Default + Copy + PartialOrd + num_traits::ToPrimitive,
+{
+    let n = matrix.size();
+    let mut result = Vec::with_capacity(n);
+
+    for point in 0..n {
+        let mut dists: Vec<f64> = (0..n)
+            .filter(|&other| other != point)
+            .map(|other| matrix.get(point, other).to_f64().unwrap())
+            .collect();
+        dists.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let idx = k.saturating_sub(1).min(dists.len().saturating_sub(1));
+        result.push(dists.get(idx).copied().unwrap_or(0.0));
+    }
+
+    result
+}
+
+/// Picks the "elbow" of a k-distance plot using the maximum-curvature
+/// heuristic: `distances` is sorted ascending, then the point farthest from
+/// the straight line connecting the first and last points of the sorted
+/// curve is taken as the elbow. This is the same idea as the "kneedle"
+/// algorithm, without needing a numerically unstable second derivative.
+pub fn find_elbow(distances: &[f64]) -> f64 {
+    let mut sorted = distances.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    if sorted.len() < 3 {
+        return sorted.last().copied().unwrap_or(0.0);
+    }
+
+    let n = sorted.len();
+    let (x1, y1) = (0.0, sorted[0]);
+    let (x2, y2) = ((n - 1) as f64, sorted[n - 1]);
+    let line_len = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+
+    let mut best_idx = 0;
+    let mut best_dist = -1.0;
+    for (i, &y) in sorted.iter().enumerate() {
+        let x = i as f64;
+        let dist = if line_len == 0.0 {
+            0.0
+        } else {
+            ((x2 - x1) * (y1 - y) - (x1 - x) * (y2 - y1)).abs() / line_len
+        };
+        if dist > best_dist {
+            best_dist = dist;
+            best_idx = i;
+        }
+    }
+
+    sorted[best_idx]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -273,4 +397,148 @@ mod tests {
 
         assert_eq!(clustering[0], None);
     }
+
+    #[test]
+    fn test_weighted_clustering_matches_unweighted_with_uniform_weights() {
+        let mut m = ScoringMatrix::<i8>::new(5, 0, 100);
+        m.set(0, 1, 1);
+        m.set(0, 2, 9);
+        m.set(0, 3, 9);
+        m.set(0, 4, 9);
+        m.set(1, 2, 9);
+        m.set(1, 3, 9);
+        m.set(1, 4, 9);
+        m.set(2, 3, 1);
+        m.set(2, 4, 9);
+        m.set(3, 4, 9);
+        let weights = vec![1.0; 5];
+
+        let mut dbscan = Dbscan::new(1, 2);
+        let clustering = dbscan.perform_clustering_weighted(&m, &weights);
+
+        assert_eq!(clustering[0], Some(0));
+        assert_eq!(clustering[1], Some(0));
+        assert_eq!(clustering[2], Some(1));
+        assert_eq!(clustering[3], Some(1));
+        assert_eq!(clustering[4], None);
+    }
+
+    #[test]
+    fn test_heavy_point_alone_can_satisfy_min_points() {
+        // A single very dense point (large weight) should form a core point
+        // on its own, even though a uniform-weight run of the same geometry
+        // would leave it as noise.
+        let mut m = ScoringMatrix::<i8>::new(2, 0, 100);
+        m.set(0, 1, 1);
+        let weights = vec![5.0, 1.0];
+
+        let mut dbscan = Dbscan::new(1, 5);
+        let clustering = dbscan.perform_clustering_weighted(&m, &weights);
+
+        assert_eq!(clustering[0], Some(0));
+        assert_eq!(clustering[1], Some(0));
+    }
+
+    #[test]
+    fn test_k_distances_excludes_self_and_picks_kth_neighbor() {
+        let mut m = ScoringMatrix::<i8>::new(3, 0, 100);
+        m.set(0, 1, 1);
+        m.set(0, 2, 9);
+        m.set(1, 2, 5);
+
+        // 1st nearest neighbor of point 0 is point 1 (distance 1)
+        assert_eq!(k_distances(&m, 1), vec![1.0, 1.0, 5.0]);
+        // 2nd nearest neighbor of point 0 is point 2 (distance 9)
+        assert_eq!(k_distances(&m, 2), vec![9.0, 5.0, 9.0]);
+    }
+
+    #[test]
+    fn test_find_elbow_picks_the_sharp_bend() {
+        // A flat run followed by a sharp jump: the elbow should land at (or
+        // just before) the jump, not at either flat end.
+        let distances = vec![1.0, 1.0, 1.0, 1.0, 1.0, 10.0, 20.0, 30.0];
+        let elbow = find_elbow(&distances);
+        assert!(elbow >= 1.0 && elbow <= 10.0, "elbow = {}", elbow);
+    }
+
+    #[test]
+    fn test_find_elbow_handles_short_input() {
+        assert_eq!(find_elbow(&[]), 0.0);
+        assert_eq!(find_elbow(&[3.0]), 3.0);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// A symmetric `ScoringMatrix<i8>` of `n` points with every pairwise
+    /// distance drawn independently, paired with `n` itself for convenience.
+    fn arb_scoring_matrix(max_n: usize) -> impl Strategy<Value = (ScoringMatrix<i8>, usize)> {
+        (1..=max_n).prop_flat_map(|n| {
+            let num_pairs = n * (n - 1) / 2;
+            prop::collection::vec(0i8..=30, num_pairs).prop_map(move |dists| {
+                let mut m = ScoringMatrix::<i8>::new(n, 0, i8::MAX);
+                let mut idx = 0;
+                for i in 0..n {
+                    for j in (i + 1)..n {
+                        m.set(i, j, dists[idx]);
+                        idx += 1;
+                    }
+                }
+                (m, n)
+            })
+        })
+    }
+
+    proptest! {
+        /// Checks the four invariants from the DBSCAN definition against
+        /// randomly generated distance matrices, independently of
+        /// `expand_cluster`/`region_query`'s implementation.
+        #[test]
+        fn dbscan_clustering_satisfies_its_invariants(
+            (matrix, n) in arb_scoring_matrix(8),
+            eps in 0i8..=30,
+            min_points in 1usize..=4,
+        ) {
+            let mut dbscan = Dbscan::new(eps, min_points);
+            let clustering = dbscan.perform_clustering(&matrix).clone();
+
+            // (1) every point has exactly one label: a cluster id or noise
+            prop_assert_eq!(clustering.len(), n);
+
+            let cluster_ids: std::collections::HashSet<usize> =
+                clustering.iter().filter_map(|label| *label).collect();
+            // (4) the cluster count is at most N (non-negative is implied by usize)
+            prop_assert!(cluster_ids.len() <= n);
+
+            for point in 0..n {
+                // Recomputed directly from the matrix, independently of the
+                // private `region_query`, self included (as DBSCAN does).
+                let neighbor_count = (0..n).filter(|&other| matrix.get(point, other) <= eps).count();
+
+                match clustering[point] {
+                    Some(cluster_id) => {
+                        let cluster_members: Vec<usize> =
+                            (0..n).filter(|&p| clustering[p] == Some(cluster_id)).collect();
+                        // (2) every member of a multi-point cluster is within eps
+                        // of at least one *other* member of that same cluster;
+                        // a singleton cluster (a core point with no in-eps
+                        // neighbors of its own) has no "other member" to check.
+                        if cluster_members.len() > 1 {
+                            let has_neighbor_in_cluster = cluster_members
+                                .iter()
+                                .any(|&other| other != point && matrix.get(point, other) <= eps);
+                            prop_assert!(has_neighbor_in_cluster);
+                        }
+                    }
+                    None => {
+                        // (3) noise points have fewer than `min_points` neighbors
+                        prop_assert!(neighbor_count < min_points);
+                    }
+                }
+            }
+        }
+    }
 }
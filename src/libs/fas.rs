@@ -36,7 +36,7 @@ fn parse_strand(strand: &str) -> Result<String, io::Error> {
     }
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug)]
 pub struct FasEntry {
     range: Range,
     seq: Vec<u8>,
@@ -102,6 +102,7 @@ impl fmt::Display for FasEntry {
 }
 
 /// A Fas alignment block.
+#[derive(Debug)]
 pub struct FasBlock {
     pub entries: Vec<FasEntry>,
     pub names: Vec<String>,
@@ -109,6 +110,13 @@ pub struct FasBlock {
 }
 
 /// Get the next FasBlock out of the input.
+///
+/// # Deprecated
+/// `Ok`/`Err` can't tell a parse error from clean EOF, so callers looping
+/// with `while let Ok(...)` silently stop at the first malformed block.
+/// Use [`FasBlockReader`] instead, which yields `Result<FasBlock>` per
+/// block and terminates the iteration cleanly on EOF.
+#[deprecated(since = "0.3.6", note = "use `FasBlockReader` instead")]
 pub fn next_fas_block<T: io::BufRead + ?Sized>(mut input: &mut T) -> Result<FasBlock, io::Error> {
     let mut header: Option<String> = None;
     {
@@ -139,6 +147,187 @@ pub fn next_fas_block<T: io::BufRead + ?Sized>(mut input: &mut T) -> Result<FasB
     Ok(block)
 }
 
+/// An error produced while reading a [`FasBlock`], carrying enough context
+/// to locate the offending block in the source file.
+#[derive(Debug)]
+pub struct FasBlockError {
+    /// 0-based index of the block being read when the error occurred.
+    pub block_index: usize,
+    /// 1-based line number in the input at which the error occurred.
+    pub line_number: usize,
+    pub source: io::Error,
+}
+
+impl fmt::Display for FasBlockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "error in fas block {} at line {}: {}",
+            self.block_index, self.line_number, self.source
+        )
+    }
+}
+
+impl std::error::Error for FasBlockError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Iterator over the [`FasBlock`]s of a `.fas` file, yielding
+/// `Result<FasBlock, FasBlockError>` so parse errors are surfaced to the
+/// caller instead of being indistinguishable from clean EOF.
+///
+/// ```
+/// # use hnsm::FasBlockReader;
+/// # use std::io::BufReader;
+/// let str = ">S288c.I(+):13267-13287|species=S288c
+/// TCGTCAGTTGGTTGACCATTA
+/// ";
+/// let reader = BufReader::new(str.as_bytes());
+/// let mut blocks = FasBlockReader::new(reader);
+/// let block = blocks.next().unwrap().unwrap();
+/// # assert_eq!(block.entries.len(), 1);
+/// assert!(blocks.next().is_none());
+/// ```
+pub struct FasBlockReader<T> {
+    input: T,
+    line_number: usize,
+    block_index: usize,
+    done: bool,
+}
+
+impl<T: io::BufRead> FasBlockReader<T> {
+    pub fn new(input: T) -> Self {
+        Self {
+            input,
+            line_number: 0,
+            block_index: 0,
+            done: false,
+        }
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut lines = LinesRef {
+            buf: &mut self.input,
+        };
+        match lines.next() {
+            Some(Ok(line)) => {
+                self.line_number += 1;
+                Ok(Some(line))
+            }
+            Some(Err(e)) => Err(e),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T: io::BufRead> Iterator for FasBlockReader<T> {
+    type Item = Result<FasBlock, FasBlockError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let header;
+        loop {
+            match self.read_line() {
+                Ok(Some(line)) => {
+                    if line.trim().is_empty() || line.starts_with('#') {
+                        continue;
+                    } else if line.starts_with('>') {
+                        header = Some(line);
+                        break;
+                    } else {
+                        self.done = true;
+                        return Some(Err(FasBlockError {
+                            block_index: self.block_index,
+                            line_number: self.line_number,
+                            source: io::Error::new(io::ErrorKind::Other, "Unexpected line"),
+                        }));
+                    }
+                }
+                Ok(None) => {
+                    // Clean EOF: no more blocks.
+                    self.done = true;
+                    return None;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(FasBlockError {
+                        block_index: self.block_index,
+                        line_number: self.line_number,
+                        source: e,
+                    }));
+                }
+            }
+        }
+
+        let header = header.unwrap();
+        let start_line = self.line_number;
+        let mut block_lines: VecDeque<String> = VecDeque::new();
+        block_lines.push_back(header);
+        loop {
+            match self.read_line() {
+                Ok(Some(line)) => {
+                    if line.is_empty() {
+                        break;
+                    }
+                    block_lines.push_back(line);
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(FasBlockError {
+                        block_index: self.block_index,
+                        line_number: self.line_number,
+                        source: e,
+                    }));
+                }
+            }
+        }
+
+        let result = parse_fas_block_lines(block_lines).map_err(|e| FasBlockError {
+            block_index: self.block_index,
+            line_number: start_line,
+            source: e,
+        });
+        self.block_index += 1;
+        Some(result)
+    }
+}
+
+fn parse_fas_block_lines(mut block_lines: VecDeque<String>) -> Result<FasBlock, io::Error> {
+    let mut block_entries: Vec<FasEntry> = vec![];
+    let mut block_names: Vec<String> = vec![];
+    let mut block_headers: Vec<String> = vec![];
+
+    while let Some(h) = block_lines.pop_front() {
+        let header = match h.starts_with('>') {
+            true => &h[1..],
+            false => h.as_str(),
+        };
+        let range = Range::from_str(header);
+        let seq = block_lines
+            .pop_front()
+            .ok_or(io::Error::new(io::ErrorKind::Other, "Truncated block"))?
+            .as_bytes()
+            .to_vec();
+
+        let entry = FasEntry::from(&range, &seq);
+        block_entries.push(entry);
+        block_names.push(range.name().to_string());
+        block_headers.push(header.to_string());
+    }
+
+    Ok(FasBlock {
+        entries: block_entries,
+        names: block_names,
+        headers: block_headers,
+    })
+}
+
 pub fn parse_fas_block(
     header: String,
     iter: impl Iterator<Item = Result<String, io::Error>>,
@@ -319,6 +508,7 @@ mod fas_tests {
     use std::io::BufReader;
 
     #[test]
+    #[allow(deprecated)]
     fn parse_fas_block_range() {
         let str = ">S288c.I(+):13267-13287|species=S288c
 TCGTCAGTTGGTTGACCATTA
@@ -355,6 +545,41 @@ GC-TAAAATATGAA-CGATATTTA-CCTGTAGAGGGACTATGGGAT-CCCCATACTACTTT--
             "GCGTATAATATGAACCAGTATCTTTTTCATGAAG-GGCTATGGTATACTCCATATTACTTCTA".to_string()
         );
     }
+
+    #[test]
+    fn fas_block_reader_stops_cleanly_at_eof() {
+        let str = ">S288c.I(+):13267-13287|species=S288c
+TCGTCAGTTGGTTGACCATTA
+>YJM789.gi_151941327(-):5668-5688|species=YJM789
+TCGTCAGTTGGTTGACCATTA
+";
+        let reader = BufReader::new(str.as_bytes());
+        let mut blocks = crate::FasBlockReader::new(reader);
+        assert!(blocks.next().unwrap().is_ok());
+        assert!(blocks.next().is_none());
+    }
+
+    #[test]
+    fn fas_block_reader_surfaces_error_on_truncated_block_instead_of_eof() {
+        // The second block is missing its sequence line: a lone header.
+        // A naive `while let Ok(...)` loop can't tell this apart from a
+        // clean EOF and would just stop; `FasBlockReader` must yield `Err`.
+        let str = ">S288c.I(+):13267-13287|species=S288c
+TCGTCAGTTGGTTGACCATTA
+
+>YJM789.gi_151941327(-):5668-5688|species=YJM789
+";
+        let reader = BufReader::new(str.as_bytes());
+        let mut blocks = crate::FasBlockReader::new(reader);
+        assert!(blocks.next().unwrap().is_ok());
+
+        let err = blocks.next().unwrap().unwrap_err();
+        assert_eq!(err.block_index, 1);
+        assert_eq!(err.line_number, 4);
+
+        // Iteration terminates after the error; it doesn't loop forever.
+        assert!(blocks.next().is_none());
+    }
 }
 
 // MAF
@@ -410,6 +635,56 @@ impl MafEntry {
     }
 }
 
+impl fmt::Display for MafEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "s {} {} {} {} {} {}",
+            self.src,
+            self.start,
+            self.size,
+            self.strand,
+            self.src_size,
+            str::from_utf8(&self.alignment).unwrap(),
+        )
+    }
+}
+
+/// Builds a [`MafEntry`] from a block-fasta entry, the inverse of [`MafEntry::to_range`].
+///
+/// `sizes` maps `name.chr` (the range's [`intspan::Range::name`] and
+/// [`intspan::Range::chr`], joined with `.`, matching the MAF `src` field) to the full
+/// length of that source sequence, needed to recover the `-` strand start coordinate.
+pub fn fas_entry_to_maf(
+    entry: &FasEntry,
+    sizes: &BTreeMap<String, i32>,
+) -> Result<MafEntry, io::Error> {
+    let range = entry.range();
+    let src = format!("{}.{}", range.name(), range.chr());
+    let strand = range.strand().to_string();
+    let size = (range.end() - range.start() + 1) as u64;
+
+    let src_size = *sizes.get(&src).ok_or(io::Error::new(
+        io::ErrorKind::Other,
+        ".sizes file doesn't contain the needed chr",
+    ))? as u64;
+
+    let start = if strand == "-" {
+        src_size - *range.end() as u64
+    } else {
+        (*range.start() - 1) as u64
+    };
+
+    Ok(MafEntry {
+        alignment: entry.seq().clone(),
+        src,
+        start,
+        size,
+        src_size,
+        strand,
+    })
+}
+
 /// A MAF alignment block.
 #[derive(Debug, PartialEq, Eq)]
 pub struct MafBlock {
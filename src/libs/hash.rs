@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 // This code is adapted from https://curiouscoding.nl/posts/fast-minimizers/
 pub trait Hasher: Clone {
@@ -6,6 +7,50 @@ pub trait Hasher: Clone {
     fn hash_kmers(&mut self, k: usize, t: &[u8]) -> Vec<u64> {
         t.windows(k).map(|kmer| self.hash(kmer)).collect::<Vec<_>>()
     }
+    /// Like [`Hasher::hash_kmers`], but hashes a spaced seed: only the
+    /// positions where `pattern` is `true` contribute to each k-mer's hash,
+    /// so a mismatch outside those positions doesn't change it. `k` (the
+    /// window length) is `pattern.len()`.
+    fn hash_kmers_seeded(&mut self, pattern: &[bool], t: &[u8]) -> Vec<u64> {
+        t.windows(pattern.len())
+            .map(|kmer| {
+                let masked: Vec<u8> = kmer
+                    .iter()
+                    .zip(pattern)
+                    .filter(|(_, &keep)| keep)
+                    .map(|(&b, _)| b)
+                    .collect();
+                self.hash(&masked)
+            })
+            .collect::<Vec<_>>()
+    }
+}
+
+/// Parses a `--seed-pattern`-style mask string (e.g. `111010011`) into a
+/// `bool` per position, `true` where that position contributes to a spaced
+/// seed's hash. Errors on anything but `0`/`1` characters, or an empty
+/// pattern.
+///
+/// ```
+///     # use hnsm::parse_seed_pattern;
+///     assert_eq!(parse_seed_pattern("101").unwrap(), vec![true, false, true]);
+///     assert!(parse_seed_pattern("102").is_err());
+///     assert!(parse_seed_pattern("").is_err());
+/// ```
+pub fn parse_seed_pattern(pattern: &str) -> anyhow::Result<Vec<bool>> {
+    if pattern.is_empty() {
+        return Err(anyhow::anyhow!("--seed-pattern must not be empty"));
+    }
+    pattern
+        .chars()
+        .map(|c| match c {
+            '1' => Ok(true),
+            '0' => Ok(false),
+            _ => Err(anyhow::anyhow!(
+                "--seed-pattern must be a binary string of 0s and 1s, got `{pattern}`"
+            )),
+        })
+        .collect()
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -35,34 +80,94 @@ pub struct JumpingMinimizer<H = FxHash> {
     pub hasher: H,
 }
 
+/// Scans a window of size `w` across `hashes`, jumping straight to the next
+/// position a new minimum could appear at rather than sliding one at a time.
+/// Shared by [`JumpingMinimizer::minimizer`] and
+/// [`JumpingMinimizer::minimizer_seeded`], which differ only in how the
+/// k-mer hashes were computed.
+fn jumping_minimizer_positions(hashes: &[u64], w: usize) -> Vec<usize> {
+    let mut minimizers = Vec::new();
+
+    let mut start = 0;
+    while start < hashes.len() - w {
+        // Position_min returns the position of the leftmost minimal hash.
+        let min_pos = start + hashes[start..start + w].iter().position_min().expect("w > 0");
+        minimizers.push(min_pos);
+        start = min_pos + 1;
+    }
+    // Possibly add one last minimizer.
+    let start = hashes.len() - w;
+    let min_pos = start + hashes[start..].iter().position_min().expect("w > 0");
+    if minimizers.last() != Some(&min_pos) {
+        minimizers.push(min_pos);
+    }
+    minimizers
+}
+
 impl<H: Hasher> Minimizer for JumpingMinimizer<H> {
     fn minimizer(&mut self, text: &[u8]) -> Vec<(usize, u64)> {
-        let mut minimizers = Vec::new();
-
         // Precompute hashes of all k-mers.
         let hashes = self.hasher.hash_kmers(self.k, text);
+        jumping_minimizer_positions(&hashes, self.w)
+            .into_iter()
+            .map(|e| (e, hashes[e]))
+            .collect()
+    }
+}
 
-        let mut start = 0;
-        while start < hashes.len() - self.w {
-            // Position_min returns the position of the leftmost minimal hash.
-            let min_pos = start
-                + hashes[start..start + self.w]
-                    .iter()
-                    .position_min()
-                    .expect("w > 0");
-            minimizers.push(min_pos);
-            start = min_pos + 1;
-        }
-        // Possibly add one last minimizer.
-        let start = hashes.len() - self.w;
-        let min_pos = start + hashes[start..].iter().position_min().expect("w > 0");
-        if minimizers.last() != Some(&min_pos) {
-            minimizers.push(min_pos);
-        }
-        minimizers.iter().map(|e| (*e, hashes[*e])).collect()
+impl<H: Hasher> JumpingMinimizer<H> {
+    /// Like [`Minimizer::minimizer`], but hashes each k-mer as a spaced
+    /// seed: only the positions marked in `pattern` (see
+    /// [`parse_seed_pattern`]) contribute to its hash. `pattern.len()`
+    /// replaces `self.k` as the window length; `self.w` still controls the
+    /// minimizer window as usual.
+    pub fn minimizer_seeded(&mut self, text: &[u8], pattern: &[bool]) -> Vec<(usize, u64)> {
+        let hashes = self.hasher.hash_kmers_seeded(pattern, text);
+        jumping_minimizer_positions(&hashes, self.w)
+            .into_iter()
+            .map(|e| (e, hashes[e]))
+            .collect()
     }
 }
 
+/// Closed syncmer positions and hashes in `seq`: a `k`-mer is kept when its
+/// minimal `s`-mer (by [`FxHash`]) occurs at the first or last offset of the
+/// `k`-mer, so unlike a minimizer window, whether a k-mer is sampled depends
+/// only on the k-mer itself, giving a more evenly spread selection than
+/// minimizers (which can cluster when several consecutive windows share the
+/// same minimum). A closed syncmer is kept at roughly `2/(k-s+1)` of
+/// positions. Returns `(position, hash-of-the-k-mer)` pairs, the same shape
+/// [`Minimizer::minimizer`] returns.
+///
+/// ```
+///     # use hnsm::seq_syncmers;
+///     let seq = b"ACGTACGTACGTACGT";
+///     let syncmers = seq_syncmers(seq, 8, 3);
+///     assert!(!syncmers.is_empty());
+/// ```
+pub fn seq_syncmers(seq: &[u8], k: usize, s: usize) -> Vec<(usize, u64)> {
+    let hasher = FxHash;
+    if s == 0 || s > k || seq.len() < k {
+        return vec![];
+    }
+
+    seq.windows(k)
+        .enumerate()
+        .filter_map(|(i, kmer)| {
+            let min_pos = kmer
+                .windows(s)
+                .map(|smer| hasher.hash(smer))
+                .position_min()
+                .expect("k >= s");
+            if min_pos == 0 || min_pos == k - s {
+                Some((i, hasher.hash(kmer)))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 // This code is adapted from the nthash crate
 // And with modifications from https://curiouscoding.nl/posts/fast-minimizers/
 
@@ -182,3 +287,434 @@ impl<'a> Iterator for NtHashIterator<'a> {
 }
 
 impl<'a> ExactSizeIterator for NtHashIterator<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // This crate exposes two `Hasher`s for `JumpingMinimizer` (`FxHash`,
+    // `MurmurHash3`) plus the standalone canonical `NtHashIterator`; there is
+    // no third-party "rapid" hasher or "mod" hasher, and no standalone
+    // `seq_mins` free function. These tests exercise the closest real
+    // equivalents: `JumpingMinimizer::minimizer` at `w = 1` (every k-mer's
+    // hash is its own minimizer, i.e. the full k-mer hash set) for `FxHash`
+    // and `MurmurHash3`, and `NtHashIterator` (already canonical: it returns
+    // `min(fh, rh)` per k-mer) for reverse-complement equivalence.
+
+    fn dna(len: usize) -> Vec<u8> {
+        const ALPHABET: [u8; 4] = [b'A', b'C', b'G', b'T'];
+        // A fixed, deterministic pseudo-random walk so the fixture is stable across runs
+        let mut seq = Vec::with_capacity(len);
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..len {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            seq.push(ALPHABET[(state % 4) as usize]);
+        }
+        seq
+    }
+
+    fn revcomp(seq: &[u8]) -> Vec<u8> {
+        seq.iter()
+            .rev()
+            .map(|&b| match b {
+                b'A' => b'T',
+                b'C' => b'G',
+                b'G' => b'C',
+                b'T' => b'A',
+                _ => b,
+            })
+            .collect()
+    }
+
+    fn kmer_hashes(seq: &[u8], k: usize, w: usize, hasher: &str) -> HashSet<u64> {
+        let minimizers = match hasher {
+            "fx" => JumpingMinimizer {
+                w,
+                k,
+                hasher: FxHash,
+            }
+            .minimizer(seq),
+            "murmur" => JumpingMinimizer {
+                w,
+                k,
+                hasher: MurmurHash3,
+            }
+            .minimizer(seq),
+            _ => unreachable!(),
+        };
+        minimizers.into_iter().map(|(_, h)| h).collect()
+    }
+
+    #[test]
+    fn all_hashers_return_non_empty_sets() {
+        let seq = dna(100);
+
+        for hasher in ["fx", "murmur"] {
+            let hashes = kmer_hashes(&seq, 7, 1, hasher);
+            assert!(!hashes.is_empty(), "{hasher} returned an empty set");
+        }
+
+        let nthash: HashSet<u64> = NtHashIterator::new(&seq, 7).unwrap().collect();
+        assert!(!nthash.is_empty(), "NtHashIterator returned an empty set");
+    }
+
+    #[test]
+    fn nthash_is_canonical_across_reverse_complement() {
+        let seq = dna(100);
+        let rc = revcomp(&seq);
+
+        let fwd: HashSet<u64> = NtHashIterator::new(&seq, 7).unwrap().collect();
+        let rev: HashSet<u64> = NtHashIterator::new(&rc, 7).unwrap().collect();
+
+        assert_eq!(fwd, rev);
+    }
+
+    #[test]
+    fn identical_inputs_produce_identical_sets() {
+        let seq = dna(100);
+
+        for hasher in ["fx", "murmur"] {
+            let a = kmer_hashes(&seq, 7, 3, hasher);
+            let b = kmer_hashes(&seq, 7, 3, hasher);
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn minimizer_set_size_does_not_increase_as_window_grows() {
+        let seq = dna(200);
+
+        for hasher in ["fx", "murmur"] {
+            let small_w = kmer_hashes(&seq, 7, 2, hasher).len();
+            let large_w = kmer_hashes(&seq, 7, 16, hasher).len();
+            assert!(
+                large_w <= small_w,
+                "{hasher}: w=16 set ({large_w}) should not exceed w=2 set ({small_w})"
+            );
+        }
+    }
+
+    #[test]
+    fn spaced_seed_ignores_mismatches_outside_the_pattern() {
+        let pattern = parse_seed_pattern("101").unwrap();
+        let mut hasher = FxHash;
+
+        // Position 1 ('C' vs 'G') is masked out by the middle `0`, so both
+        // k-mers hash the same despite differing at that position.
+        let a = hasher.hash_kmers_seeded(&pattern, b"ACT");
+        let b = hasher.hash_kmers_seeded(&pattern, b"AGT");
+        assert_eq!(a, b);
+
+        // Position 0 ('A' vs 'T') is unmasked, so this one differs.
+        let c = hasher.hash_kmers_seeded(&pattern, b"TCT");
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn parse_seed_pattern_rejects_non_binary_and_empty_strings() {
+        assert!(parse_seed_pattern("111010011").is_ok());
+        assert!(parse_seed_pattern("102").is_err());
+        assert!(parse_seed_pattern("").is_err());
+    }
+
+    #[test]
+    fn minimizer_seeded_matches_plain_minimizer_for_an_all_ones_pattern() {
+        let seq = dna(100);
+        let pattern = vec![true; 7];
+
+        let seeded = JumpingMinimizer {
+            w: 3,
+            k: 7,
+            hasher: FxHash,
+        }
+        .minimizer_seeded(&seq, &pattern);
+        let plain = JumpingMinimizer {
+            w: 3,
+            k: 7,
+            hasher: FxHash,
+        }
+        .minimizer(&seq);
+
+        assert_eq!(seeded, plain);
+    }
+
+    #[test]
+    fn syncmer_density_is_roughly_two_over_k_minus_s_plus_one() {
+        let seq = dna(20_000);
+        let (k, s) = (16, 8);
+
+        let syncmers = seq_syncmers(&seq, k, s);
+        let num_kmers = seq.len() - k + 1;
+        let density = syncmers.len() as f64 / num_kmers as f64;
+        let expected = 2.0 / (k - s + 1) as f64;
+
+        assert!(
+            (density - expected).abs() < 0.02,
+            "density {density} should be close to {expected}"
+        );
+    }
+}
+
+/// Counts the common elements between two sorted, deduplicated slices via a
+/// merge-style walk, the sort-then-merge alternative to hashing both sides
+/// into a `HashSet` and intersecting. Better cache behavior than hash-set
+/// lookups makes this faster once both sides are large enough.
+///
+/// ```
+///     # use hnsm::intersect_sorted;
+///     assert_eq!(intersect_sorted(&[1, 3, 5, 7], &[2, 3, 4, 5]), 2);
+/// ```
+pub fn intersect_sorted(a: &[u64], b: &[u64]) -> usize {
+    let (mut i, mut j) = (0, 0);
+    let mut count = 0;
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+/// A persisted per-record hash vector: the deduplicated minimizer hashes
+/// computed for one record, tagged with the hasher/k/w that produced them so
+/// a `--load`ed entry can be told apart from one built with different
+/// parameters.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HvEntry {
+    pub name: String,
+    pub hasher: String,
+    pub kmer: usize,
+    pub window: usize,
+    pub hashes: Vec<u64>,
+}
+
+/// Packs a DNA k-mer into the low `2*k` bits of a `u64`, two bits per base
+/// (`A` = 0, `C` = 1, `G` = 2, `T` = 3, matched case-insensitively). Returns
+/// `None` if `kmer` is longer than 32 bases (would overflow a `u64`) or
+/// contains anything but `A`/`C`/`G`/`T`.
+///
+/// ```
+///     # use hnsm::encode_kmer_2bit;
+///     assert_eq!(encode_kmer_2bit(b"ACGT"), Some(0b00_01_10_11));
+///     assert_eq!(encode_kmer_2bit(b"ACGN"), None);
+/// ```
+pub fn encode_kmer_2bit(kmer: &[u8]) -> Option<u64> {
+    if kmer.len() > 32 {
+        return None;
+    }
+    let mut code: u64 = 0;
+    for &b in kmer {
+        let bits: u64 = match b.to_ascii_uppercase() {
+            b'A' => 0,
+            b'C' => 1,
+            b'G' => 2,
+            b'T' => 3,
+            _ => return None,
+        };
+        code = (code << 2) | bits;
+    }
+    Some(code)
+}
+
+/// The inverse of [`encode_kmer_2bit`]: unpacks a `2*k`-bit code back into
+/// its uppercase base string.
+///
+/// ```
+///     # use hnsm::{decode_kmer_2bit, encode_kmer_2bit};
+///     let code = encode_kmer_2bit(b"ACGT").unwrap();
+///     assert_eq!(decode_kmer_2bit(code, 4), "ACGT");
+/// ```
+pub fn decode_kmer_2bit(code: u64, k: usize) -> String {
+    const BASES: [u8; 4] = [b'A', b'C', b'G', b'T'];
+    let mut bytes = vec![0u8; k];
+    let mut code = code;
+    for i in (0..k).rev() {
+        bytes[i] = BASES[(code & 0b11) as usize];
+        code >>= 2;
+    }
+    String::from_utf8(bytes).unwrap()
+}
+
+/// The reverse complement of a packed k-mer, computed directly on its 2-bit
+/// code: complementing a base flips its low bit (`A` <-> `T` is `00` <-> `11`,
+/// `C` <-> `G` is `01` <-> `10`), and reversing the k-mer reverses the order
+/// of its 2-bit groups.
+fn revcomp_kmer_2bit(code: u64, k: usize) -> u64 {
+    let mut code = code;
+    let mut rc: u64 = 0;
+    for _ in 0..k {
+        rc = (rc << 2) | (!code & 0b11);
+        code >>= 2;
+    }
+    rc
+}
+
+/// The canonical form of a k-mer: whichever of it and its reverse complement
+/// packs to the smaller `u64` code, so a k-mer and its reverse complement
+/// always land on the same value. `None` under the same conditions as
+/// [`encode_kmer_2bit`].
+///
+/// ```
+///     # use hnsm::canonical_kmer_2bit;
+///     assert_eq!(canonical_kmer_2bit(b"ACGT"), canonical_kmer_2bit(b"ACGT"));
+/// ```
+pub fn canonical_kmer_2bit(kmer: &[u8]) -> Option<u64> {
+    let fwd = encode_kmer_2bit(kmer)?;
+    let rc = revcomp_kmer_2bit(fwd, kmer.len());
+    Some(fwd.min(rc))
+}
+
+#[cfg(test)]
+mod kmer_2bit_tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_encode_and_decode() {
+        let kmer = b"ACGTACGTAC";
+        let code = encode_kmer_2bit(kmer).unwrap();
+        assert_eq!(decode_kmer_2bit(code, kmer.len()), "ACGTACGTAC");
+    }
+
+    #[test]
+    fn rejects_non_acgt_bases() {
+        assert_eq!(encode_kmer_2bit(b"ACGN"), None);
+        assert_eq!(canonical_kmer_2bit(b"ACGN"), None);
+    }
+
+    #[test]
+    fn rejects_kmers_longer_than_32_bases() {
+        let kmer = vec![b'A'; 33];
+        assert_eq!(encode_kmer_2bit(&kmer), None);
+    }
+
+    #[test]
+    fn is_case_insensitive() {
+        assert_eq!(encode_kmer_2bit(b"acgt"), encode_kmer_2bit(b"ACGT"));
+    }
+
+    #[test]
+    fn canonical_form_agrees_with_its_own_reverse_complement() {
+        // TTAA's reverse complement is TTAA itself (a palindrome).
+        assert_eq!(
+            canonical_kmer_2bit(b"TTAA").unwrap(),
+            encode_kmer_2bit(b"TTAA").unwrap()
+        );
+
+        // ACGT and its reverse complement ACGT are also a palindrome.
+        assert_eq!(
+            canonical_kmer_2bit(b"AACGTT").unwrap(),
+            canonical_kmer_2bit(b"AACGTT").unwrap()
+        );
+
+        // GGGG's reverse complement is CCCC; canonical picks the smaller code
+        // of the two, so both orientations agree.
+        let fwd = canonical_kmer_2bit(b"GGGG").unwrap();
+        let rc = canonical_kmer_2bit(b"CCCC").unwrap();
+        assert_eq!(fwd, rc);
+    }
+}
+
+/// One occurrence of a k-mer hash: which sequence it came from and where.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Posting {
+    pub seq_id: u32,
+    pub pos: u32,
+}
+
+/// A k-mer hash index shared by anything that would otherwise recompute
+/// minimizers from scratch: hashes map to the `(seq_id, pos)` postings where
+/// they occur, and the whole thing bincode-serializes to a single file so it
+/// can be built once and reused across runs.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct KmerIndex {
+    postings: std::collections::HashMap<u64, Vec<Posting>>,
+}
+
+impl KmerIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one occurrence of `hash` at `pos` in sequence `seq_id`.
+    pub fn insert(&mut self, hash: u64, seq_id: u32, pos: u32) {
+        self.postings
+            .entry(hash)
+            .or_default()
+            .push(Posting { seq_id, pos });
+    }
+
+    /// The postings recorded for `hash`, or an empty slice if it was never inserted.
+    pub fn query(&self, hash: u64) -> &[Posting] {
+        self.postings.get(&hash).map_or(&[], |v| v.as_slice())
+    }
+
+    pub fn len(&self) -> usize {
+        self.postings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.postings.is_empty()
+    }
+
+    /// Bincode-serializes the index to `path`, the same compact binary format
+    /// `hnsm hv --save` uses for `HvEntry`.
+    pub fn save(&self, path: &str) -> anyhow::Result<()> {
+        std::fs::write(path, bincode::serialize(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Ok(bincode::deserialize(&bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod kmer_index_tests {
+    use super::*;
+
+    #[test]
+    fn query_returns_inserted_postings_in_insertion_order() {
+        let mut index = KmerIndex::new();
+        index.insert(42, 0, 10);
+        index.insert(42, 1, 3);
+        index.insert(7, 0, 0);
+
+        assert_eq!(
+            index.query(42),
+            &[Posting { seq_id: 0, pos: 10 }, Posting { seq_id: 1, pos: 3 }]
+        );
+        assert_eq!(index.query(7), &[Posting { seq_id: 0, pos: 0 }]);
+        assert!(index.query(999).is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_a_binary_file() -> anyhow::Result<()> {
+        let mut index = KmerIndex::new();
+        index.insert(42, 0, 10);
+        index.insert(42, 1, 3);
+        index.insert(7, 2, 5);
+
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("kmers.idx");
+        let path = path.to_str().unwrap();
+
+        index.save(path)?;
+        let loaded = KmerIndex::load(path)?;
+
+        assert_eq!(loaded.len(), index.len());
+        assert_eq!(loaded.query(42), index.query(42));
+        assert_eq!(loaded.query(7), index.query(7));
+
+        Ok(())
+    }
+}
@@ -1,7 +1,74 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufRead, Read, Write};
 
+//----------------------------
+// Name lists
+//----------------------------
+/// Returns whether `path` is the stdin sentinel used for `<infile>`-style
+/// arguments (`stdin`) or the common short form (`-`).
+pub fn is_stdin(path: &str) -> bool {
+    path == "stdin" || path == "-"
+}
+
+/// Reads a newline-delimited name list from `path`, or from stdin when
+/// [`is_stdin`] is true. Lines are trimmed; blank lines and `#` comments are
+/// ignored.
+pub fn read_name_list(path: &str) -> Vec<String> {
+    let reader = intspan::reader(if is_stdin(path) { "stdin" } else { path });
+    reader
+        .lines()
+        .map_while(Result::ok)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+//----------------------------
+// ExcludeSet
+//----------------------------
+/// A set of names loaded from a `--exclude` list file, shared by commands
+/// that need to drop listed names (e.g. `hnsm some`, `hnsm order`).
+///
+/// With `strict`, [`ExcludeSet::warn_unused`] reports list entries that
+/// [`ExcludeSet::contains`] never matched against the input.
+pub struct ExcludeSet {
+    names: HashSet<String>,
+    seen: HashSet<String>,
+    strict: bool,
+}
+
+impl ExcludeSet {
+    pub fn new(path: &str, strict: bool) -> Self {
+        Self {
+            names: intspan::read_first_column(path).into_iter().collect(),
+            seen: HashSet::new(),
+            strict,
+        }
+    }
+
+    /// Returns whether `name` is on the exclude list, recording it as seen.
+    pub fn contains(&mut self, name: &str) -> bool {
+        let hit = self.names.contains(name);
+        if hit {
+            self.seen.insert(name.to_string());
+        }
+        hit
+    }
+
+    /// With `--strict`, warns on stderr about excluded names never seen in the input.
+    pub fn warn_unused(&self) {
+        if !self.strict {
+            return;
+        }
+        for name in &self.names {
+            if !self.seen.contains(name) {
+                eprintln!("Name in --exclude not found: {}", name);
+            }
+        }
+    }
+}
+
 //----------------------------
 // AsmEntry
 //----------------------------
@@ -0,0 +1,346 @@
+//! Implementation of the [Markov Clustering (MCL)](https://micans.org/mcl/)
+//! algorithm.
+use crate::ScoringMatrix;
+use ndarray::Array2;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub struct Mcl {
+    expansion: i32,
+    inflation: f64,
+    max_iter: usize,
+    tolerance: f64,
+    prune_limit: f64,
+}
+
+impl Mcl {
+    /// Creates a new MCL instance.
+    ///
+    /// # Parameters
+    ///
+    /// * `expansion` - The power to which the (column-normalized) matrix is raised
+    ///   at each iteration.
+    /// * `inflation` - The power used for the entry-wise inflation step. Larger
+    ///   values yield more, smaller clusters.
+    /// * `max_iter` - The maximum number of iterations before giving up on
+    ///   convergence.
+    /// * `tolerance` - The maximum absolute difference between successive
+    ///   matrices for the process to be considered converged.
+    /// * `prune_limit` - Entries below this value are zeroed out after each
+    ///   inflation step, keeping the matrix sparse. `0.0` disables pruning.
+    pub fn new(expansion: i32, inflation: f64, max_iter: usize, tolerance: f64) -> Self {
+        Mcl {
+            expansion,
+            inflation,
+            max_iter,
+            tolerance,
+            prune_limit: 0.0,
+        }
+    }
+
+    /// Sets the pruning threshold; entries below `limit` are zeroed out after
+    /// each inflation step. `0.0` (the default) disables pruning.
+    pub fn with_prune_limit(mut self, limit: f64) -> Self {
+        self.prune_limit = limit;
+        self
+    }
+
+    /// Performs MCL clustering from the given similarity matrix.
+    ///
+    /// # Returns
+    ///
+    /// Returns a list of clusters, each a list of point indices. Callers that
+    /// also need the convergence iteration count should use
+    /// [`Mcl::perform_clustering_with_stats`] instead.
+    ///
+    /// ```
+    /// # use hnsm::Mcl;
+    /// # use hnsm::ScoringMatrix;
+    ///
+    /// let mut m = ScoringMatrix::<f64>::new(4, 1.0, 0.0);
+    /// m.set(0, 1, 0.9);
+    /// m.set(2, 3, 0.9);
+    ///
+    /// let mcl = Mcl::new(2, 2.0, 100, 1e-6);
+    /// let clusters = mcl.perform_clustering(&m);
+    ///
+    /// assert_eq!(clusters.len(), 2);
+    /// ```
+    pub fn perform_clustering(&self, matrix: &ScoringMatrix<f64>) -> Vec<Vec<usize>> {
+        let (clusters, _, _) = self.perform_clustering_with_stats(matrix);
+        clusters
+    }
+
+    /// Performs MCL clustering, additionally reporting convergence diagnostics.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of `(clusters, iterations, converged)`, where `iterations` is the
+    /// number of expansion/inflation rounds actually run, and `converged`
+    /// indicates whether the process settled below `tolerance` before
+    /// `max_iter` was reached. Callers can use `converged == false` to detect
+    /// a half-mixed matrix rather than silently trusting the result.
+    pub fn perform_clustering_with_stats(
+        &self,
+        matrix: &ScoringMatrix<f64>,
+    ) -> (Vec<Vec<usize>>, usize, bool) {
+        let size = matrix.size();
+        let mut m = matrix.to_arr2();
+        add_self_loops(&mut m);
+        normalize_columns(&mut m);
+
+        let mut iterations = 0;
+        let mut converged = false;
+
+        for i in 0..self.max_iter {
+            iterations = i + 1;
+
+            let prev = m.clone();
+            m = expand(&m, self.expansion);
+            inflate(&mut m, self.inflation);
+            prune(&mut m, self.prune_limit);
+            normalize_columns(&mut m);
+
+            let diff = (&m - &prev).mapv(f64::abs).sum();
+            if diff < self.tolerance {
+                converged = true;
+                break;
+            }
+        }
+
+        (interpret_clusters(&m, size), iterations, converged)
+    }
+}
+
+fn add_self_loops(m: &mut Array2<f64>) {
+    for i in 0..m.nrows() {
+        m[[i, i]] += 1.0;
+    }
+}
+
+fn normalize_columns(m: &mut Array2<f64>) {
+    for mut col in m.columns_mut() {
+        let sum: f64 = col.sum();
+        if sum > 0.0 {
+            col.mapv_inplace(|v| v / sum);
+        }
+    }
+}
+
+fn expand(m: &Array2<f64>, power: i32) -> Array2<f64> {
+    let mut result = m.clone();
+    for _ in 1..power {
+        result = result.dot(m);
+    }
+    result
+}
+
+fn inflate(m: &mut Array2<f64>, inflation: f64) {
+    m.mapv_inplace(|v| v.max(0.0).powf(inflation));
+}
+
+/// Zeroes out entries below `limit`, keeping the matrix sparse. A `limit` of
+/// `0.0` is a no-op.
+fn prune(m: &mut Array2<f64>, limit: f64) {
+    if limit <= 0.0 {
+        return;
+    }
+    m.mapv_inplace(|v| if v < limit { 0.0 } else { v });
+}
+
+/// Reads off clusters by grouping columns that share the same set of
+/// surviving (non-zero) rows, i.e. the attractors of the converged matrix.
+fn interpret_clusters(m: &Array2<f64>, size: usize) -> Vec<Vec<usize>> {
+    let mut attractor_of: HashMap<usize, usize> = HashMap::new();
+
+    for col in 0..size {
+        let mut best_row = col;
+        let mut best_val = f64::MIN;
+        for row in 0..size {
+            let v = m[[row, col]];
+            if v > best_val {
+                best_val = v;
+                best_row = row;
+            }
+        }
+        attractor_of.insert(col, best_row);
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (point, attractor) in attractor_of {
+        groups.entry(attractor).or_default().push(point);
+    }
+
+    let mut clusters: Vec<Vec<usize>> = groups.into_values().collect();
+    for cluster in clusters.iter_mut() {
+        cluster.sort_unstable();
+    }
+    clusters.sort_by_key(|c| c[0]);
+
+    clusters
+}
+
+/// Computes the graph modularity `Q` of a clustering, weighted by the edge
+/// scores in `matrix`: `Q = (1 / 2m) * sum_ij (A_ij - k_i*k_j / 2m) * delta(c_i, c_j)`,
+/// summed over ordered pairs `(i, j)` in the same cluster, where `A` is the
+/// weighted adjacency matrix, `k_i` is the weighted degree of node `i`, and
+/// `m` is the total edge weight. Used to score how well a set of clusters
+/// (e.g. from [`Mcl::perform_clustering`] at different inflation values)
+/// explains the graph's structure; higher is better, `0.0` for a graph with
+/// no edges.
+pub fn modularity(matrix: &ScoringMatrix<f64>, clusters: &[Vec<usize>]) -> f64 {
+    let size = matrix.size();
+
+    let degree: Vec<f64> = (0..size)
+        .map(|i| (0..size).map(|j| matrix.get(i, j)).sum())
+        .collect();
+    let two_m: f64 = degree.iter().sum();
+    if two_m == 0.0 {
+        return 0.0;
+    }
+
+    let mut cluster_of = vec![usize::MAX; size];
+    for (c, members) in clusters.iter().enumerate() {
+        for &point in members {
+            cluster_of[point] = c;
+        }
+    }
+
+    let mut q = 0.0;
+    for i in 0..size {
+        for j in 0..size {
+            if cluster_of[i] == cluster_of[j] {
+                q += matrix.get(i, j) - degree[i] * degree[j] / two_m;
+            }
+        }
+    }
+
+    q / two_m
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_disjoint_pairs_form_two_clusters() {
+        let mut m = ScoringMatrix::<f64>::new(4, 1.0, 0.0);
+        m.set(0, 1, 0.9);
+        m.set(2, 3, 0.9);
+
+        let mcl = Mcl::new(2, 2.0, 100, 1e-6);
+        let clusters = mcl.perform_clustering(&m);
+
+        assert_eq!(clusters.len(), 2);
+        let mut sizes: Vec<usize> = clusters.iter().map(|c| c.len()).collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![2, 2]);
+    }
+
+    #[test]
+    fn test_reports_converged_when_below_tolerance() {
+        let mut m = ScoringMatrix::<f64>::new(3, 1.0, 0.0);
+        m.set(0, 1, 0.9);
+        m.set(1, 2, 0.9);
+
+        let mcl = Mcl::new(2, 2.0, 100, 1e-6);
+        let (_, iterations, converged) = mcl.perform_clustering_with_stats(&m);
+
+        assert!(converged);
+        assert!(iterations <= 100);
+    }
+
+    #[test]
+    fn test_reports_non_convergence_at_max_iter() {
+        let mut m = ScoringMatrix::<f64>::new(3, 1.0, 0.0);
+        m.set(0, 1, 0.9);
+        m.set(1, 2, 0.9);
+
+        let mcl = Mcl::new(2, 2.0, 1, 0.0);
+        let (_, iterations, converged) = mcl.perform_clustering_with_stats(&m);
+
+        assert_eq!(iterations, 1);
+        assert!(!converged);
+    }
+
+    #[test]
+    fn test_modularity_is_high_for_two_disjoint_pairs() {
+        let mut m = ScoringMatrix::<f64>::new(4, 0.0, 0.0);
+        m.set(0, 1, 1.0);
+        m.set(2, 3, 1.0);
+
+        let good = modularity(&m, &[vec![0, 1], vec![2, 3]]);
+        let bad = modularity(&m, &[vec![0, 2], vec![1, 3]]);
+
+        assert!(good > bad);
+        assert!(good > 0.0);
+    }
+
+    #[test]
+    fn test_modularity_is_zero_with_no_edges() {
+        let m = ScoringMatrix::<f64>::new(3, 0.0, 0.0);
+        assert_eq!(modularity(&m, &[vec![0], vec![1], vec![2]]), 0.0);
+    }
+
+    #[test]
+    fn test_two_cliques_converge_within_20_iterations() {
+        // A-B and C-D, no inter-clique edges.
+        let mut m = ScoringMatrix::<f64>::new(4, 1.0, 0.0);
+        m.set(0, 1, 0.9);
+        m.set(2, 3, 0.9);
+
+        let mcl = Mcl::new(2, 2.0, 100, 1e-6);
+        let (clusters, iterations, converged) = mcl.perform_clustering_with_stats(&m);
+
+        assert!(converged);
+        assert!(iterations < 20, "converged in {} iterations", iterations);
+        assert_eq!(clusters, vec![vec![0, 1], vec![2, 3]]);
+    }
+
+    #[test]
+    fn test_perform_clustering_is_stable_across_calls() {
+        let mut m = ScoringMatrix::<f64>::new(4, 1.0, 0.0);
+        m.set(0, 1, 0.9);
+        m.set(2, 3, 0.9);
+
+        let mcl = Mcl::new(2, 2.0, 100, 1e-6);
+        let first = mcl.perform_clustering(&m);
+        let second = mcl.perform_clustering(&m);
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_high_inflation_yields_singletons_on_a_sparse_graph() {
+        // A loose chain, no dense cliques: 0-1-2-3-4, each edge modest.
+        let mut m = ScoringMatrix::<f64>::new(5, 0.0, 0.0);
+        m.set(0, 1, 0.3);
+        m.set(1, 2, 0.3);
+        m.set(2, 3, 0.3);
+        m.set(3, 4, 0.3);
+
+        let mcl = Mcl::new(2, 10.0, 100, 1e-6);
+        let clusters = mcl.perform_clustering(&m);
+
+        assert!(
+            clusters.iter().all(|c| c.len() == 1),
+            "expected all-singleton clusters, got {:?}",
+            clusters
+        );
+        assert_eq!(clusters.len(), 5);
+    }
+
+    #[test]
+    fn test_prune_limit_zero_matches_a_negligible_prune_limit() {
+        let mut m = ScoringMatrix::<f64>::new(4, 1.0, 0.0);
+        m.set(0, 1, 0.9);
+        m.set(2, 3, 0.9);
+
+        let unpruned = Mcl::new(2, 2.0, 100, 1e-6).perform_clustering(&m);
+        let pruned = Mcl::new(2, 2.0, 100, 1e-6)
+            .with_prune_limit(1e-5)
+            .perform_clustering(&m);
+
+        assert_eq!(unpruned, pruned);
+    }
+}
@@ -1,9 +1,17 @@
 pub mod alignment;
+pub mod banded;
+pub mod chain;
 pub mod dbscan;
 pub mod fas;
 pub mod hash;
 pub mod io;
 pub mod loc;
 pub mod matrix;
+pub mod mcl;
 pub mod mds;
 pub mod nt;
+pub mod progress;
+pub mod psl;
+pub mod stats;
+pub mod tsne;
+pub mod twobit;
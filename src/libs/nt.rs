@@ -1,3 +1,5 @@
+use intspan::IntSpan;
+
 /// Standard IUB/IUPAC Nucleic Acid Codes
 /// Code =>  Nucleic Acid(s)
 ///  A   =>  Adenine
@@ -142,6 +144,375 @@ pub fn to_n(nt: u8) -> u8 {
     }
 }
 
+/// Returns the concrete bases an IUPAC ambiguity code can stand for, per the
+/// table documented at the top of this file. Returns `None` for a byte that
+/// isn't an IUPAC nucleotide code (e.g. `-`), which callers should treat as
+/// a single literal base rather than an ambiguity to expand.
+///
+/// ```
+/// assert_eq!(hnsm::iupac_bases(b'a'), Some(b"A".as_ref()));
+/// assert_eq!(hnsm::iupac_bases(b'M'), Some(b"AC".as_ref()));
+/// assert_eq!(hnsm::iupac_bases(b'N'), Some(b"ACGT".as_ref()));
+/// assert_eq!(hnsm::iupac_bases(b'-'), None);
+/// ```
+pub fn iupac_bases(code: u8) -> Option<&'static [u8]> {
+    Some(match code.to_ascii_uppercase() {
+        b'A' => b"A",
+        b'C' => b"C",
+        b'G' => b"G",
+        b'T' | b'U' => b"T",
+        b'M' => b"AC",
+        b'R' => b"AG",
+        b'W' => b"AT",
+        b'S' => b"CG",
+        b'Y' => b"CT",
+        b'K' => b"GT",
+        b'V' => b"ACG",
+        b'H' => b"ACT",
+        b'D' => b"AGT",
+        b'B' => b"CGT",
+        b'N' => b"ACGT",
+        _ => return None,
+    })
+}
+
+/// Encodes a non-empty set of concrete bases (any of `A`/`C`/`G`/`T`, case-
+/// and order-insensitive, duplicates ignored) as the single IUPAC ambiguity
+/// code standing for exactly that set, the inverse of [`iupac_bases`]. Bases
+/// outside `ACGT` are ignored; an empty or all-ignored input returns `N`.
+///
+/// ```
+/// assert_eq!(hnsm::iupac_code(b"A"), b'A');
+/// assert_eq!(hnsm::iupac_code(b"CA"), b'M');
+/// assert_eq!(hnsm::iupac_code(b"acgt"), b'N');
+/// ```
+pub fn iupac_code(bases: &[u8]) -> u8 {
+    let mut has = [false; 4]; // A, C, G, T
+    for &b in bases {
+        match b.to_ascii_uppercase() {
+            b'A' => has[0] = true,
+            b'C' => has[1] = true,
+            b'G' => has[2] = true,
+            b'T' | b'U' => has[3] = true,
+            _ => {}
+        }
+    }
+    match has {
+        [true, false, false, false] => b'A',
+        [false, true, false, false] => b'C',
+        [false, false, true, false] => b'G',
+        [false, false, false, true] => b'T',
+        [true, true, false, false] => b'M',
+        [true, false, true, false] => b'R',
+        [true, false, false, true] => b'W',
+        [false, true, true, false] => b'S',
+        [false, true, false, true] => b'Y',
+        [false, false, true, true] => b'K',
+        [true, true, true, false] => b'V',
+        [true, true, false, true] => b'H',
+        [true, false, true, true] => b'D',
+        [false, true, true, true] => b'B',
+        _ => b'N',
+    }
+}
+
+/// Maps an ASCII base or IUPAC ambiguity code to its complement, preserving
+/// case. Bases outside the IUPAC alphabet (e.g. `-`) map to themselves.
+pub static NT_COMP: &[u8; 256] = &{
+    let mut array = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        array[i] = i as u8;
+        i += 1;
+    }
+
+    array[b'A' as usize] = b'T';
+    array[b'T' as usize] = b'A';
+    array[b'a' as usize] = b't';
+    array[b't' as usize] = b'a';
+
+    array[b'C' as usize] = b'G';
+    array[b'G' as usize] = b'C';
+    array[b'c' as usize] = b'g';
+    array[b'g' as usize] = b'c';
+
+    array[b'U' as usize] = b'A';
+    array[b'u' as usize] = b'a';
+
+    array[b'M' as usize] = b'K';
+    array[b'K' as usize] = b'M';
+    array[b'm' as usize] = b'k';
+    array[b'k' as usize] = b'm';
+
+    array[b'R' as usize] = b'Y';
+    array[b'Y' as usize] = b'R';
+    array[b'r' as usize] = b'y';
+    array[b'y' as usize] = b'r';
+
+    array[b'W' as usize] = b'W';
+    array[b'w' as usize] = b'w';
+
+    array[b'S' as usize] = b'S';
+    array[b's' as usize] = b's';
+
+    array[b'V' as usize] = b'B';
+    array[b'B' as usize] = b'V';
+    array[b'v' as usize] = b'b';
+    array[b'b' as usize] = b'v';
+
+    array[b'H' as usize] = b'D';
+    array[b'D' as usize] = b'H';
+    array[b'h' as usize] = b'd';
+    array[b'd' as usize] = b'h';
+
+    array[b'N' as usize] = b'N';
+    array[b'n' as usize] = b'n';
+
+    array
+};
+
+/// Complements a single base or IUPAC ambiguity code, preserving case.
+///
+/// ```
+/// assert_eq!(hnsm::complement_nt(b'a'), b't');
+/// assert_eq!(hnsm::complement_nt(b'M'), b'K');
+/// assert_eq!(hnsm::complement_nt(b'-'), b'-');
+/// ```
+pub fn complement_nt(nt: u8) -> u8 {
+    NT_COMP[nt as usize]
+}
+
+/// Shannon entropy (in bits) of the k-mer frequency distribution in `seq`,
+/// using `NT_VAL` to pack each k-mer of A/C/G/T into a 2-bit-per-base
+/// integer index. Any k-mer spanning an N or ambiguity code is skipped
+/// rather than counted. Used by `hnsm filter --min-entropy` to drop
+/// low-complexity/repetitive sequences.
+///
+/// Sequences shorter than `k` (or `k == 0`) have no complete k-mer and
+/// return entropy 0.
+/// ```
+/// // Highly repetitive sequence: entropy is low.
+/// assert!(hnsm::kmer_entropy(b"AAAAAAAAAA", 2) < 0.5);
+/// // A sequence cycling through all four bases has near-maximal entropy.
+/// assert!(hnsm::kmer_entropy(b"ACGTACGTACGT", 2) > 1.9);
+/// assert_eq!(hnsm::kmer_entropy(b"AC", 3), 0.0);
+/// ```
+pub fn kmer_entropy(seq: &[u8], k: usize) -> f64 {
+    if k == 0 || seq.len() < k {
+        return 0.0;
+    }
+
+    let n_kmers = 1usize << (2 * k); // 4^k
+    let mask = n_kmers - 1;
+    let mut counts = vec![0u32; n_kmers];
+    let mut total = 0u32;
+
+    let mut code = 0usize;
+    let mut run = 0usize; // consecutive valid (A/C/G/T) bases seen
+    for &nt in seq {
+        let val = NT_VAL[nt as usize];
+        if val > Nt::T as usize {
+            // N or an ambiguity code: the current window can't form a k-mer.
+            run = 0;
+            continue;
+        }
+        code = ((code << 2) | val) & mask;
+        run += 1;
+        if run >= k {
+            counts[code] += 1;
+            total += 1;
+        }
+    }
+
+    if total == 0 {
+        return 0.0;
+    }
+
+    let mut entropy = 0.0;
+    for &c in &counts {
+        if c == 0 {
+            continue;
+        }
+        let p = c as f64 / total as f64;
+        entropy -= p * p.log2();
+    }
+    entropy
+}
+
+/// Coordinates (1-based, inclusive) of low-complexity regions in `seq`,
+/// found by a simplified symmetric DUST algorithm: sliding a `window`-sized
+/// frame across the sequence and scoring it by the abundance of repeated
+/// overlapping triplets, using `NT_VAL` to bin each A/C/G/T triplet into one
+/// of 64 counters (in the spirit of `dustmasker`'s windowed
+/// triplet-frequency score). A window scores `sum(c_i*(c_i-1)/2)` over its
+/// triplet counts `c_i`, normalized by one less than the number of triplets
+/// in the window; windows scoring at or above `level` are unioned into the
+/// returned regions. A run of Ns/ambiguity codes splits the sequence so no
+/// window crosses it. Used by `hnsm dust`.
+///
+/// ```
+/// // A long poly-A run is extremely low-complexity...
+/// let poly_a = vec![b'A'; 64];
+/// let ints = hnsm::dust_mask(&poly_a, 64, 20.0);
+/// assert_eq!(ints.spans(), vec![(1, 64)]);
+///
+/// // ...while a sequence that cycles through many distinct triplets is not.
+/// let varied = b"ACGTACGATCGATCGGATCGATGATCGTAGCTAGTACGTGCATGCATGCA";
+/// let ints = hnsm::dust_mask(varied, 64, 20.0);
+/// assert!(ints.spans().is_empty());
+/// ```
+pub fn dust_mask(seq: &[u8], window: usize, level: f64) -> IntSpan {
+    let mut ints = IntSpan::new();
+    if window < 3 {
+        return ints;
+    }
+
+    let mut run_start = 0usize;
+    for pos in 0..=seq.len() {
+        let is_break = pos == seq.len() || is_n(seq[pos]);
+        if is_break {
+            if pos > run_start {
+                dust_run(&seq[run_start..pos], run_start, window, level, &mut ints);
+            }
+            run_start = pos + 1;
+        }
+    }
+
+    ints
+}
+
+/// Scores every `window`-sized (or shorter, if the run itself is shorter)
+/// frame of an N-free `run` and unions the ones scoring at or above `level`
+/// into `ints`, offsetting coordinates by `offset` (the run's start in the
+/// original sequence).
+fn dust_run(run: &[u8], offset: usize, window: usize, level: f64, ints: &mut IntSpan) {
+    let len = run.len();
+    let w = window.min(len);
+    if w < 3 {
+        return;
+    }
+
+    for start in 0..=(len - w) {
+        let sub = &run[start..start + w];
+
+        let mut counts = [0u32; 64];
+        for t in sub.windows(3) {
+            let idx = (NT_VAL[t[0] as usize] << 4) | (NT_VAL[t[1] as usize] << 2) | NT_VAL[t[2] as usize];
+            counts[idx] += 1;
+        }
+
+        let num_triplets = (w - 2) as f64;
+        let sum: f64 = counts
+            .iter()
+            .filter(|&&c| c > 0)
+            .map(|&c| (c as f64) * ((c - 1) as f64) / 2.0)
+            .sum();
+        let score = sum / (num_triplets - 1.0).max(1.0);
+
+        if score >= level {
+            let lo = (offset + start + 1) as i32;
+            let hi = (offset + start + w) as i32;
+            ints.add_pair(lo, hi);
+        }
+    }
+}
+
+/// Fraction of bytes satisfying `pred` in each sliding window of `seq`,
+/// scanned in one O(n) pass via a running prefix sum rather than
+/// recounting each window from scratch. Returns `(start, end, fraction)`
+/// triples with 0-based, half-open `[start, end)` coordinates; a final
+/// partial window shorter than `window` is dropped. Used by `hnsm gc` and
+/// `hnsm masked --window`.
+///
+/// ```
+/// let seq = b"AAAACCCCGGGGTTTT";
+/// let windows = hnsm::windowed_fraction(seq, 4, 4, |b| b == b'A');
+/// assert_eq!(windows, vec![(0, 4, 1.0), (4, 8, 0.0), (8, 12, 0.0), (12, 16, 0.0)]);
+/// ```
+pub fn windowed_fraction(
+    seq: &[u8],
+    window: usize,
+    step: usize,
+    pred: impl Fn(u8) -> bool,
+) -> Vec<(usize, usize, f64)> {
+    if window == 0 || step == 0 || seq.len() < window {
+        return vec![];
+    }
+
+    // prefix[i] = number of bytes satisfying `pred` in seq[..i]
+    let mut prefix = Vec::with_capacity(seq.len() + 1);
+    prefix.push(0usize);
+    for &b in seq {
+        let last = *prefix.last().unwrap();
+        prefix.push(last + if pred(b) { 1 } else { 0 });
+    }
+
+    let mut windows = Vec::new();
+    let mut start = 0usize;
+    while start + window <= seq.len() {
+        let end = start + window;
+        let hits = prefix[end] - prefix[start];
+        windows.push((start, end, hits as f64 / window as f64));
+        start += step;
+    }
+    windows
+}
+
+/// One sliding window's GC content/skew, as produced by `gc_windows`.
+pub struct GcWindow {
+    pub start: usize,
+    pub end: usize,
+    pub gc: Option<f64>,
+    pub skew: Option<f64>,
+}
+
+/// GC content and GC skew (`(G-C)/(G+C)`) in sliding windows across `seq`,
+/// built atop `windowed_fraction` so each base is only ever visited by the
+/// underlying prefix-sum pass, never recounted per window. A window whose
+/// N-fraction exceeds `max_n_frac` reports `None` for both metrics; a
+/// window with no G/C at all also reports `None` for skew, since the ratio
+/// is undefined. Used by `hnsm gc`.
+///
+/// ```
+/// let seq = b"GGGGCCCCAAAATTTT";
+/// let windows = hnsm::gc_windows(seq, 4, 4, 0.5);
+/// assert_eq!(windows[0].gc, Some(1.0));
+/// assert_eq!(windows[0].skew, Some(1.0));
+/// assert_eq!(windows[2].gc, Some(0.0));
+/// assert_eq!(windows[2].skew, None);
+/// ```
+pub fn gc_windows(seq: &[u8], window: usize, step: usize, max_n_frac: f64) -> Vec<GcWindow> {
+    let g = windowed_fraction(seq, window, step, |b| b.to_ascii_uppercase() == b'G');
+    let c = windowed_fraction(seq, window, step, |b| b.to_ascii_uppercase() == b'C');
+    let n = windowed_fraction(seq, window, step, is_n);
+
+    g.into_iter()
+        .zip(c)
+        .zip(n)
+        .map(|(((start, end, g_frac), (_, _, c_frac)), (_, _, n_frac))| {
+            if n_frac > max_n_frac {
+                GcWindow {
+                    start,
+                    end,
+                    gc: None,
+                    skew: None,
+                }
+            } else {
+                GcWindow {
+                    start,
+                    end,
+                    gc: Some(g_frac + c_frac),
+                    skew: if g_frac + c_frac > 0.0 {
+                        Some((g_frac - c_frac) / (g_frac + c_frac))
+                    } else {
+                        None
+                    },
+                }
+            }
+        })
+        .collect()
+}
+
 /// block -> row -> column
 pub static AA_TAB: &[[[char; 4]; 4]; 4] = &[
     [
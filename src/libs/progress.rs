@@ -0,0 +1,173 @@
+//! Shared progress-reporting helper for long-running record/pair loops.
+//!
+//! Wraps the `AtomicU64` counter + background polling thread pattern
+//! originally hand-rolled in `cmd::distance`: call [`ProgressReporter::spawn`]
+//! once, increment it from a single-threaded loop or a `rayon` closure via
+//! [`ProgressReporter::inc`]/[`ProgressReporter::counter`], and call
+//! [`ProgressReporter::finish`] when the work is done. The line-formatting
+//! itself is the pure, injectable-writer function [`render_line`], so its
+//! output can be unit-tested without spawning threads or touching stderr.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A running progress counter, optionally backed by a background thread that
+/// prints `\r`-updating status lines to stderr every `interval`.
+pub struct ProgressReporter {
+    counter: Arc<AtomicU64>,
+    done: Arc<AtomicBool>,
+    monitor: Option<std::thread::JoinHandle<()>>,
+}
+
+impl ProgressReporter {
+    /// Starts a background monitor thread when `enabled` (typically
+    /// `is_progress && !is_quiet`), printing to stderr every `interval`.
+    /// `total` is the expected item count, when known upfront (as in
+    /// `distance`'s pair list); pass `None` for streaming loops (as in `hv`
+    /// or `filter`) where only a running count, not an ETA, can be reported.
+    ///
+    /// When `enabled` is `false`, [`ProgressReporter::inc`] still updates the
+    /// counter but nothing is ever printed, so call sites don't need to
+    /// special-case `--quiet`. Regardless of `enabled`, the monitor thread is
+    /// also silenced when stderr isn't a terminal, so redirected/piped runs
+    /// stay clean without every call site having to check that itself.
+    pub fn spawn(total: Option<u64>, unit: &str, enabled: bool, interval: Duration) -> Self {
+        let enabled = enabled && is_stderr_tty();
+        let counter = Arc::new(AtomicU64::new(0));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let monitor = if enabled {
+            let counter = Arc::clone(&counter);
+            let done = Arc::clone(&done);
+            let unit = unit.to_string();
+            Some(std::thread::spawn(move || {
+                let start = Instant::now();
+                while !done.load(Ordering::Relaxed) {
+                    std::thread::sleep(interval);
+                    let processed = counter.load(Ordering::Relaxed);
+                    let mut line = Vec::new();
+                    let _ = render_line(&mut line, processed, total, &unit, start.elapsed());
+                    let _ = std::io::stderr().write_all(&line);
+                    let _ = std::io::stderr().flush();
+                }
+            }))
+        } else {
+            None
+        };
+
+        Self {
+            counter,
+            done,
+            monitor,
+        }
+    }
+
+    /// Returns a cheaply-clonable handle to the counter, for incrementing
+    /// from inside `rayon` closures or other worker threads.
+    pub fn counter(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.counter)
+    }
+
+    /// Increments the counter by `n`; for single-threaded loops that don't
+    /// need their own `Arc<AtomicU64>` clone.
+    pub fn inc(&self, n: u64) {
+        self.counter.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Stops the background thread (if any) and, if it was running, prints a
+    /// trailing newline so subsequent output doesn't collide with the last
+    /// `\r` line.
+    pub fn finish(self) {
+        self.done.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.monitor {
+            handle.join().unwrap();
+            eprintln!();
+        }
+    }
+}
+
+/// Whether stderr is currently attached to a terminal; used to silence the
+/// background monitor thread when output is redirected or piped.
+fn is_stderr_tty() -> bool {
+    use std::io::IsTerminal;
+    std::io::stderr().is_terminal()
+}
+
+/// Renders one `\r`-prefixed status line, e.g.
+/// `\rprocessed 42/100 pairs, 12.3 pairs/s, ETA 4s   ` when `total` is known,
+/// or `\rprocessed 42 records, 12.3 records/s   ` when it isn't. Pure and
+/// injectable so it can be unit-tested without spawning threads or touching
+/// stderr.
+pub fn render_line(
+    w: &mut impl Write,
+    processed: u64,
+    total: Option<u64>,
+    unit: &str,
+    elapsed: Duration,
+) -> std::io::Result<()> {
+    let elapsed_s = elapsed.as_secs_f64();
+    let rate = processed as f64 / elapsed_s.max(1e-9);
+    match total {
+        Some(total) => {
+            let remaining = total.saturating_sub(processed);
+            let eta = if rate > 0.0 {
+                remaining as f64 / rate
+            } else {
+                f64::INFINITY
+            };
+            write!(
+                w,
+                "\rprocessed {}/{} {}, {:.1} {}/s, ETA {:.0}s   ",
+                processed, total, unit, rate, unit, eta
+            )
+        }
+        None => write!(
+            w,
+            "\rprocessed {} {}, {:.1} {}/s   ",
+            processed, unit, rate, unit
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_line_reports_rate_and_eta_with_a_known_total() {
+        let mut buf = Vec::new();
+        render_line(&mut buf, 50, Some(100), "pairs", Duration::from_secs(5)).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains("processed 50/100 pairs"));
+        assert!(text.contains("10.0 pairs/s"));
+        assert!(text.contains("ETA 5s"));
+    }
+
+    #[test]
+    fn render_line_omits_eta_without_a_known_total() {
+        let mut buf = Vec::new();
+        render_line(&mut buf, 7, None, "records", Duration::from_secs(1)).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains("processed 7 records"));
+        assert!(text.contains("7.0 records/s"));
+        assert!(!text.contains("ETA"));
+    }
+
+    #[test]
+    fn render_line_reports_infinite_eta_before_any_progress() {
+        let mut buf = Vec::new();
+        render_line(&mut buf, 0, Some(100), "pairs", Duration::from_secs(0)).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains("processed 0/100 pairs"));
+        assert!(text.contains("ETA inf"));
+    }
+
+    #[test]
+    fn inc_and_finish_do_not_panic_when_disabled() {
+        let reporter = ProgressReporter::spawn(Some(10), "records", false, Duration::from_millis(10));
+        reporter.inc(3);
+        reporter.finish();
+    }
+}
@@ -0,0 +1,125 @@
+//! Parser for the UCSC PSL alignment format, as produced by `blat` and read
+//! by the kent-tools chaining pipeline (`axtChain -psl`).
+
+use std::io::BufRead;
+
+/// One PSL record: a single gapped alignment between a query and a target
+/// sequence, described as a series of ungapped blocks.
+#[derive(Debug, Clone)]
+pub struct PslRecord {
+    pub matches: u64,
+    pub mismatches: u64,
+    pub rep_matches: u64,
+    pub n_count: u64,
+    pub q_num_insert: u64,
+    pub q_base_insert: u64,
+    pub t_num_insert: u64,
+    pub t_base_insert: u64,
+    pub strand: String,
+    pub q_name: String,
+    pub q_size: u64,
+    pub q_start: u64,
+    pub q_end: u64,
+    pub t_name: String,
+    pub t_size: u64,
+    pub t_start: u64,
+    pub t_end: u64,
+    pub block_count: usize,
+    pub block_sizes: Vec<u64>,
+    pub q_starts: Vec<u64>,
+    pub t_starts: Vec<u64>,
+}
+
+impl PslRecord {
+    /// Parses one tab-separated PSL data line (the standard 21 fields; the
+    /// optional protein-PSL 23-field variant is not supported).
+    pub fn parse(line: &str) -> anyhow::Result<Self> {
+        let fields: Vec<&str> = line.trim_end().split('\t').collect();
+        if fields.len() < 21 {
+            return Err(anyhow::anyhow!(
+                "malformed PSL line, expected 21 fields, got {}: {}",
+                fields.len(),
+                line
+            ));
+        }
+
+        let parse_list = |s: &str| -> anyhow::Result<Vec<u64>> {
+            s.trim_end_matches(',')
+                .split(',')
+                .filter(|f| !f.is_empty())
+                .map(|f| f.parse::<u64>().map_err(anyhow::Error::from))
+                .collect()
+        };
+
+        Ok(PslRecord {
+            matches: fields[0].parse()?,
+            mismatches: fields[1].parse()?,
+            rep_matches: fields[2].parse()?,
+            n_count: fields[3].parse()?,
+            q_num_insert: fields[4].parse()?,
+            q_base_insert: fields[5].parse()?,
+            t_num_insert: fields[6].parse()?,
+            t_base_insert: fields[7].parse()?,
+            strand: fields[8].to_string(),
+            q_name: fields[9].to_string(),
+            q_size: fields[10].parse()?,
+            q_start: fields[11].parse()?,
+            q_end: fields[12].parse()?,
+            t_name: fields[13].to_string(),
+            t_size: fields[14].parse()?,
+            t_start: fields[15].parse()?,
+            t_end: fields[16].parse()?,
+            block_count: fields[17].parse()?,
+            block_sizes: parse_list(fields[18])?,
+            q_starts: parse_list(fields[19])?,
+            t_starts: parse_list(fields[20])?,
+        })
+    }
+}
+
+/// Reads a `.psl` file, skipping the optional 5-line `pslLayout` header (and
+/// any blank lines) that `blat -out=psl` sometimes prepends.
+pub fn read_psl(infile: &str) -> anyhow::Result<Vec<PslRecord>> {
+    let reader = intspan::reader(infile);
+
+    let mut records = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty()
+            || trimmed.starts_with("psLayout")
+            || trimmed.starts_with('-')
+            || trimmed.starts_with("match")
+        {
+            continue;
+        }
+        records.push(PslRecord::parse(&line)?);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_two_block_psl_line() {
+        let line = "90\t0\t0\t0\t0\t0\t0\t10\t+\tquery1\t100\t0\t90\ttarget1\t200\t5\t105\t2\t40,50,\t0,40,\t5,55,";
+        let rec = PslRecord::parse(line).unwrap();
+
+        assert_eq!(rec.matches, 90);
+        assert_eq!(rec.strand, "+");
+        assert_eq!(rec.q_name, "query1");
+        assert_eq!(rec.t_name, "target1");
+        assert_eq!(rec.block_count, 2);
+        assert_eq!(rec.block_sizes, vec![40, 50]);
+        assert_eq!(rec.q_starts, vec![0, 40]);
+        assert_eq!(rec.t_starts, vec![5, 55]);
+    }
+
+    #[test]
+    fn rejects_a_line_with_too_few_fields() {
+        assert!(PslRecord::parse("90\t0\t0").is_err());
+    }
+}
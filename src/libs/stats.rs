@@ -0,0 +1,172 @@
+//! Single-pass FASTA statistics, shared by `size`, `count`, and `n50`.
+use super::nt::{is_lower, to_nt, Nt};
+use noodles_fasta as fasta;
+
+/// Accumulates per-record and total statistics over a stream of FASTA
+/// records in a single pass: raw length, base composition, N count, and
+/// soft-masked bp, plus the per-record length vector needed for Nx/Lx.
+///
+/// `merge()` combines the partial stats of independently processed shards,
+/// e.g. one `SeqStats` per rayon thread, so `finalize()` only needs to run once.
+#[derive(Debug, Default, Clone)]
+pub struct SeqStats {
+    pub record_cnt: usize,
+    /// Sum of raw sequence lengths, including any non-ACGTN characters.
+    pub total_len: usize,
+    /// Sum of lengths counting only valid (A/C/G/T/N) bases.
+    pub valid_len: usize,
+    /// Indexed by `Nt as usize`; covers A, C, G, T, N.
+    pub base_cnt: [usize; 5],
+    pub masked_cnt: usize,
+    /// Raw per-record lengths, for Nx/Lx/E-size computations.
+    pub lens: Vec<usize>,
+}
+
+impl SeqStats {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Folds one record's sequence into the running totals.
+    pub fn update(&mut self, record: &fasta::Record) {
+        let seq = record.sequence();
+        let len = seq.len();
+
+        let mut valid_len = 0usize;
+        let mut masked = 0usize;
+        for &el in seq.get(..).unwrap().iter() {
+            let nt = to_nt(el);
+            if !matches!(nt, Nt::Invalid) {
+                valid_len += 1;
+                self.base_cnt[nt as usize] += 1;
+            }
+            if is_lower(el) {
+                masked += 1;
+            }
+        }
+
+        self.record_cnt += 1;
+        self.total_len += len;
+        self.valid_len += valid_len;
+        self.masked_cnt += masked;
+        self.lens.push(len);
+    }
+
+    /// Combines another shard's stats into this one.
+    pub fn merge(&mut self, other: &SeqStats) {
+        self.record_cnt += other.record_cnt;
+        self.total_len += other.total_len;
+        self.valid_len += other.valid_len;
+        for i in 0..self.base_cnt.len() {
+            self.base_cnt[i] += other.base_cnt[i];
+        }
+        self.masked_cnt += other.masked_cnt;
+        self.lens.extend_from_slice(&other.lens);
+    }
+
+    /// Sorts the accumulated lengths longest-first, ready for Nx/Lx/E-size
+    /// computations. Call once, after all `update()`/`merge()` calls.
+    pub fn finalize(mut self) -> Self {
+        self.lens.sort_unstable_by(|a, b| b.cmp(a));
+        self
+    }
+
+    pub fn n_count(&self) -> usize {
+        self.base_cnt[Nt::N as usize]
+    }
+
+    /// The fraction of valid bases that are G or C.
+    pub fn gc(&self) -> f64 {
+        let gc = self.base_cnt[Nt::G as usize] + self.base_cnt[Nt::C as usize];
+        gc as f64 / self.valid_len as f64
+    }
+
+    pub fn average_len(&self) -> f64 {
+        self.total_len as f64 / self.record_cnt as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(name: &str, seq: &[u8]) -> fasta::Record {
+        fasta::Record::new(
+            fasta::record::Definition::new(name, None),
+            fasta::record::Sequence::from(seq.to_vec()),
+        )
+    }
+
+    #[test]
+    fn update_counts_bases_and_length() {
+        let mut stats = SeqStats::new();
+        stats.update(&record("s1", b"ACGTN"));
+
+        assert_eq!(stats.record_cnt, 1);
+        assert_eq!(stats.total_len, 5);
+        assert_eq!(stats.valid_len, 5);
+        assert_eq!(stats.base_cnt[Nt::A as usize], 1);
+        assert_eq!(stats.base_cnt[Nt::C as usize], 1);
+        assert_eq!(stats.base_cnt[Nt::G as usize], 1);
+        assert_eq!(stats.base_cnt[Nt::T as usize], 1);
+        assert_eq!(stats.n_count(), 1);
+        assert_eq!(stats.masked_cnt, 0);
+    }
+
+    #[test]
+    fn update_counts_soft_masked_bases() {
+        let mut stats = SeqStats::new();
+        stats.update(&record("s1", b"ACgtN"));
+
+        assert_eq!(stats.masked_cnt, 2);
+    }
+
+    #[test]
+    fn update_counts_invalid_bases_in_raw_length_but_not_valid_length() {
+        let mut stats = SeqStats::new();
+        stats.update(&record("s1", b"AC-G"));
+
+        // The dash is invalid: it still counts toward the raw length (as
+        // `size`/`n50` expect) but not toward valid_len/base_cnt/gc.
+        assert_eq!(stats.total_len, 4);
+        assert_eq!(stats.valid_len, 3);
+        assert_eq!((stats.gc() * 3.0).round() as usize, 2);
+    }
+
+    #[test]
+    fn merge_combines_two_shards() {
+        let mut a = SeqStats::new();
+        a.update(&record("s1", b"AACC"));
+
+        let mut b = SeqStats::new();
+        b.update(&record("s2", b"GGTT"));
+
+        a.merge(&b);
+
+        assert_eq!(a.record_cnt, 2);
+        assert_eq!(a.total_len, 8);
+        assert_eq!(a.base_cnt[Nt::A as usize], 2);
+        assert_eq!(a.base_cnt[Nt::G as usize], 2);
+        assert_eq!(a.lens, vec![4, 4]);
+    }
+
+    #[test]
+    fn finalize_sorts_lengths_longest_first() {
+        let mut stats = SeqStats::new();
+        stats.update(&record("short", b"AC"));
+        stats.update(&record("long", b"ACGTACGT"));
+
+        let stats = stats.finalize();
+
+        assert_eq!(stats.lens, vec![8, 2]);
+    }
+
+    #[test]
+    fn average_len_divides_total_by_record_count() {
+        let mut stats = SeqStats::new();
+        stats.update(&record("s1", b"AAAA"));
+        stats.update(&record("s2", b"AA"));
+
+        assert_eq!(stats.average_len(), 3.0);
+    }
+}
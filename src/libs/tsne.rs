@@ -0,0 +1,220 @@
+//! A plain (non-Barnes-Hut) implementation of
+//! [t-SNE](https://lvdmaaten.github.io/tsne/) for embedding a pairwise
+//! distance matrix into 2 or 3 dimensions. Intended for the small inputs
+//! typical of `hnsm manifold`; large inputs should use `pcoa` instead.
+use crate::ScoringMatrix;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+#[derive(Debug)]
+pub struct Tsne {
+    dims: usize,
+    perplexity: f64,
+    max_iter: usize,
+    seed: u64,
+}
+
+impl Tsne {
+    /// Creates a new t-SNE instance.
+    ///
+    /// # Parameters
+    ///
+    /// * `dims` - The number of output dimensions, usually 2 or 3.
+    /// * `perplexity` - Roughly the number of effective nearest neighbors
+    ///   considered for each point.
+    /// * `max_iter` - The number of gradient-descent iterations to run.
+    /// * `seed` - Seeds the random initial layout so runs are reproducible.
+    pub fn new(dims: usize, perplexity: f64, max_iter: usize, seed: u64) -> Self {
+        Tsne {
+            dims,
+            perplexity,
+            max_iter,
+            seed,
+        }
+    }
+
+    /// Embeds the points behind a pairwise distance matrix into `self.dims`
+    /// dimensions, returning one coordinate vector per point.
+    pub fn fit(&self, matrix: &ScoringMatrix<f64>) -> Vec<Vec<f64>> {
+        let n = matrix.size();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        if n == 0 {
+            return vec![];
+        }
+
+        let p = self.joint_probabilities(matrix, n);
+
+        // Random initial layout, small so early gradients are stable
+        let mut y: Vec<Vec<f64>> = (0..n)
+            .map(|_| (0..self.dims).map(|_| rng.gen_range(-1e-4..1e-4)).collect())
+            .collect();
+
+        let mut gains: Vec<Vec<f64>> = vec![vec![1.0; self.dims]; n];
+        let mut update = vec![vec![0.0; self.dims]; n];
+        let momentum_switch_iter = 250.min(self.max_iter / 2);
+
+        for iter in 0..self.max_iter {
+            let momentum = if iter < momentum_switch_iter { 0.5 } else { 0.8 };
+
+            // Low-dimensional affinities (Student-t kernel)
+            let mut num = vec![vec![0.0; n]; n];
+            let mut sum_num = 0.0;
+            for i in 0..n {
+                for j in 0..n {
+                    if i == j {
+                        continue;
+                    }
+                    let mut dist2 = 0.0;
+                    for d in 0..self.dims {
+                        let diff = y[i][d] - y[j][d];
+                        dist2 += diff * diff;
+                    }
+                    let v = 1.0 / (1.0 + dist2);
+                    num[i][j] = v;
+                    sum_num += v;
+                }
+            }
+
+            let mut grad = vec![vec![0.0; self.dims]; n];
+            for i in 0..n {
+                for j in 0..n {
+                    if i == j {
+                        continue;
+                    }
+                    let q = (num[i][j] / sum_num).max(1e-12);
+                    let mult = (p[i][j] - q) * num[i][j];
+                    for d in 0..self.dims {
+                        grad[i][d] += 4.0 * mult * (y[i][d] - y[j][d]);
+                    }
+                }
+            }
+
+            for i in 0..n {
+                for d in 0..self.dims {
+                    let sign_changed = (grad[i][d] > 0.0) != (update[i][d] > 0.0);
+                    if sign_changed {
+                        gains[i][d] += 0.2;
+                    } else {
+                        gains[i][d] *= 0.8;
+                    }
+                    gains[i][d] = gains[i][d].max(0.01);
+
+                    update[i][d] = momentum * update[i][d] - 0.2 * gains[i][d] * grad[i][d];
+                    y[i][d] += update[i][d];
+                }
+            }
+        }
+
+        y
+    }
+
+    /// Computes the symmetrized, perplexity-calibrated joint probability
+    /// matrix `p_ij` from the pairwise distances.
+    fn joint_probabilities(&self, matrix: &ScoringMatrix<f64>, n: usize) -> Vec<Vec<f64>> {
+        let target_entropy = self.perplexity.max(1.0).ln();
+        let mut p = vec![vec![0.0; n]; n];
+
+        for i in 0..n {
+            let dist2: Vec<f64> = (0..n).map(|j| matrix.get(i, j).powi(2)).collect();
+
+            let mut beta = 1.0_f64;
+            let (mut beta_min, mut beta_max) = (f64::NEG_INFINITY, f64::INFINITY);
+
+            for _ in 0..50 {
+                let mut row = vec![0.0; n];
+                let mut sum = 0.0;
+                for j in 0..n {
+                    if j == i {
+                        continue;
+                    }
+                    let v = (-beta * dist2[j]).exp();
+                    row[j] = v;
+                    sum += v;
+                }
+                if sum <= 0.0 {
+                    break;
+                }
+
+                let mut entropy = 0.0;
+                for j in 0..n {
+                    if j == i {
+                        continue;
+                    }
+                    let pj = row[j] / sum;
+                    if pj > 1e-12 {
+                        entropy -= pj * pj.ln();
+                    }
+                }
+
+                let diff = entropy - target_entropy;
+                if diff.abs() < 1e-5 {
+                    p[i] = row.iter().map(|v| v / sum).collect();
+                    break;
+                }
+
+                if diff > 0.0 {
+                    beta_min = beta;
+                    beta = if beta_max.is_infinite() {
+                        beta * 2.0
+                    } else {
+                        (beta + beta_max) / 2.0
+                    };
+                } else {
+                    beta_max = beta;
+                    beta = if beta_min.is_infinite() {
+                        beta / 2.0
+                    } else {
+                        (beta + beta_min) / 2.0
+                    };
+                }
+                p[i] = row.iter().map(|v| v / sum).collect();
+            }
+        }
+
+        // Symmetrize and normalize
+        let mut joint = vec![vec![0.0; n]; n];
+        let denom = 2.0 * n as f64;
+        for i in 0..n {
+            for j in 0..n {
+                joint[i][j] = ((p[i][j] + p[j][i]) / denom).max(1e-12);
+            }
+        }
+        joint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScoringMatrix;
+
+    #[test]
+    fn embeds_two_close_and_one_far_point() {
+        let mut matrix = ScoringMatrix::new(3, 0.0, 0.0);
+        matrix.set(0, 1, 0.1);
+        matrix.set(0, 2, 5.0);
+        matrix.set(1, 2, 5.0);
+
+        let tsne = Tsne::new(2, 1.0, 200, 42);
+        let y = tsne.fit(&matrix);
+
+        assert_eq!(y.len(), 3);
+        for point in &y {
+            assert_eq!(point.len(), 2);
+        }
+
+        let dist = |a: &[f64], b: &[f64]| {
+            a.iter()
+                .zip(b)
+                .map(|(x, y)| (x - y).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        };
+
+        // Points 0 and 1 started close together and should stay closer to
+        // each other than either is to the distant point 2.
+        assert!(dist(&y[0], &y[1]) < dist(&y[0], &y[2]));
+        assert!(dist(&y[0], &y[1]) < dist(&y[1], &y[2]));
+    }
+}
@@ -0,0 +1,291 @@
+//! A reader for UCSC `.2bit` files.
+//!
+//! <https://genome.ucsc.edu/FAQ/FAQformat.html#format7> describes the on-disk layout: a
+//! header, a per-sequence name/offset index, and then one packed record per sequence
+//! (dna size, N-blocks, soft-mask blocks, and the 2-bit-per-base packed sequence itself).
+
+use noodles_fasta as fasta;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+const SIGNATURE: u32 = 0x1A412743;
+
+/// Decodes the 2-bit packed base codes, in the order `.2bit` stores them.
+const BASES: [u8; 4] = [b'T', b'C', b'A', b'G'];
+
+struct SeqIndexEntry {
+    name: String,
+    offset: u32,
+}
+
+/// A random-access reader over a `.2bit` file's sequences.
+pub struct TwoBitReader {
+    file: File,
+    big_endian: bool,
+    index: Vec<SeqIndexEntry>,
+}
+
+impl TwoBitReader {
+    /// Returns whether `path` starts with a `.2bit` magic number, in either byte order.
+    pub fn is_twobit(path: &str) -> bool {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(_) => return false,
+        };
+        let mut buf = [0u8; 4];
+        if file.read_exact(&mut buf).is_err() {
+            return false;
+        }
+        u32::from_le_bytes(buf) == SIGNATURE || u32::from_be_bytes(buf) == SIGNATURE
+    }
+
+    pub fn open(path: &str) -> anyhow::Result<Self> {
+        let mut file = File::open(path)?;
+
+        let mut buf = [0u8; 4];
+        file.read_exact(&mut buf)?;
+        let big_endian = if u32::from_le_bytes(buf) == SIGNATURE {
+            false
+        } else if u32::from_be_bytes(buf) == SIGNATURE {
+            true
+        } else {
+            return Err(anyhow::anyhow!("`{}` is not a .2bit file", path));
+        };
+
+        let mut reader = TwoBitReader {
+            file,
+            big_endian,
+            index: vec![],
+        };
+
+        let _version = reader.read_u32()?;
+        let sequence_count = reader.read_u32()?;
+        let _reserved = reader.read_u32()?;
+
+        for _ in 0..sequence_count {
+            let mut name_size = [0u8; 1];
+            reader.file.read_exact(&mut name_size)?;
+            let mut name_buf = vec![0u8; name_size[0] as usize];
+            reader.file.read_exact(&mut name_buf)?;
+            let name = String::from_utf8(name_buf)?;
+            let offset = reader.read_u32()?;
+            reader.index.push(SeqIndexEntry { name, offset });
+        }
+
+        Ok(reader)
+    }
+
+    fn read_u32(&mut self) -> anyhow::Result<u32> {
+        let mut buf = [0u8; 4];
+        self.file.read_exact(&mut buf)?;
+        Ok(if self.big_endian {
+            u32::from_be_bytes(buf)
+        } else {
+            u32::from_le_bytes(buf)
+        })
+    }
+
+    /// Sequence names, in the order they appear in the file's index.
+    pub fn names(&self) -> Vec<String> {
+        self.index.iter().map(|e| e.name.clone()).collect()
+    }
+
+    fn offset_of(&self, name: &str) -> anyhow::Result<u32> {
+        self.index
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| e.offset)
+            .ok_or_else(|| anyhow::anyhow!("sequence `{}` not found in .2bit index", name))
+    }
+
+    fn read_blocks(&mut self) -> anyhow::Result<Vec<(u32, u32)>> {
+        let count = self.read_u32()?;
+        let starts: Vec<u32> = (0..count).map(|_| self.read_u32()).collect::<Result<_, _>>()?;
+        let sizes: Vec<u32> = (0..count).map(|_| self.read_u32()).collect::<Result<_, _>>()?;
+        Ok(starts.into_iter().zip(sizes).collect())
+    }
+
+    /// The ungapped base count of a sequence, i.e. its FASTA-equivalent length.
+    pub fn seq_size(&mut self, name: &str) -> anyhow::Result<u32> {
+        let offset = self.offset_of(name)?;
+        self.file.seek(SeekFrom::Start(offset as u64))?;
+        self.read_u32()
+    }
+
+    /// Extracts `[start, end)` (0-based, half-open) of `name`, restoring `N`-blocks and
+    /// soft-mask blocks (as lowercase).
+    pub fn sequence(&mut self, name: &str, start: u32, end: u32) -> anyhow::Result<Vec<u8>> {
+        let offset = self.offset_of(name)?;
+        self.file.seek(SeekFrom::Start(offset as u64))?;
+
+        let dna_size = self.read_u32()?;
+        let n_blocks = self.read_blocks()?;
+        let mask_blocks = self.read_blocks()?;
+        let _reserved = self.read_u32()?;
+        let packed_start = self.file.stream_position()?;
+
+        let end = end.min(dna_size);
+        if start >= end {
+            return Ok(vec![]);
+        }
+
+        let first_byte = start / 4;
+        let last_byte = (end - 1) / 4;
+        let mut packed = vec![0u8; (last_byte - first_byte + 1) as usize];
+        self.file
+            .seek(SeekFrom::Start(packed_start + first_byte as u64))?;
+        self.file.read_exact(&mut packed)?;
+
+        let mut seq = Vec::with_capacity((end - start) as usize);
+        for pos in start..end {
+            let byte = packed[(pos / 4 - first_byte) as usize];
+            let shift = 6 - 2 * (pos % 4);
+            let code = (byte >> shift) & 0x3;
+            seq.push(BASES[code as usize]);
+        }
+
+        for &(block_start, block_size) in &n_blocks {
+            let block_end = block_start + block_size;
+            for pos in start.max(block_start)..end.min(block_end) {
+                seq[(pos - start) as usize] = b'N';
+            }
+        }
+
+        for &(block_start, block_size) in &mask_blocks {
+            let block_end = block_start + block_size;
+            for pos in start.max(block_start)..end.min(block_end) {
+                seq[(pos - start) as usize] = seq[(pos - start) as usize].to_ascii_lowercase();
+            }
+        }
+
+        Ok(seq)
+    }
+
+    /// The full sequence of `name`, equivalent to `sequence(name, 0, seq_size(name))`.
+    pub fn full_sequence(&mut self, name: &str) -> anyhow::Result<Vec<u8>> {
+        let size = self.seq_size(name)?;
+        self.sequence(name, 0, size)
+    }
+
+    /// The full record of `name`, as a [`fasta::Record`].
+    pub fn record(&mut self, name: &str) -> anyhow::Result<fasta::Record> {
+        let seq = self.full_sequence(name)?;
+        Ok(fasta::Record::new(
+            fasta::record::Definition::new(name, None),
+            fasta::record::Sequence::from(seq),
+        ))
+    }
+}
+
+/// `base` -> 2-bit code, the inverse of [`BASES`]. Non-ACGT bases (`N` and
+/// IUPAC ambiguity codes) have no 2-bit encoding; `.2bit`'s N-blocks record
+/// their positions separately, so any placeholder code is fine here.
+fn base_code(base: u8) -> u8 {
+    match base.to_ascii_uppercase() {
+        b'T' => 0,
+        b'C' => 1,
+        b'A' => 2,
+        b'G' => 3,
+        _ => 0,
+    }
+}
+
+/// Runs of consecutive positions satisfying `pred`, as `(start, size)` pairs.
+fn runs_where(seq: &[u8], pred: impl Fn(u8) -> bool) -> Vec<(u32, u32)> {
+    let mut blocks = vec![];
+    let mut start: Option<usize> = None;
+
+    for (i, &b) in seq.iter().enumerate() {
+        if pred(b) {
+            start.get_or_insert(i);
+        } else if let Some(s) = start.take() {
+            blocks.push((s as u32, (i - s) as u32));
+        }
+    }
+    if let Some(s) = start {
+        blocks.push((s as u32, (seq.len() - s) as u32));
+    }
+
+    blocks
+}
+
+struct PackedSeq {
+    name: String,
+    bases: Vec<u8>,
+    n_blocks: Vec<(u32, u32)>,
+    mask_blocks: Vec<(u32, u32)>,
+}
+
+/// Writes `fasta_path` out as a `.2bit` file at `out_path`, a pure-Rust
+/// equivalent of kent's `faToTwoBit`. The on-disk layout is documented on
+/// [`TwoBitReader`], which this is the inverse of.
+pub fn write_two_bit(fasta_path: &str, out_path: &str) -> anyhow::Result<()> {
+    let reader = intspan::reader(fasta_path);
+    let mut fa_in = fasta::io::Reader::new(reader);
+
+    let mut seqs = vec![];
+    for result in fa_in.records() {
+        let record = result?;
+        let bases = record.sequence().get(..).unwrap().to_vec();
+        seqs.push(PackedSeq {
+            name: String::from_utf8(record.name().into())?,
+            n_blocks: runs_where(&bases, |b| b.to_ascii_uppercase() == b'N'),
+            mask_blocks: runs_where(&bases, |b| b.is_ascii_lowercase()),
+            bases,
+        });
+    }
+
+    let mut out = File::create(out_path)?;
+    out.write_all(&SIGNATURE.to_le_bytes())?;
+    out.write_all(&1u32.to_le_bytes())?; // version
+    out.write_all(&(seqs.len() as u32).to_le_bytes())?; // sequence count
+    out.write_all(&0u32.to_le_bytes())?; // reserved
+
+    // Index: 1-byte name length + name + 4-byte offset, per sequence.
+    let index_size: u32 = seqs.iter().map(|s| 1 + s.name.len() as u32 + 4).sum();
+    let mut offset = 16 + index_size;
+    for s in &seqs {
+        out.write_all(&[s.name.len() as u8])?;
+        out.write_all(s.name.as_bytes())?;
+        out.write_all(&offset.to_le_bytes())?;
+
+        let record_size = 4
+            + 4 + 8 * s.n_blocks.len() as u32
+            + 4 + 8 * s.mask_blocks.len() as u32
+            + 4
+            + s.bases.len().div_ceil(4) as u32;
+        offset += record_size;
+    }
+
+    for s in &seqs {
+        out.write_all(&(s.bases.len() as u32).to_le_bytes())?;
+
+        out.write_all(&(s.n_blocks.len() as u32).to_le_bytes())?;
+        for &(start, _) in &s.n_blocks {
+            out.write_all(&start.to_le_bytes())?;
+        }
+        for &(_, size) in &s.n_blocks {
+            out.write_all(&size.to_le_bytes())?;
+        }
+
+        out.write_all(&(s.mask_blocks.len() as u32).to_le_bytes())?;
+        for &(start, _) in &s.mask_blocks {
+            out.write_all(&start.to_le_bytes())?;
+        }
+        for &(_, size) in &s.mask_blocks {
+            out.write_all(&size.to_le_bytes())?;
+        }
+
+        out.write_all(&0u32.to_le_bytes())?; // reserved
+
+        for chunk in s.bases.chunks(4) {
+            let mut byte = 0u8;
+            for (i, &b) in chunk.iter().enumerate() {
+                byte |= base_code(b) << (6 - 2 * i);
+            }
+            out.write_all(&[byte])?;
+        }
+    }
+
+    Ok(())
+}
@@ -14,6 +14,7 @@ fn main() -> anyhow::Result<()> {
         .subcommand(cmd_pgr::chain::make_subcommand())
         .subcommand(cmd_pgr::ir::make_subcommand())
         .subcommand(cmd_pgr::rept::make_subcommand())
+        .subcommand(cmd_pgr::stat::make_subcommand())
         .subcommand(cmd_pgr::trf::make_subcommand())
         .after_help(
             r###"
@@ -25,6 +26,7 @@ Subcommand groups:
 * Genome alignments:
     * lastz
     * chain
+    * stat: per-chromosome coverage/identity from chain's axt/maf output
 
 * Repeats:
     * ir / rept / trf
@@ -37,6 +39,7 @@ Subcommand groups:
         Some(("chain", sub_matches)) => cmd_pgr::chain::execute(sub_matches),
         Some(("ir", sub_matches)) => cmd_pgr::ir::execute(sub_matches),
         Some(("rept", sub_matches)) => cmd_pgr::rept::execute(sub_matches),
+        Some(("stat", sub_matches)) => cmd_pgr::stat::execute(sub_matches),
         Some(("trf", sub_matches)) => cmd_pgr::trf::execute(sub_matches),
         _ => unreachable!(),
     }?;
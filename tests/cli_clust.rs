@@ -47,6 +47,172 @@ fn command_distance() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_distance_no_self() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("distance")
+        .arg("tests/clust/IBPA.fa")
+        .arg("tests/clust/IBPA.fa")
+        .arg("-k")
+        .arg("7")
+        .arg("-w")
+        .arg("1")
+        .arg("--no-self")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // 10x10 cross pairs, minus the 10 pairs where both names are equal
+    assert_eq!(stdout.lines().count(), 90);
+    assert!(!stdout.contains("IBPA_ECOLI\tIBPA_ECOLI\t"));
+
+    Ok(())
+}
+
+#[test]
+fn command_distance_seed_pattern_runs_and_replaces_kmer() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("distance")
+        .arg("tests/clust/IBPA.fa")
+        .arg("--seed-pattern")
+        .arg("1110111")
+        .arg("-w")
+        .arg("1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 100);
+    assert!(stdout.contains("IBPA_ECOLI\tIBPA_ECOLI\t1.0000"));
+
+    Ok(())
+}
+
+#[test]
+fn command_distance_seed_pattern_rejects_non_binary_mask() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    cmd.arg("distance")
+        .arg("tests/clust/IBPA.fa")
+        .arg("--seed-pattern")
+        .arg("11201")
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+#[test]
+fn command_distance_sampler_syncmer_runs() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("distance")
+        .arg("tests/clust/IBPA.fa")
+        .arg("--sampler")
+        .arg("syncmer")
+        .arg("-k")
+        .arg("16")
+        .arg("--syncmer-s")
+        .arg("5")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 100);
+    assert!(stdout.contains("IBPA_ECOLI\tIBPA_ECOLI\t1.0000"));
+
+    Ok(())
+}
+
+#[test]
+fn command_distance_sampler_syncmer_rejects_seed_pattern() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    cmd.arg("distance")
+        .arg("tests/clust/IBPA.fa")
+        .arg("--sampler")
+        .arg("syncmer")
+        .arg("--seed-pattern")
+        .arg("111")
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+#[test]
+fn command_hash() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("hash")
+        .arg("tests/clust/IBPA.fa")
+        .arg("-k")
+        .arg("7")
+        .arg("-w")
+        .arg("1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!stdout.is_empty());
+    let first = stdout.lines().next().unwrap();
+    let fields: Vec<&str> = first.split('\t').collect();
+    assert_eq!(fields.len(), 4);
+    assert_eq!(fields[2], "+");
+    assert!(stdout.contains("IBPA_ECOLI\t"));
+
+    Ok(())
+}
+
+#[test]
+fn command_distance_self_exclude_requires_two_infiles() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    cmd.arg("distance")
+        .arg("tests/clust/IBPA.fa")
+        .arg("-k")
+        .arg("7")
+        .arg("-w")
+        .arg("1")
+        .arg("--self-exclude")
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+#[test]
+fn command_distance_phylip_lower_relaxed() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("distance")
+        .arg("tests/clust/IBPA.fa")
+        .arg("-k")
+        .arg("7")
+        .arg("-w")
+        .arg("1")
+        .arg("--output-format")
+        .arg("phylip")
+        .arg("--lower")
+        .arg("--relaxed")
+        .arg("--precision")
+        .arg("2")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert_eq!(lines[0], "10");
+    // Relaxed names are followed by a single space, not padded to 10 columns
+    assert!(lines[1].starts_with("IBPA_ECOLI "));
+    // Lower triangle: row i has i distances, none for the first row
+    assert_eq!(lines[1].trim(), "IBPA_ECOLI");
+    assert_eq!(lines[2].split_whitespace().count(), 2);
+    // --precision 2 means two decimal places, not the default six
+    assert!(lines[2].split_whitespace().nth(1).unwrap().len() <= 4);
+
+    Ok(())
+}
+
 #[test]
 fn command_convert_matrix() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("hnsm")?;
@@ -84,6 +250,49 @@ fn command_convert_lower() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_convert_matrix_regex() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("convert")
+        .arg("tests/clust/IBPA.fa.tsv")
+        .arg("--mode")
+        .arg("matrix")
+        .arg("--regex")
+        .arg("^IBPA")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // Only the 5 `IBPA_*` names survive; every row has 5 columns
+    assert_eq!(stdout.lines().count(), 5);
+    for line in stdout.lines() {
+        assert_eq!(line.split('\t').count(), 6); // name + 5 columns
+        assert!(line.starts_with("IBPA"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn command_convert_matrix_min_dist() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("convert")
+        .arg("tests/clust/IBPA.fa.tsv")
+        .arg("--mode")
+        .arg("lower")
+        .arg("--min-dist")
+        .arg("0.5")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!stdout.contains("IBPA_ECOLI\t0.0669"));
+
+    Ok(())
+}
+
 #[test]
 fn command_convert_pair() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("hnsm")?;
@@ -124,3 +333,28 @@ fn command_cluster_dbscan() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn command_cluster_cc_dot() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let dot_path = tempdir.path().join("cc.dot");
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    cmd.arg("cluster")
+        .arg("tests/clust/IBPA.fa.tsv")
+        .arg("--mode")
+        .arg("cc")
+        .arg("--dot")
+        .arg(dot_path.to_str().unwrap())
+        .assert()
+        .success();
+
+    let dot = std::fs::read_to_string(&dot_path)?;
+    assert!(dot.starts_with("graph G {\n"));
+    assert!(dot.contains("IBPA_ECOLI"));
+    assert!(dot.contains(" -- "));
+    assert!(dot.trim_end().ends_with('}'));
+
+    tempdir.close()?;
+    Ok(())
+}
@@ -49,6 +49,45 @@ fn command_maf2fas() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_fas2maf_roundtrip() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let fas = cmd
+        .arg("maf2fas")
+        .arg("tests/fasr/example.maf")
+        .output()
+        .unwrap()
+        .stdout;
+
+    let tmp_dir = TempDir::new()?;
+    let fas_path = tmp_dir.path().join("example.fas");
+    std::fs::write(&fas_path, &fas)?;
+
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("fas2maf")
+        .arg("tests/fasr/example.maf.chr.sizes")
+        .arg(fas_path.to_str().unwrap())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(
+        stdout.contains("s S288c.VIII 13376 34 + 562643 TTACTCGTCTTGCGGCCAAAACTCGAAGAAAAAC"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(
+            "s Spar.gi_29362578 637 33 - 73522 TTACCCGTCTTGCGTCCAAAACTCGAA-AAAAAC"
+        ),
+        "negative strand coordinates round-trip: {}",
+        stdout
+    );
+
+    Ok(())
+}
+
 #[test]
 fn command_axt2fas() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("fasr")?;
@@ -426,6 +465,56 @@ fn command_split_to() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_split_by_block() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let tempdir_str = tempdir.path().to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("fasr")?;
+    cmd.arg("split")
+        .arg("tests/fasr/example.fas")
+        .arg("--by")
+        .arg("block")
+        .arg("-o")
+        .arg(tempdir_str)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    assert!(&tempdir.path().join("block_000001.fas").is_file());
+    assert!(&tempdir.path().join("index.tsv").is_file());
+
+    tempdir.close()?;
+    Ok(())
+}
+
+#[test]
+fn command_split_by_name() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let tempdir_str = tempdir.path().to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("fasr")?;
+    cmd.arg("split")
+        .arg("tests/fasr/example.fas")
+        .arg("--by")
+        .arg("name")
+        .arg("--fill")
+        .arg("-o")
+        .arg(tempdir_str)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    assert!(&tempdir.path().join("S288c.fas").is_file());
+    assert!(&tempdir.path().join("index.tsv").is_file());
+
+    let content = std::fs::read_to_string(tempdir.path().join("S288c.fas"))?;
+    assert!(content.contains(">S288c"));
+
+    tempdir.close()?;
+    Ok(())
+}
+
 #[test]
 fn command_consensus() -> anyhow::Result<()> {
     let mut bin = String::new();
@@ -470,6 +559,24 @@ fn command_consensus() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_consensus_majority() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("consensus")
+        .arg("tests/fasr/refine.fas")
+        .arg("--method")
+        .arg("majority")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 6);
+    assert!(stdout.contains(">consensus\n"), "simple name");
+
+    Ok(())
+}
+
 #[test]
 fn command_refine() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("fasr")?;
@@ -668,6 +775,27 @@ fn command_slice() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_slice_range() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("slice")
+        .arg("tests/fasr/slice.fas")
+        .arg("--range")
+        .arg("13301-13400")
+        .arg("--ref")
+        .arg("S288c")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 7);
+    assert!(stdout.contains("13301-13400"), "sliced S288c");
+    assert!(stdout.contains("\nTAGTCATCTCAG"), "sliced S288c seq");
+
+    Ok(())
+}
+
 #[test]
 fn command_stat() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("fasr")?;
@@ -696,6 +824,29 @@ fn command_stat() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_stat_diversity() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("stat")
+        .arg("tests/fasr/example.fas")
+        .arg("--diversity")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    // header + 3 blocks + a weighted total row
+    assert_eq!(lines.len(), 5);
+    assert_eq!(
+        lines[0],
+        "target\tlength\tcount\tcomparable\tsegregating\tpi\ttheta\tD"
+    );
+    assert!(lines.last().unwrap().starts_with("total\t"));
+
+    Ok(())
+}
+
 #[test]
 fn command_variation() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("fasr")?;
@@ -722,6 +873,121 @@ fn command_variation() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_conserve() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("conserve")
+        .arg("tests/fasr/example.fas")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 239);
+    assert!(stdout
+        .lines()
+        .next()
+        .unwrap()
+        .starts_with("#target\tchr\tchr_pos\tcolumn\tentropy"));
+    // The first block's four sequences are identical, so every column is
+    // fully conserved
+    assert!(stdout.contains("\t1\t0.0000"));
+
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("conserve")
+        .arg("tests/fasr/example.fas")
+        .arg("--window")
+        .arg("3")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 239);
+
+    Ok(())
+}
+
+#[test]
+fn command_snp() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("snp")
+        .arg("tests/fasr/example.fas")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 81);
+    assert!(stdout.lines().next().unwrap().starts_with("#pos\t"));
+
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("snp")
+        .arg("tests/fasr/example.fas")
+        .arg("--outgroup")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 49);
+    assert!(stdout.lines().next().unwrap().ends_with("\tobase"));
+
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("snp")
+        .arg("tests/fasr/example.fas")
+        .arg("--outgroup")
+        .arg("--outgroups")
+        .arg("2")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // Only 2 ingroup samples remain, so the header has #pos + 2 names + obase
+    assert_eq!(stdout.lines().next().unwrap().split('\t').count(), 4);
+    assert!(stdout.lines().next().unwrap().ends_with("\tobase"));
+
+    Ok(())
+}
+
+#[test]
+fn command_vcf() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("vcf")
+        .arg("tests/fasr/example.fas")
+        .arg("--ref")
+        .arg("S288c")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.starts_with("##fileformat=VCFv4.2\n"));
+    assert!(stdout.lines().nth(1).unwrap().starts_with("#CHROM\tPOS\tID\tREF\tALT"));
+    assert!(stdout.lines().count() > 2);
+
+    Ok(())
+}
+
+#[test]
+fn command_trim() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("trim")
+        .arg("tests/fasr/example.fas")
+        .arg("--max-gap")
+        .arg("0.5")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.matches('>').count(), 12);
+    assert!(stdout.contains("13267-13287"), "ungapped block unchanged");
+
+    Ok(())
+}
+
 #[test]
 fn command_xlsx() -> anyhow::Result<()> {
     let tempfile = NamedTempFile::new().unwrap().into_temp_path();
@@ -742,6 +1008,44 @@ fn command_xlsx() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_xlsx_outgroups() -> anyhow::Result<()> {
+    let tempfile = NamedTempFile::new().unwrap().into_temp_path();
+
+    let mut cmd = Command::cargo_bin("fasr")?;
+    cmd.arg("xlsx")
+        .arg("tests/fasr/example.fas")
+        .arg("--outgroup")
+        .arg("--outgroups")
+        .arg("2")
+        .arg(tempfile.to_str().unwrap())
+        .assert()
+        .success();
+
+    assert!(tempfile.is_file());
+
+    Ok(())
+}
+
+#[test]
+fn command_xlsx_summary() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let outfile = tempdir.path().join("out.xlsx");
+
+    let mut cmd = Command::cargo_bin("fasr")?;
+    cmd.arg("xlsx")
+        .arg("tests/fasr/example.fas")
+        .arg("--summary")
+        .arg("--outfile")
+        .arg(outfile.to_str().unwrap())
+        .assert()
+        .success();
+
+    assert!(outfile.is_file());
+
+    Ok(())
+}
+
 #[test]
 fn command_filter() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("fasr")?;
@@ -787,6 +1091,41 @@ fn command_filter() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_filter_codon() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("fasr")?;
+    let output = cmd
+        .arg("filter")
+        .arg("tests/fasr/example.fas")
+        .arg("--codon")
+        .arg("--mask-stops")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 27);
+
+    // Every sequence in a block is the same length, and that length is a
+    // multiple of 3
+    let mut block_len: Option<usize> = None;
+    for line in stdout.lines() {
+        if line.is_empty() {
+            block_len = None;
+            continue;
+        }
+        if line.starts_with('>') {
+            continue;
+        }
+        assert_eq!(line.len() % 3, 0, "not a multiple of 3: {}", line);
+        match block_len {
+            None => block_len = Some(line.len()),
+            Some(len) => assert_eq!(len, line.len(), "sequence lengths differ within a block"),
+        }
+    }
+
+    Ok(())
+}
+
 #[test]
 fn command_pl_p2m() -> anyhow::Result<()> {
     match which::which("clustaw") {
@@ -62,6 +62,48 @@ fn command_size() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_size_sort() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("size")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("--sort")
+        .arg("desc")
+        .arg("--top")
+        .arg("3")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let lens: Vec<i32> = stdout
+        .lines()
+        .map(|l| l.split('\t').nth(1).unwrap().parse::<i32>().unwrap())
+        .collect();
+
+    assert_eq!(lens.len(), 3);
+    assert!(lens.windows(2).all(|w| w[0] >= w[1]), "descending");
+
+    Ok(())
+}
+
+#[test]
+fn command_size_2bit() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("size")
+        .arg("tests/fasta/small.2bit")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(stdout.contains("seq1\t12"), "seq1");
+    assert!(stdout.contains("seq2\t8"), "seq2");
+
+    Ok(())
+}
+
 #[test]
 fn command_size_gz() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("hnsm")?;
@@ -112,122 +154,801 @@ fn command_some() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_some_exclude() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let exclude_file = tempdir.path().join("exclude.txt");
+    std::fs::write(&exclude_file, "read0\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("some")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/list.txt")
+        .arg("--exclude")
+        .arg(exclude_file.to_str().unwrap())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(!stdout.contains("read0\n"), "read0");
+    assert!(stdout.contains("read12\n"), "read12");
+
+    Ok(())
+}
+
+#[test]
+fn command_some_list_stdin() -> anyhow::Result<()> {
+    let file_output = Command::cargo_bin("hnsm")?
+        .arg("some")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/list.txt")
+        .output()
+        .unwrap();
+
+    let stdin_output = assert_cmd::Command::cargo_bin("hnsm")?
+        .arg("some")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("stdin")
+        .write_stdin("read12\n# a comment\n\nread0\n")
+        .output()
+        .unwrap();
+
+    assert_eq!(stdin_output.stdout, file_output.stdout);
+
+    let dash_output = assert_cmd::Command::cargo_bin("hnsm")?
+        .arg("some")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("-")
+        .write_stdin("read12\nread0\n")
+        .output()
+        .unwrap();
+
+    assert_eq!(dash_output.stdout, file_output.stdout);
+
+    Ok(())
+}
+
+#[test]
+fn command_some_rejects_double_stdin() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    cmd.arg("some").arg("stdin").arg("-");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot both read from stdin"));
+
+    Ok(())
+}
+
 #[test]
 fn command_order() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("hnsm")?;
     let output = cmd
         .arg("order")
         .arg("tests/fasta/ufasta.fa")
-        .arg("tests/fasta/list.txt")
+        .arg("tests/fasta/list.txt")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 4);
+    assert!(stdout.contains("read12\n"), "read12");
+    assert!(stdout.contains("read0\n"), "read0");
+
+    Ok(())
+}
+
+#[test]
+fn command_order_exclude() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let exclude_file = tempdir.path().join("exclude.txt");
+    std::fs::write(&exclude_file, "read0\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("order")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/list.txt")
+        .arg("--exclude")
+        .arg(exclude_file.to_str().unwrap())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(!stdout.contains("read0\n"), "read0");
+    assert!(stdout.contains("read12\n"), "read12");
+
+    Ok(())
+}
+
+#[test]
+fn command_order_list_stdin() -> anyhow::Result<()> {
+    let file_output = Command::cargo_bin("hnsm")?
+        .arg("order")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/list.txt")
+        .output()
+        .unwrap();
+
+    let stdin_output = assert_cmd::Command::cargo_bin("hnsm")?
+        .arg("order")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("stdin")
+        .write_stdin("read12\nread0\n")
+        .output()
+        .unwrap();
+
+    assert_eq!(stdin_output.stdout, file_output.stdout);
+
+    Ok(())
+}
+
+#[test]
+fn command_one() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("one")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("read12")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(stdout.contains("read12\n"), "read12");
+
+    Ok(())
+}
+
+#[test]
+fn command_one_output_format_raw_and_len() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("one")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("read12")
+        .arg("--output-format")
+        .arg("raw")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // No `>read12` header, just the sequence
+    assert!(!stdout.contains("read12"), "no header");
+    assert_eq!(stdout.lines().count(), 1);
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("one")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("read12")
+        .arg("--output-format")
+        .arg("len")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.trim(), "428");
+
+    Ok(())
+}
+
+#[test]
+fn command_one_2bit() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("one")
+        .arg("tests/fasta/small.2bit")
+        .arg("seq1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("seq1\n"), "seq1");
+    assert!(stdout.contains("ACGTNNTTGGCA"), "seq1 seq");
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("one")
+        .arg("tests/fasta/small.2bit")
+        .arg("seq2")
+        .arg("--range")
+        .arg("1-4")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("acgt"), "seq2 range");
+
+    Ok(())
+}
+
+#[test]
+fn command_masked() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("masked")
+        .arg("tests/fasta/ufasta.fa")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("read46:3-4"), "read46");
+
+    Ok(())
+}
+
+#[test]
+fn command_masked_window() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("masked")
+        .arg("tests/fasta/dust.fa")
+        .arg("--window")
+        .arg("20")
+        .arg("--step")
+        .arg("20")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // Uppercase sequences have no masked (N/lowercase) bases at all
+    assert!(stdout.contains("polyA\t0\t20\t0.0000"), "polyA window");
+    assert!(stdout.contains("varied\t0\t20\t0.0000"), "varied window");
+
+    Ok(())
+}
+
+#[test]
+fn command_gc() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("gc")
+        .arg("tests/fasta/gc.fa")
+        .arg("--window")
+        .arg("8")
+        .arg("--step")
+        .arg("8")
+        .arg("--skew")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("gc_high\t0\t8\t1.0000\t0.0000"), "gc_high");
+    assert!(stdout.contains("gc_low\t0\t8\t0.0000\tNA"), "gc_low, no G/C at all");
+    assert!(stdout.contains("gc_n\t8\t16\tNA\tNA"), "gc_n, too many Ns");
+
+    Ok(())
+}
+
+#[test]
+fn command_fa2tab() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("fa2tab")
+        .arg("tests/fasta/dust.fa")
+        .arg("--length")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(
+        stdout.contains("polyA\tAAAAAAAAAAAAAAAAAAAA\t20"),
+        "polyA with length"
+    );
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("fa2tab")
+        .arg("tests/fasta/dust.fa")
+        .arg("--hash")
+        .arg("md5")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    // A checksum replaces the sequence column entirely
+    assert_eq!(lines[0].split('\t').nth(1).unwrap().len(), 32);
+
+    Ok(())
+}
+
+#[test]
+fn command_mask() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("mask")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/mask.json")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("read0\ntcgtttaacccaaatcaagg"), "read0");
+    assert!(stdout.contains("read2\natagcaagct"), "read2");
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("mask")
+        .arg("--hard")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/mask.json")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("read0\nNNNNNNNNNNNNNNNNNNNN"), "read0");
+    assert!(stdout.contains("read2\nNNNNNNNNNN"), "read2");
+
+    Ok(())
+}
+
+#[test]
+fn command_mask_extract_round_trip() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("mask")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/mask.json")
+        .arg("--extract")
+        .arg("masked")
+        .output()
+        .unwrap();
+    let masked_stdout = String::from_utf8(output.stdout).unwrap();
+    let masked_lines: Vec<&str> = masked_stdout.lines().collect();
+    let idx = masked_lines
+        .iter()
+        .position(|l| *l == ">read0:1-20")
+        .unwrap();
+    let masked_seq = masked_lines[idx + 1];
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("mask")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/mask.json")
+        .arg("--extract")
+        .arg("unmasked")
+        .output()
+        .unwrap();
+    let unmasked_stdout = String::from_utf8(output.stdout).unwrap();
+    let unmasked_lines: Vec<&str> = unmasked_stdout.lines().collect();
+    let idx = unmasked_lines
+        .iter()
+        .position(|l| *l == ">read0:21-359")
+        .unwrap();
+    let unmasked_seq = unmasked_lines[idx + 1];
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("one")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("read0")
+        .arg("--output-format")
+        .arg("raw")
+        .output()
+        .unwrap();
+    let original = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(format!("{}{}\n", masked_seq, unmasked_seq), original);
+
+    Ok(())
+}
+
+#[test]
+fn command_mask_extract_min_len() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("mask")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/mask.json")
+        .arg("--extract")
+        .arg("masked")
+        .arg("--min-len")
+        .arg("100")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // Both masked runs (20bp and 10bp) fall below --min-len 100
+    assert!(!stdout.contains("read0:1-20"), "read0 dropped");
+    assert!(!stdout.contains("read2:1-10"), "read2 dropped");
+
+    Ok(())
+}
+
+#[test]
+fn command_dust() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("dust")
+        .arg("tests/fasta/dust.fa")
+        .arg("--window")
+        .arg("20")
+        .arg("--level")
+        .arg("4")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // A poly-A run and an (AT)n repeat are both soft-masked in full
+    assert!(stdout.contains("polyA\naaaaaaaaaaaaaaaaaaaa"), "polyA");
+    assert!(
+        stdout.contains("at_repeat\natatatatatatatatatat"),
+        "at_repeat"
+    );
+    // A non-repetitive sequence of the same length is left untouched
+    assert!(stdout.contains("varied\nCTGATCGTAGCATCGGATCA"), "varied");
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("dust")
+        .arg("tests/fasta/dust.fa")
+        .arg("--window")
+        .arg("20")
+        .arg("--level")
+        .arg("4")
+        .arg("--ranges")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("polyA:1-20"), "polyA range");
+    assert!(stdout.contains("at_repeat:1-20"), "at_repeat range");
+    assert!(!stdout.contains("varied:"), "varied is not masked");
+
+    Ok(())
+}
+
+#[test]
+fn command_repeats() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("repeats")
+        .arg("tests/fasta/repeats.fa")
+        .arg("--kmer")
+        .arg("20")
+        .arg("--min-len")
+        .arg("50")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // A direct repeat within seqA
+    assert!(
+        stdout.contains("seqA\t31\t111\tseqA\t151\t231\t81\tdirect"),
+        "direct repeat within seqA:\n{stdout}"
+    );
+    // The same repeat, inverted, shared between seqA and seqB
+    assert!(
+        stdout.contains("seqA\t31\t110\tseqB\t26\t105\t80\tinverted"),
+        "inverted repeat, first copy:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("seqA\t151\t230\tseqB\t26\t105\t80\tinverted"),
+        "inverted repeat, second copy:\n{stdout}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn command_rc() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd.arg("rc").arg("tests/fasta/ufasta.fa").output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("GgacTgcggCTagAA"), "read46");
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("rc")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/list.txt")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains(">RC_read12"), "read12");
+    assert!(!stdout.contains(">RC_read46"), "read46");
+    assert!(!stdout.contains("GgacTgcggCTagAA"), "read46");
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("rc")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/list.txt")
+        .arg("--invert")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!stdout.contains(">RC_read12"), "read12");
+    assert!(stdout.contains(">RC_read46"), "read46");
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("rc")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/list.txt")
+        .arg("--all")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains(">RC_read12"), "read12");
+    assert!(stdout.contains(">RC_read46"), "read46");
+
+    Ok(())
+}
+
+#[test]
+fn command_rc_list_stdin() -> anyhow::Result<()> {
+    let file_output = Command::cargo_bin("hnsm")?
+        .arg("rc")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("tests/fasta/list.txt")
+        .output()
+        .unwrap();
+
+    let stdin_output = assert_cmd::Command::cargo_bin("hnsm")?
+        .arg("rc")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("stdin")
+        .write_stdin("read12\nread0\n")
+        .output()
+        .unwrap();
+
+    assert_eq!(stdin_output.stdout, file_output.stdout);
+
+    Ok(())
+}
+
+#[test]
+fn command_rc_2bit() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("rc")
+        .arg("tests/fasta/small.2bit")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains(">RC_seq1"), "seq1");
+    assert!(stdout.contains("TGCCAANNACGT"), "seq1 rc");
+
+    Ok(())
+}
+
+#[test]
+fn command_rc_iupac() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let infile = tempdir.path().join("iupac.fa");
+    std::fs::write(&infile, ">seq1\nACGTMRWSYKVHDBN\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("rc")
+        .arg(infile.to_str().unwrap())
+        .arg("--iupac")
+        .arg("-c")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // reverse complement of ACGTMRWSYKVHDBN is NVHDBMRSWYKACGT
+    assert!(stdout.contains("NVHDBMRSWYKACGT"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn command_count() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("count")
+        .arg("tests/fasta/ufasta.fa")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("read45\t0\t0"), "empty");
+    assert!(stdout.contains("total\t9317\t2318"), "total");
+
+    Ok(())
+}
+
+#[test]
+fn command_count_gc_skew() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("count")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("--gc-skew")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().next().unwrap(), "#seq\tgc_skew\tat_skew");
+    assert!(stdout.lines().count() > 1);
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("count")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("--gc-skew")
+        .arg("--window")
+        .arg("100")
+        .arg("--cumulative")
         .output()
         .unwrap();
     let stdout = String::from_utf8(output.stdout).unwrap();
 
-    assert_eq!(stdout.lines().count(), 4);
-    assert!(stdout.contains("read12\n"), "read12");
-    assert!(stdout.contains("read0\n"), "read0");
+    assert_eq!(stdout.lines().next().unwrap(), "#seq\tpos\tgc_skew");
+    assert!(stdout.lines().count() > 1);
 
     Ok(())
 }
 
 #[test]
-fn command_one() -> anyhow::Result<()> {
+fn command_count_per_file() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("hnsm")?;
     let output = cmd
-        .arg("one")
+        .arg("count")
         .arg("tests/fasta/ufasta.fa")
-        .arg("read12")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("--per-file")
         .output()
         .unwrap();
     let stdout = String::from_utf8(output.stdout).unwrap();
 
-    assert_eq!(stdout.lines().count(), 2);
-    assert!(stdout.contains("read12\n"), "read12");
+    assert!(
+        stdout.contains("tests/fasta/ufasta.fa\t9317\t2318"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("total\t18634\t4636"), "{}", stdout);
 
     Ok(())
 }
 
 #[test]
-fn command_masked() -> anyhow::Result<()> {
+fn command_validate() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("hnsm")?;
-    let output = cmd
-        .arg("masked")
-        .arg("tests/fasta/ufasta.fa")
-        .output()
-        .unwrap();
-    let stdout = String::from_utf8(output.stdout).unwrap();
+    let assert = cmd
+        .arg("validate")
+        .arg("tests/fasta/validate.fa")
+        .assert()
+        .failure();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
 
-    assert!(stdout.contains("read46:3-4"), "read46");
+    assert!(stdout.contains("duplicate name"), "{}", stdout);
+    assert!(stdout.contains("empty sequence"), "{}", stdout);
+    assert!(stdout.contains("non-IUPAC character"), "{}", stdout);
+    assert!(stdout.contains("3 issue(s) found"), "{}", stdout);
 
     Ok(())
 }
 
 #[test]
-fn command_mask() -> anyhow::Result<()> {
+fn command_validate_warn_only() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("hnsm")?;
-    let output = cmd
-        .arg("mask")
-        .arg("tests/fasta/ufasta.fa")
-        .arg("tests/fasta/mask.json")
-        .output()
-        .unwrap();
-    let stdout = String::from_utf8(output.stdout).unwrap();
+    cmd.arg("validate")
+        .arg("tests/fasta/validate.fa")
+        .arg("--warn-only")
+        .assert()
+        .success();
 
-    assert!(stdout.contains("read0\ntcgtttaacccaaatcaagg"), "read0");
-    assert!(stdout.contains("read2\natagcaagct"), "read2");
+    Ok(())
+}
+
+#[test]
+fn command_hv_save_load_list() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let dir = tempdir.path().to_str().unwrap();
 
     let mut cmd = Command::cargo_bin("hnsm")?;
-    let output = cmd
-        .arg("mask")
-        .arg("--hard")
+    let assert = cmd
+        .arg("hv")
         .arg("tests/fasta/ufasta.fa")
-        .arg("tests/fasta/mask.json")
-        .output()
-        .unwrap();
-    let stdout = String::from_utf8(output.stdout).unwrap();
+        .arg("--save")
+        .arg(dir)
+        .assert()
+        .success();
+    let computed = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert!(computed.lines().count() > 0, "{}", computed);
 
-    assert!(stdout.contains("read0\nNNNNNNNNNNNNNNNNNNNN"), "read0");
-    assert!(stdout.contains("read2\nNNNNNNNNNN"), "read2");
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let assert = cmd.arg("hv").arg("--load").arg(dir).assert().success();
+    let loaded = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert_eq!(computed, loaded);
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let assert = cmd.arg("hv").arg("--list").arg(dir).assert().success();
+    let manifest = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert_eq!(manifest.lines().count(), computed.lines().count());
 
     Ok(())
 }
 
 #[test]
-fn command_rc() -> anyhow::Result<()> {
+fn command_hv_dim_test() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("hnsm")?;
-    let output = cmd.arg("rc").arg("tests/fasta/ufasta.fa").output().unwrap();
-    let stdout = String::from_utf8(output.stdout).unwrap();
+    let assert = cmd
+        .arg("hv")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("--dim-test")
+        .arg("8,4096")
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
 
-    assert!(stdout.contains("GgacTgcggCTagAA"), "read46");
+    let mut lines = stdout.lines();
+    assert_eq!(lines.next(), Some("dim\tcorr_with_4096\truntime_ms"));
 
-    let mut cmd = Command::cargo_bin("hnsm")?;
-    let output = cmd
-        .arg("rc")
-        .arg("tests/fasta/ufasta.fa")
-        .arg("tests/fasta/list.txt")
-        .output()
-        .unwrap();
-    let stdout = String::from_utf8(output.stdout).unwrap();
+    let fields = lines.next().unwrap().split('\t').collect::<Vec<_>>();
+    assert_eq!(fields[0], "8");
 
-    assert!(stdout.contains(">RC_read12"), "read12");
-    assert!(!stdout.contains(">RC_read46"), "read46");
-    assert!(!stdout.contains("GgacTgcggCTagAA"), "read46");
+    let last_fields = lines.last().unwrap().split('\t').collect::<Vec<_>>();
+    assert_eq!(last_fields[0], "4096");
+    // dimension 4096 is the ground truth, so it must correlate perfectly with itself
+    assert_eq!(last_fields[1], "1.0000");
 
     Ok(())
 }
 
 #[test]
-fn command_count() -> anyhow::Result<()> {
+fn command_expand() -> anyhow::Result<()> {
     let mut cmd = Command::cargo_bin("hnsm")?;
-    let output = cmd
-        .arg("count")
-        .arg("tests/fasta/ufasta.fa")
-        .output()
-        .unwrap();
-    let stdout = String::from_utf8(output.stdout).unwrap();
+    let assert = cmd
+        .arg("expand")
+        .arg("tests/fasta/expand.fa")
+        .arg("--max")
+        .arg("10")
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let stderr = String::from_utf8(assert.get_output().stderr.clone()).unwrap();
+
+    assert!(stdout.contains(">seq1_1"), "{}", stdout);
+    assert!(stdout.contains(">seq1_2"), "{}", stdout);
+    assert!(stdout.contains("ACAT"), "{}", stdout);
+    assert!(stdout.contains("ACGT"), "{}", stdout);
+    assert!(!stdout.contains("seq2"), "{}", stdout);
+    assert!(stderr.contains("seq2"), "{}", stderr);
+    assert!(stderr.contains("skipped"), "{}", stderr);
 
-    assert!(stdout.contains("read45\t0\t0"), "empty");
-    assert!(stdout.contains("total\t9317\t2318"), "total");
+    Ok(())
+}
+
+#[test]
+fn command_degap() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let assert = cmd
+        .arg("degap")
+        .arg("tests/fasta/degap.fa")
+        .arg("--dot")
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+
+    assert!(stdout.contains(">seq1\nACGTac\n"), "{}", stdout);
+    assert!(stdout.contains(">seq2\nACGT\n"), "{}", stdout);
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let assert = cmd
+        .arg("degap")
+        .arg("tests/fasta/degap.fa")
+        .arg("--dot")
+        .arg("--upper")
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+
+    assert!(stdout.contains(">seq1\nACGTAC\n"), "{}", stdout);
 
     Ok(())
 }
@@ -264,6 +985,35 @@ fn command_replace() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_rename() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let map_path = tempdir.path().join("name_map.tsv");
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("rename")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("--prefix")
+        .arg("SEQ")
+        .arg("--width")
+        .arg("3")
+        .arg("--map")
+        .arg(map_path.to_str().unwrap())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains(">SEQ001\n"), "sequential name");
+    assert!(!stdout.contains(">read0\n"), "old name replaced");
+
+    let map_content = std::fs::read_to_string(&map_path)?;
+    assert!(map_content.contains("read0\tSEQ001"), "name map");
+
+    tempdir.close()?;
+    Ok(())
+}
+
 // faops filter -l 0 -a 10 -z 50 tests/fasta/ufasta.fa stdout
 // faops filter -l 0 -a 1 -u <(cat tests/fasta/ufasta.fa tests/fasta/ufasta.fa) stdout
 #[test]
@@ -301,6 +1051,100 @@ fn command_filter() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_filter_sample() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("filter")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("--sample")
+        .arg("10")
+        .arg("--seed")
+        .arg("1")
+        .output()
+        .unwrap();
+    let stdout1 = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout1.lines().filter(|l| l.starts_with('>')).count(), 10);
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("filter")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("--sample")
+        .arg("10")
+        .arg("--seed")
+        .arg("1")
+        .output()
+        .unwrap();
+    let stdout2 = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout1, stdout2, "same seed should reproduce the same draw");
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("filter")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("--sample")
+        .arg("10")
+        .arg("--weighted-by-length")
+        .arg("--seed")
+        .arg("1")
+        .output()
+        .unwrap();
+    let stdout3 = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout3.lines().filter(|l| l.starts_with('>')).count(), 10);
+
+    Ok(())
+}
+
+#[test]
+fn command_filter_contained() -> anyhow::Result<()> {
+    // read2 is a substring of read1; read3 is unrelated
+    let fa = ">read1\nACGTACGTACGTACGTACGTACGT\n>read2\nACGTACGTACGT\n>read3\nTTTTGGGGCCCCAAAATTTTGGGG\n";
+
+    let output = assert_cmd::Command::cargo_bin("hnsm")?
+        .arg("filter")
+        .arg("stdin")
+        .arg("--contained")
+        .write_stdin(fa)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains(">read1"));
+    assert!(!stdout.contains(">read2"));
+    assert!(stdout.contains(">read3"));
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("removed 1 of 3"), "{}", stderr);
+
+    // read4 is the reverse complement of read2, only caught by --rc-contained
+    let fa_rc = ">read1\nACGTACGTACGTACGTACGTACGT\n>read4\nACGTACGTACGT\n";
+    let output = assert_cmd::Command::cargo_bin("hnsm")?
+        .arg("filter")
+        .arg("stdin")
+        .arg("--contained")
+        .write_stdin(fa_rc)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(">read4"), "plain --contained keeps read4");
+
+    let output = assert_cmd::Command::cargo_bin("hnsm")?
+        .arg("filter")
+        .arg("stdin")
+        .arg("--rc-contained")
+        .write_stdin(fa_rc)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains(">read4"), "--rc-contained removes read4");
+
+    Ok(())
+}
+
 #[test]
 fn command_filter_fmt() -> anyhow::Result<()> {
     // faops filter -N tests/fasta/filter.fa stdout
@@ -439,6 +1283,41 @@ fn command_dedup() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_dedup_cluster_out() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let cluster_file = tempdir.path().join("clusters.tsv");
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("dedup")
+        .arg("tests/fasta/dedup.fa")
+        .arg("--seq")
+        .arg("--cluster-out")
+        .arg(cluster_file.to_str().unwrap())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // `--seq` collapses the 6 input records into 3 kept records: only the
+    // "AGGG" sequence (read0 x3, read1) has duplicates.
+    let kept: usize = stdout.lines().filter(|l| l.starts_with('>')).count();
+    assert_eq!(kept, 3);
+
+    let cluster_tsv = std::fs::read_to_string(&cluster_file).unwrap();
+    let dup_lines: Vec<&str> = cluster_tsv.lines().collect();
+
+    // 4 records shared the "AGGG" sequence, so 3 of them are recorded as
+    // duplicates of the first-seen representative, "read0".
+    assert_eq!(dup_lines.len(), 3);
+    for line in &dup_lines {
+        assert!(line.starts_with("read0\t"));
+    }
+    assert!(stdout.contains(">read0"));
+
+    Ok(())
+}
+
 #[test]
 fn command_split_name() -> anyhow::Result<()> {
     let tempdir = TempDir::new().unwrap();
@@ -487,6 +1366,110 @@ fn command_split_about() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn command_split_about_digits_prefix_suffix() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let tempdir_str = tempdir.path().to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    cmd.arg("split")
+        .arg("about")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("-c")
+        .arg("2000")
+        .arg("--digits")
+        .arg("4")
+        .arg("--name-prefix")
+        .arg("chunk_")
+        .arg("--suffix")
+        .arg(".fasta")
+        .arg("-o")
+        .arg(tempdir_str)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    assert!(&tempdir.path().join("chunk_0000.fasta").is_file());
+    assert!(&tempdir.path().join("chunk_0004.fasta").is_file());
+    assert!(!&tempdir.path().join("000.fa").exists());
+
+    tempdir.close()?;
+    Ok(())
+}
+
+#[test]
+fn command_split_about_group_by_prefix() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let tempdir_str = tempdir.path().to_str().unwrap();
+    let infile = tempdir.path().join("genomes.fa");
+
+    // Two records per genome; a group-blind size-based split of `-c 60`
+    // would otherwise split genomeA across two files.
+    std::fs::write(
+        &infile,
+        ">genomeA.chr1\n\
+         AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA\n\
+         >genomeA.chr2\n\
+         CCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCC\n\
+         >genomeB.chr1\n\
+         GGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGG\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    cmd.arg("split")
+        .arg("about")
+        .arg(&infile)
+        .arg("-c")
+        .arg("60")
+        .arg("--group-by-prefix")
+        .arg(".")
+        .arg("-o")
+        .arg(tempdir_str)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let mut genome_a_file = None;
+    for entry in std::fs::read_dir(&tempdir)? {
+        let path = entry?.path();
+        let content = std::fs::read_to_string(&path)?;
+        if content.contains(">genomeA.chr1") {
+            assert!(
+                content.contains(">genomeA.chr2"),
+                "genomeA's records were split across files"
+            );
+            genome_a_file = Some(path);
+        }
+    }
+    assert!(genome_a_file.is_some(), "genomeA's file was not found");
+
+    tempdir.close()?;
+    Ok(())
+}
+
+#[test]
+fn command_split_name_gzip() -> anyhow::Result<()> {
+    let tempdir = TempDir::new().unwrap();
+    let tempdir_str = tempdir.path().to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    cmd.arg("split")
+        .arg("name")
+        .arg("tests/fasta/ufasta.fa")
+        .arg("--gzip")
+        .arg("-o")
+        .arg(tempdir_str)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    assert!(&tempdir.path().join("read0.fa.gz").is_file());
+    assert!(!&tempdir.path().join("read0.fa").exists());
+
+    tempdir.close()?;
+    Ok(())
+}
+
 #[test]
 fn command_n50() -> anyhow::Result<()> {
     // display header
@@ -584,3 +1567,20 @@ fn command_n50() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn command_maf2fa() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("maf2fa")
+        .arg("tests/fasr/example.maf")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().count(), 18);
+    assert!(stdout.contains("S288c.VIII"), "name list");
+    assert!(stdout.contains(":42072-42168"), "coordinate transformed");
+
+    Ok(())
+}
@@ -71,3 +71,93 @@ fn command_range_r() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn command_range_2bit() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("range")
+        .arg("tests/fasta/small.2bit")
+        .arg("seq1")
+        .arg("seq1:1-4")
+        .arg("seq1(-):1-8")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains(">seq1\nACGTNNTTGGCA\n"));
+    assert!(stdout.contains(">seq1:1-4\nACGT\n"));
+    assert!(stdout.contains(">seq1(-):1-8\nAANNACGT\n"));
+
+    Ok(())
+}
+
+#[test]
+fn command_range_flank() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("range")
+        .arg("tests/fasta/small.2bit")
+        .arg("seq1:5-6")
+        .arg("--flank")
+        .arg("2")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains(">seq1:3-8\nGTNNTT\n"));
+
+    // Clipped at the sequence's start
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("range")
+        .arg("tests/fasta/small.2bit")
+        .arg("seq1:1-2")
+        .arg("--flank")
+        .arg("5")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains(">seq1:1-7\nACGTNN\n"));
+
+    Ok(())
+}
+
+#[test]
+fn command_range_up_down() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("range")
+        .arg("tests/fasta/small.2bit")
+        .arg("seq1(-):1-4")
+        .arg("--up")
+        .arg("2")
+        .arg("--down")
+        .arg("1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains(">seq1(-):1-6\nNNACGT\n"));
+
+    Ok(())
+}
+
+#[test]
+fn command_range_name_template() -> anyhow::Result<()> {
+    let mut cmd = Command::cargo_bin("hnsm")?;
+    let output = cmd
+        .arg("range")
+        .arg("tests/fasta/small.2bit")
+        .arg("seq1(+):1-4")
+        .arg("--name-template")
+        .arg("{chr}_{start}_{end}_{strand}")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains(">seq1_1_4_+\nACGT\n"));
+
+    Ok(())
+}